@@ -0,0 +1,764 @@
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mockbanker_core::export::{CsvOptions, SqlOptions};
+use mockbanker_core::idsmith::{
+    company_id, credit_card, driver_license, lei, passport, personal_id, swift, tax_id, vat,
+};
+use mockbanker_core::{
+    CompanyGenerator, IbanRow, PersonGenerator, Record, Validator, anatomy, dataset, dates, export,
+    generate, nacha, negative, pseudonymize, sepa, snippet, statement, validate,
+};
+
+const USAGE: &str = "\
+Usage: mockbanker <command> [options]
+
+Generators:
+  iban             --country CC|Random   [--no-spaces]
+                   [--bank CODE] [--branch CODE]  fixed bank code / branch
+                   (e.g. a German BLZ or UK sort code), random account
+  personal-id      --country CC          [--gender male|female] [--year YYYY]
+  bank-account     --country CC
+  credit-card      --brand BRAND
+  swift            --country CC
+  company-id       --country CC
+  driver-license   --country CC          [--state XX]
+  passport         --country CC
+  tax-id           --country CC
+  vat              --country CC
+  lei              [--country CC]
+  company          --country CC
+  creditor-id      [--country CC]        SEPA Creditor Identifier
+  person           --country CC          [--gender male|female] [--year YYYY]
+
+Generator options:
+  --count N        Number of rows (default 5)
+  --format FMT     text, csv, json, sql, ndjson, xml, yaml, xlsx or parquet
+                   (default text), or a test fixture to paste: rust, pytest,
+                   junit or typescript
+  --output FILE    Write to FILE instead of stdout
+  --delimiter D    CSV delimiter: comma, semicolon or tab (default comma)
+  --no-header      CSV without the header row
+  --bom            Start CSV with a UTF-8 byte-order mark, for Excel
+  --crlf           CSV with CRLF line endings instead of LF
+  --dialect D      SQL for postgres, mysql, sqlite or sqlserver (default
+                   postgres)
+  --table NAME     SQL table name (default per type, e.g. ibans)
+  --batch N        Rows per SQL INSERT statement (default 1)
+  --load           Bulk-load instead of INSERT: COPY FROM STDIN for
+                   postgres, or LOAD DATA for mysql, which reads the rows
+                   from TABLE.tsv written next to --output
+  --seed SEED      Reproduce a previous run; number or any text (default
+                   random, printed to stderr). Same seed as the web app gives
+                   the same rows
+  --defects LIST   Near-miss data for negative tests (not for company or
+                   person): any, or a comma-separated list of check_digit,
+                   transposition, length, illegal_character, country_prefix,
+                   impossible_date. Every row is invalid and names its defect
+
+Datasets:
+  dataset          --schema FILE         [--format csv|json|sql]  (default csv)
+                   --count rows (at most 10000) with the columns of a schema
+                   saved from the web app's Dataset tab; CSV and SQL options
+                   apply, --table overrides the schema's table name
+
+Payment files (--count does not apply):
+  sepa-transfer    [--payments N] [--transactions N] [--currency EUR]
+                   [--min 1.00] [--max 1000.00] [--date YYYY-MM-DD]
+                   [--debtor-country CC] [--creditor-country CC]
+                   pain.001.001.09 credit transfer; --payments payment infos
+                   (default 1) of --transactions each (default 5); countries
+                   default to random SEPA countries, date to today
+  sepa-debit       [--payments N] [--transactions N] [--currency EUR]
+                   [--min 1.00] [--max 1000.00] [--date YYYY-MM-DD]
+                   [--scheme CORE|B2B] [--sequence FRST|RCUR|FNAL|OOFF]
+                   [--creditor-country CC] [--debtor-country CC]
+                   pain.008.001.08 direct debit, EUR only; one creditor and
+                   sequence type (random unless given) per payment info
+  statement        [--country CC] [--currency EUR] [--date YYYY-MM-DD]
+                   [--days N] [--transactions N] [--opening AMOUNT]
+                   [--format camt053|mt940]
+                   --days calendar days from --date (default 7, one
+                   statement per business day), up to --transactions
+                   bookings a day (default 5)
+  ach              [--batches N] [--entries N] [--sec PPD|CCD|WEB]
+                   [--service-class 200|220|225] [--min 1.00] [--max 1000.00]
+                   [--date YYYY-MM-DD]
+                   NACHA ACH file; --batches batches (default 1) of --entries
+                   entries each (default 5); SEC code random per batch unless
+                   given, --date is the effective entry date (default today)
+
+Validation:
+  validate <type> [--country CC] [VALUE...]
+                   Types: iban, id, bank, card, swift, company, driver_license,
+                   passport, tax_id, vat, lei, creditor_id, or detect to report
+                   the most specific type and country each value matches.
+                   Reads one value per line from stdin when no VALUE is given
+                   (the first field of CSV input). Exits 1 if any value is
+                   invalid.
+
+Pseudonymization:
+  pseudonymize --key KEY --columns NAME=TYPE[:CC],... [FILE]
+                   Replace the values in the named CSV columns of FILE (or
+                   stdin) with valid fake ones of the same type and country,
+                   laid out like the originals. TYPE is a validate type or
+                   detect; CC is the country of the column's values, read
+                   from each value when left out. The same KEY maps a value
+                   to the same fake one in every file. Values whose type or
+                   country can't be told are blanked, never written as they
+                   were; exits 1 if any was.
+
+Conversion:
+  convert IBAN...  Print the country, domestic bank code, account and
+                   national check digits behind each IBAN
+  convert --country CC --bank CODE ACCOUNT...
+                   Print the IBAN for each domestic account; CODE is the
+                   bank code, optionally followed by the branch code.
+                   National check digits left off ACCOUNT are computed
+                   where they can be
+";
+
+/// Parsed `--flag value` pairs plus positional arguments.
+struct Args {
+    flags: Vec<(String, Option<String>)>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    const SWITCHES: &'static [&'static str] =
+        &["no-spaces", "no-header", "bom", "crlf", "load", "help"];
+
+    fn parse(raw: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut flags = Vec::new();
+        let mut positional = Vec::new();
+        let mut raw = raw.peekable();
+        while let Some(arg) = raw.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if let Some((name, value)) = name.split_once('=') {
+                    flags.push((name.to_string(), Some(value.to_string())));
+                } else if Self::SWITCHES.contains(&name) {
+                    flags.push((name.to_string(), None));
+                } else {
+                    let value = raw
+                        .next()
+                        .ok_or_else(|| format!("--{} expects a value", name))?;
+                    flags.push((name.to_string(), Some(value)));
+                }
+            } else if arg == "-h" {
+                flags.push(("help".to_string(), None));
+            } else {
+                positional.push(arg);
+            }
+        }
+        Ok(Self { flags, positional })
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.flags.iter().any(|(n, _)| n == name)
+    }
+
+    fn count(&self) -> Result<u32, String> {
+        match self.value("count") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| format!("--count expects a number, got '{}'", v)),
+            None => Ok(5),
+        }
+    }
+
+    fn number(&self, name: &str, default: u32) -> Result<u32, String> {
+        match self.value(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| format!("--{} expects a number, got '{}'", name, v)),
+            None => Ok(default),
+        }
+    }
+
+    fn amount(&self, name: &str, default: u64) -> Result<u64, String> {
+        match self.value(name) {
+            Some(v) => sepa::parse_amount(v)
+                .ok_or_else(|| format!("--{} expects an amount like 12.50, got '{}'", name, v)),
+            None => Ok(default),
+        }
+    }
+
+    /// CSV layout from `--delimiter`, `--no-header`, `--bom` and `--crlf`.
+    fn csv(&self) -> Result<CsvOptions, String> {
+        Ok(CsvOptions {
+            delimiter: CsvOptions::delimiter_for(self.value("delimiter").unwrap_or("comma"))?,
+            header: !self.switch("no-header"),
+            bom: self.switch("bom"),
+            crlf: self.switch("crlf"),
+        })
+    }
+
+    /// SQL layout from `--dialect`, `--table`, `--batch` and `--load`.
+    fn sql(&self) -> Result<SqlOptions, String> {
+        let dialect = export::Dialect::parse(self.value("dialect").unwrap_or("postgres"))?;
+        if self.switch("load") && dialect.load_statement().is_none() {
+            return Err(format!(
+                "--load needs postgres (COPY) or mysql (LOAD DATA), not {}",
+                dialect.key()
+            ));
+        }
+        Ok(SqlOptions {
+            dialect,
+            table: self.value("table").unwrap_or("").to_string(),
+            batch: self.number("batch", 1)?,
+            load: self.switch("load"),
+        })
+    }
+
+    fn year(&self) -> Result<Option<u16>, String> {
+        match self.value("year") {
+            Some(y) => y
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("--year expects a number, got '{}'", y)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Current UTC date as days since 1970-01-01.
+fn today() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    (secs / 86_400) as i64
+}
+
+/// Last two digits of the current UTC year, as the Credit Card tab passes it.
+fn current_year() -> u16 {
+    (dates::civil_from_days(today()).0 % 100) as u16
+}
+
+/// How generated rows are written, from the generator options.
+struct Output<'a> {
+    format: &'a str,
+    seed: u64,
+    csv: CsvOptions,
+    sql: SqlOptions,
+    /// `--output`, next to which MySQL `LOAD DATA` rows are written.
+    file: Option<&'a str>,
+}
+
+/// Renders rows in the requested format using the same exporters as the web
+/// app's download buttons. `text` prints one code per line, like "Copy all".
+fn render<T: Record>(
+    rows: &[T],
+    out: &Output,
+    text: impl Fn(&T) -> String,
+) -> Result<Vec<u8>, String> {
+    match out.format {
+        "text" => Ok(rows
+            .iter()
+            .map(|r| text(r) + "\n")
+            .collect::<String>()
+            .into_bytes()),
+        "csv" => Ok(export::to_csv(rows, &out.csv).into_bytes()),
+        "json" => Ok(export::to_json(rows).into_bytes()),
+        "sql" => {
+            if out.sql.separate_data() {
+                let script = out
+                    .file
+                    .ok_or("MySQL LOAD DATA reads its rows from a separate file; add --output")?;
+                let path = Path::new(script).with_file_name(out.sql.data_file(T::TABLE));
+                std::fs::write(&path, export::load_data(rows))
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+            Ok(export::to_sql(rows, Some(out.seed), &out.sql).into_bytes())
+        }
+        other => match snippet::Language::parse(other) {
+            Ok(language) => Ok(snippet::to_snippet(rows, language, Some(out.seed)).into_bytes()),
+            Err(_) => export::to_bytes(rows, other),
+        },
+    }
+}
+
+fn render_ibans(rows: &[IbanRow], out: &Output, spaces: bool) -> Result<Vec<u8>, String> {
+    match out.format {
+        "csv" => Ok(export::ibans_csv(rows, spaces, &out.csv).into_bytes()),
+        _ => render(rows, out, |r| r.display(spaces).to_string()),
+    }
+}
+
+/// Builds a payment file, or `None` if `command` isn't one.
+fn run_payment_file(command: &str, args: &Args, seed: u64) -> Result<Option<String>, String> {
+    let mut rng = generate::seeded_rng(seed);
+    let swift = swift::Registry::new();
+    let country = |name: &str| args.value(name).map(str::to_string);
+    let date = args
+        .value("date")
+        .map_or_else(|| dates::format(today()), str::to_string);
+
+    let output = match command {
+        "sepa-transfer" => {
+            let defaults = sepa::CreditTransferOptions::default();
+            let opts = sepa::CreditTransferOptions {
+                payment_infos: args.number("payments", defaults.payment_infos)?,
+                transactions: args.number("transactions", defaults.transactions)?,
+                currency: args.value("currency").unwrap_or("EUR").to_uppercase(),
+                min_amount: args.amount("min", defaults.min_amount)?,
+                max_amount: args.amount("max", defaults.max_amount)?,
+                execution_date: date,
+                created_at: args.value("created").unwrap_or("").to_string(),
+                debtor_country: country("debtor-country"),
+                creditor_country: country("creditor-country"),
+            };
+            sepa::credit_transfer(&swift, &opts, &mut rng)?
+        }
+        "sepa-debit" => {
+            let defaults = sepa::DirectDebitOptions::default();
+            let opts = sepa::DirectDebitOptions {
+                payment_infos: args.number("payments", defaults.payment_infos)?,
+                transactions: args.number("transactions", defaults.transactions)?,
+                currency: args.value("currency").unwrap_or("EUR").to_uppercase(),
+                min_amount: args.amount("min", defaults.min_amount)?,
+                max_amount: args.amount("max", defaults.max_amount)?,
+                collection_date: date,
+                created_at: args.value("created").unwrap_or("").to_string(),
+                scheme: args.value("scheme").unwrap_or("CORE").to_uppercase(),
+                sequence_type: args.value("sequence").map(str::to_uppercase),
+                creditor_country: country("creditor-country"),
+                debtor_country: country("debtor-country"),
+            };
+            sepa::direct_debit(&swift, &opts, &mut rng)?
+        }
+        "statement" => {
+            let defaults = statement::StatementOptions::default();
+            let opening = match args.value("opening") {
+                Some(v) => sepa::parse_signed_amount(v).ok_or_else(|| {
+                    format!("--opening expects an amount like -12.50, got '{}'", v)
+                })?,
+                None => defaults.opening_balance,
+            };
+            let opts = statement::StatementOptions {
+                country: country("country"),
+                currency: args.value("currency").unwrap_or("EUR").to_uppercase(),
+                start_date: date,
+                days: args.number("days", defaults.days)?,
+                max_transactions: args.number("transactions", defaults.max_transactions)?,
+                opening_balance: opening,
+                created_at: args.value("created").unwrap_or("").to_string(),
+            };
+            let stmt = statement::generate(&swift, &opts, &mut rng)?;
+            match args.value("format").unwrap_or("camt053") {
+                "camt053" => stmt.camt053(),
+                "mt940" => stmt.mt940(),
+                other => return Err(format!("unknown statement format '{}'", other)),
+            }
+        }
+        "ach" => {
+            let defaults = nacha::AchOptions::default();
+            let sec = args.value("sec").map(str::to_uppercase);
+            if let Some(s) = &sec
+                && !nacha::SEC_CODES.iter().any(|(code, _)| code == s)
+            {
+                return Err(format!("unknown SEC code '{}'", s));
+            }
+            let class = args.number("service-class", defaults.service_class.into())?;
+            let service_class = nacha::SERVICE_CLASSES
+                .iter()
+                .map(|(code, _)| *code)
+                .find(|code| u32::from(*code) == class)
+                .ok_or_else(|| format!("unknown service class '{}'", class))?;
+            let opts = nacha::AchOptions {
+                batches: args.number("batches", defaults.batches)?,
+                entries: args.number("entries", defaults.entries)?,
+                sec_code: sec,
+                service_class,
+                min_amount: args.amount("min", defaults.min_amount)?,
+                max_amount: args.amount("max", defaults.max_amount)?,
+                effective_date: date,
+            };
+            nacha::ach_file(&opts, &mut rng).ok_or_else(|| {
+                format!(
+                    "could not build an ACH file for --date '{}' (expects YYYY-MM-DD)",
+                    opts.effective_date
+                )
+            })?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(output))
+}
+
+/// Generates `n` rows of the dataset described by the `--schema` file.
+fn run_dataset(
+    args: &Args,
+    out: &Output,
+    n: u32,
+    rng: &mut impl rand::Rng,
+) -> Result<Vec<u8>, String> {
+    let path = args
+        .value("schema")
+        .ok_or("dataset requires --schema FILE")?;
+    let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let dataset = dataset::Schema::from_json(&json)?.generate(n, current_year(), rng)?;
+    match args.value("format").unwrap_or("csv") {
+        "csv" => Ok(dataset.to_csv(&out.csv).into_bytes()),
+        "json" => Ok(dataset.to_json().into_bytes()),
+        "sql" => {
+            if out.sql.separate_data() {
+                let script = out
+                    .file
+                    .ok_or("MySQL LOAD DATA reads its rows from a separate file; add --output")?;
+                let path = Path::new(script).with_file_name(out.sql.data_file(&dataset.table));
+                std::fs::write(&path, dataset.load_data())
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+            Ok(dataset.to_sql(Some(out.seed), &out.sql).into_bytes())
+        }
+        other => Err(format!(
+            "unknown dataset format '{}', use csv, json or sql",
+            other
+        )),
+    }
+}
+
+fn run_generator(command: &str, args: &Args) -> Result<Vec<u8>, String> {
+    let seed = generate::resolve_seed(args.value("seed").unwrap_or(""));
+    if args.value("seed").is_none() {
+        eprintln!("seed: {}", seed);
+    }
+    if let Some(output) = run_payment_file(command, args, seed)? {
+        return Ok(output.into_bytes());
+    }
+    let mut rng = generate::seeded_rng(seed);
+    let n = args.count()?;
+    let out = Output {
+        format: args.value("format").unwrap_or("text"),
+        seed,
+        csv: args.csv()?,
+        sql: args.sql()?,
+        file: args.value("output"),
+    };
+    if command == "dataset" {
+        return run_dataset(args, &out, n, &mut rng);
+    }
+    let country = |default: &'static str| args.value("country").unwrap_or(default).to_string();
+    let defects = negative::parse(args.value("defects").unwrap_or(""))?;
+    if !defects.is_empty() && matches!(command, "company" | "person") {
+        return Err(format!("--defects is not supported for {}", command));
+    }
+
+    let (output, rows) = match command {
+        "iban" => {
+            let c = country("DE");
+            let c_opt = if c.eq_ignore_ascii_case("random") {
+                None
+            } else {
+                Some(c.as_str())
+            };
+            let bank = args.value("bank").unwrap_or("");
+            let branch = args.value("branch").unwrap_or("");
+            let rows = if bank.is_empty() && branch.is_empty() {
+                generate::ibans(c_opt, n, &mut rng)
+            } else {
+                let c = c_opt.ok_or("--bank and --branch need a --country")?;
+                generate::ibans_at_bank(c, bank, branch, n, &mut rng)?
+            };
+            let rows = negative::break_rows(rows, "", &defects, &mut rng)?;
+            (
+                render_ibans(&rows, &out, !args.switch("no-spaces"))?,
+                rows.len(),
+            )
+        }
+        "personal-id" => {
+            let year = args.year()?;
+            let reg = personal_id::Registry::new();
+            let c = country("EE");
+            let rows = generate::personal_ids(
+                &reg,
+                &c,
+                args.value("gender").unwrap_or("any"),
+                year,
+                n,
+                &mut rng,
+            );
+            let rows = negative::break_rows(rows, &c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "bank-account" => {
+            let c = country("US");
+            let rows = generate::bank_accounts(&c, n, &mut rng);
+            let rows = negative::break_rows(rows, &c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.display())?, rows.len())
+        }
+        "credit-card" => {
+            let reg = credit_card::Registry::new();
+            let brand = args.value("brand").unwrap_or("visa");
+            let rows = generate::credit_cards(&reg, brand, current_year(), n, &mut rng);
+            let rows = negative::break_rows(rows, "", &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.number.clone())?, rows.len())
+        }
+        "swift" => {
+            let reg = swift::Registry::new();
+            let rows = generate::swift_codes(&reg, &country("DE"), n, &mut rng);
+            let rows = negative::break_rows(rows, "", &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "company-id" => {
+            let reg = company_id::Registry::new();
+            let c = country("EE");
+            let rows = generate::company_ids(&reg, &c, n, &mut rng);
+            let rows = negative::break_rows(rows, &c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "driver-license" => {
+            let reg = driver_license::Registry::new();
+            let c = args
+                .value("country")
+                .ok_or("driver-license requires --country")?;
+            let rows = generate::driver_licenses(&reg, c, args.value("state"), n, &mut rng);
+            let rows = negative::break_rows(rows, c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "passport" => {
+            let reg = passport::Registry::new();
+            let c = args.value("country").ok_or("passport requires --country")?;
+            let rows = generate::passports(&reg, c, n, &mut rng);
+            let rows = negative::break_rows(rows, c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "tax-id" => {
+            let reg = tax_id::Registry::new();
+            let c = args.value("country").ok_or("tax-id requires --country")?;
+            let rows = generate::tax_ids(&reg, c, None, n, &mut rng);
+            let rows = negative::break_rows(rows, c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "vat" => {
+            let reg = vat::Registry::new();
+            let c = args.value("country").ok_or("vat requires --country")?;
+            let rows = generate::vat_numbers(&reg, c, n, &mut rng);
+            let rows = negative::break_rows(rows, c, &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "lei" => {
+            let reg = lei::Registry::new();
+            let rows = generate::leis(&reg, args.value("country"), n, &mut rng);
+            let rows = negative::break_rows(rows, "", &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "company" => {
+            let rows = CompanyGenerator::new().generate(&country("EE"), n, &mut rng);
+            (render(&rows, &out, |r| r.company_id.clone())?, rows.len())
+        }
+        "creditor-id" => {
+            let rows = generate::creditor_ids(args.value("country"), n, &mut rng);
+            let rows = negative::break_rows(rows, "", &defects, &mut rng)?;
+            (render(&rows, &out, |r| r.code.clone())?, rows.len())
+        }
+        "person" => {
+            let rows = PersonGenerator::new().generate(
+                &country("EE"),
+                args.value("gender").unwrap_or("any"),
+                args.year()?,
+                n,
+                &mut rng,
+            );
+            (render(&rows, &out, |r| r.personal_id.clone())?, rows.len())
+        }
+        other => return Err(format!("unknown command '{}'", other)),
+    };
+
+    if rows == 0 && n > 0 && !defects.is_empty() {
+        return Err(format!(
+            "no {} generated; none of the selected defects applies to it",
+            command
+        ));
+    }
+    if rows == 0 && n > 0 {
+        return Err(format!(
+            "no {} generated; check that the country or brand is supported",
+            command
+        ));
+    }
+    Ok(output)
+}
+
+/// Validates every value and returns the report plus whether all passed.
+fn run_validate(args: &Args) -> Result<(String, bool), String> {
+    let (kind, values) = args
+        .positional
+        .split_first()
+        .ok_or("validate requires a type")?;
+    if kind != validate::DETECT && !validate::KINDS.iter().any(|(k, _)| k == kind) {
+        return Err(format!("unknown validation type '{}'", kind));
+    }
+    let country = args.value("country").unwrap_or("");
+    if validate::needs_country(kind) && country.is_empty() {
+        return Err(format!("validating {} requires --country", kind));
+    }
+
+    let values: Vec<String> = if values.is_empty() {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| e.to_string())?;
+        validate::values(&input)
+    } else {
+        values.to_vec()
+    };
+
+    let rows = Validator::new().validate_all(kind, country, &values);
+    let mut report = String::new();
+    let mut all_valid = true;
+    for row in &rows {
+        all_valid &= row.valid;
+        report.push_str(&format!(
+            "{}\t{}\t{}\n",
+            if row.valid { "VALID" } else { "INVALID" },
+            row.value,
+            row.reason
+        ));
+    }
+    Ok((report, all_valid))
+}
+
+/// Pseudonymizes the CSV file given, or stdin, and returns it plus whether
+/// every marked value was replaced. Prints a count per column to stderr.
+fn run_pseudonymize(args: &Args) -> Result<(String, bool), String> {
+    let key = args.value("key").ok_or("pseudonymize requires --key")?;
+    let spec = args
+        .value("columns")
+        .ok_or("pseudonymize requires --columns NAME=TYPE[:CC],...")?;
+    let mut rules = Vec::new();
+    for column in spec.split(',').filter(|c| !c.trim().is_empty()) {
+        let (name, rule) = column
+            .rsplit_once('=')
+            .ok_or_else(|| format!("'{}' is not NAME=TYPE[:CC]", column))?;
+        let (kind, country) = rule.split_once(':').unwrap_or((rule, ""));
+        rules.push((
+            name.trim().to_string(),
+            pseudonymize::Rule {
+                kind: kind.trim().to_string(),
+                country: country.trim().to_uppercase(),
+            },
+        ));
+    }
+
+    let input = match args.positional.first() {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?,
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| e.to_string())?;
+            input
+        }
+    };
+    let result = pseudonymize::Pseudonymizer::new(key, current_year())?.csv(&input, &rules)?;
+    let mut all_replaced = true;
+    for column in &result.columns {
+        all_replaced &= column.blanked == 0;
+        eprintln!(
+            "{}: {} replaced, {} blanked",
+            column.name, column.replaced, column.blanked
+        );
+    }
+    Ok((result.csv, all_replaced))
+}
+
+/// Converts IBANs to domestic accounts, or domestic accounts at `--bank`
+/// to IBANs, one tab-separated line per value.
+fn run_convert(args: &Args) -> Result<String, String> {
+    if args.positional.is_empty() {
+        return Err("convert requires an IBAN or an account number".to_string());
+    }
+    let mut output = String::new();
+    match args.value("bank") {
+        Some(bank) => {
+            let country = args.value("country").ok_or("--bank needs a --country")?;
+            for account in &args.positional {
+                let iban = anatomy::iban_from_account(country, bank, account)
+                    .map_err(|e| format!("{}: {}", account, e))?;
+                output.push_str(&format!("{}\t{}\n", account, iban));
+            }
+        }
+        None => {
+            for iban in &args.positional {
+                let domestic =
+                    anatomy::account_from_iban(iban).map_err(|e| format!("{}: {}", iban, e))?;
+                output.push_str(&format!(
+                    "{}\t{}\t{}\t{}\t{}\n",
+                    iban,
+                    domestic.country,
+                    domestic.bank_code,
+                    domestic.account,
+                    domestic.national_check
+                ));
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn write_output(output: &[u8], path: Option<&str>) -> Result<(), String> {
+    match path {
+        Some(path) => std::fs::write(path, output).map_err(|e| format!("{}: {}", path, e)),
+        None => io::stdout().write_all(output).map_err(|e| e.to_string()),
+    }
+}
+
+fn run() -> Result<bool, String> {
+    let mut raw = std::env::args().skip(1);
+    let Some(command) = raw.next() else {
+        print!("{}", USAGE);
+        return Ok(true);
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        print!("{}", USAGE);
+        return Ok(true);
+    }
+    let args = Args::parse(raw)?;
+    if args.switch("help") {
+        print!("{}", USAGE);
+        return Ok(true);
+    }
+
+    if command == "validate" {
+        let (report, all_valid) = run_validate(&args)?;
+        write_output(report.as_bytes(), args.value("output"))?;
+        return Ok(all_valid);
+    }
+    if command == "pseudonymize" {
+        let (csv, all_replaced) = run_pseudonymize(&args)?;
+        write_output(csv.as_bytes(), args.value("output"))?;
+        return Ok(all_replaced);
+    }
+
+    let output = if command == "convert" {
+        run_convert(&args)?.into_bytes()
+    } else {
+        run_generator(&command, &args)?
+    };
+    write_output(&output, args.value("output"))?;
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("Run 'mockbanker --help' for usage.");
+            ExitCode::from(2)
+        }
+    }
+}
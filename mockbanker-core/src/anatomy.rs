@@ -0,0 +1,552 @@
+//! IBAN anatomy: the country code, check digits and the registry-defined
+//! parts of the BBAN (bank identifier, branch or sort code, account number
+//! and national check digits).
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnose;
+use crate::generate::mod97;
+
+/// BBAN layout per country from the SWIFT IBAN registry, one character per
+/// position: `b` bank identifier, `s` branch or sort code, `c` account
+/// number, `k` national check digits and `x` anything else (account type,
+/// currency, reserved digits). Countries not listed show the whole BBAN as
+/// the account number.
+pub const BBAN_LAYOUTS: &[(&str, &str)] = &[
+    ("AD", "bbbbsssscccccccccccc"),
+    ("AE", "bbbcccccccccccccccc"),
+    ("AL", "bbbsssskcccccccccccccccc"),
+    ("AT", "bbbbbccccccccccc"),
+    ("AZ", "bbbbcccccccccccccccccccc"),
+    ("BA", "bbbssscccccccckk"),
+    ("BE", "bbbccccccckk"),
+    ("BG", "bbbbssssxxcccccccc"),
+    ("BH", "bbbbcccccccccccccc"),
+    ("BR", "bbbbbbbbsssssccccccccccxx"),
+    ("BY", "bbbbxxxxcccccccccccccccc"),
+    ("CH", "bbbbbcccccccccccc"),
+    ("CR", "xbbbcccccccccccccc"),
+    ("CY", "bbbssssscccccccccccccccc"),
+    ("CZ", "bbbbcccccccccccccccc"),
+    ("DE", "bbbbbbbbcccccccccc"),
+    ("DK", "bbbbcccccccccc"),
+    ("DO", "bbbbcccccccccccccccccccc"),
+    ("EE", "bbssccccccccccck"),
+    ("EG", "bbbbssssccccccccccccccccc"),
+    ("ES", "bbbbsssskkcccccccccc"),
+    ("FI", "bbbcccccccccck"),
+    ("FO", "bbbbccccccccck"),
+    ("FR", "bbbbbsssssccccccccccckk"),
+    ("GB", "bbbbsssssscccccccc"),
+    ("GE", "bbcccccccccccccccc"),
+    ("GI", "bbbbccccccccccccccc"),
+    ("GL", "bbbbcccccccccc"),
+    ("GR", "bbbsssscccccccccccccccc"),
+    ("GT", "bbbbcccccccccccccccccccc"),
+    ("HR", "bbbbbbbcccccccccc"),
+    ("HU", "bbbsssskccccccccccccccck"),
+    ("IE", "bbbbsssssscccccccc"),
+    ("IL", "bbbsssccccccccccccc"),
+    ("IQ", "bbbbssscccccccccccc"),
+    ("IS", "bbssxxccccccxxxxxxxxxx"),
+    ("IT", "kbbbbbssssscccccccccccc"),
+    ("JO", "bbbbsssscccccccccccccccccc"),
+    ("KW", "bbbbcccccccccccccccccccccc"),
+    ("KZ", "bbbccccccccccccc"),
+    ("LB", "bbbbcccccccccccccccccccc"),
+    ("LC", "bbbbcccccccccccccccccccccccc"),
+    ("LI", "bbbbbcccccccccccc"),
+    ("LT", "bbbbbccccccccccc"),
+    ("LU", "bbbccccccccccccc"),
+    ("LV", "bbbbccccccccccccc"),
+    ("LY", "bbbsssccccccccccccccc"),
+    ("MC", "bbbbbsssssccccccccccckk"),
+    ("MD", "bbcccccccccccccccccc"),
+    ("ME", "bbbccccccccccccckk"),
+    ("MK", "bbbcccccccccckk"),
+    ("MR", "bbbbbsssssccccccccccckk"),
+    ("MT", "bbbbssssscccccccccccccccccc"),
+    ("MU", "bbbbbbssccccccccccccxxxxxx"),
+    ("NL", "bbbbcccccccccc"),
+    ("NO", "bbbbcccccck"),
+    ("PK", "bbbbcccccccccccccccc"),
+    ("PL", "bbbsssskcccccccccccccccc"),
+    ("PS", "bbbbccccccccccccccccccccc"),
+    ("PT", "bbbbssssccccccccccckk"),
+    ("QA", "bbbbccccccccccccccccccccc"),
+    ("RO", "bbbbcccccccccccccccc"),
+    ("RS", "bbbccccccccccccckk"),
+    ("RU", "bbbbbbbbbsssssccccccccccccccc"),
+    ("SA", "bbcccccccccccccccccc"),
+    ("SC", "bbbbbbssccccccccccccccccxxx"),
+    ("SE", "bbbccccccccccccccccc"),
+    ("SI", "bbssscccccccckk"),
+    ("SK", "bbbbcccccccccccccccc"),
+    ("SM", "kbbbbbssssscccccccccccc"),
+    ("ST", "bbbbssssccccccccccckk"),
+    ("SV", "bbbbcccccccccccccccccccc"),
+    ("TL", "bbbcccccccccccccckk"),
+    ("TN", "bbsssccccccccccccckk"),
+    ("TR", "bbbbbxcccccccccccccccc"),
+    ("UA", "bbbbbbccccccccccccccccccc"),
+    ("VA", "bbbccccccccccccccc"),
+    ("VG", "bbbbcccccccccccccccc"),
+    ("XK", "bbsscccccccccckk"),
+];
+
+/// What a run of IBAN characters is, for labels and colour-coding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Country,
+    CheckDigits,
+    Bank,
+    Branch,
+    Account,
+    NationalCheck,
+    Other,
+}
+
+impl Role {
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Country => "Country",
+            Role::CheckDigits => "Check digits",
+            Role::Bank => "Bank code",
+            Role::Branch => "Branch code",
+            Role::Account => "Account number",
+            Role::NationalCheck => "National check",
+            Role::Other => "Other",
+        }
+    }
+
+    /// CSS-friendly key, e.g. `national_check`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Role::Country => "country",
+            Role::CheckDigits => "check_digits",
+            Role::Bank => "bank",
+            Role::Branch => "branch",
+            Role::Account => "account",
+            Role::NationalCheck => "national_check",
+            Role::Other => "other",
+        }
+    }
+
+    fn from_layout(c: char) -> Self {
+        match c {
+            'b' => Role::Bank,
+            's' => Role::Branch,
+            'c' => Role::Account,
+            'k' => Role::NationalCheck,
+            _ => Role::Other,
+        }
+    }
+}
+
+/// The parts of an IBAN as separate fields, empty where the country's
+/// BBAN has no such part.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IbanParts {
+    pub country: String,
+    pub check_digits: String,
+    pub bank_code: String,
+    pub branch_code: String,
+    pub account_number: String,
+    pub national_check: String,
+}
+
+/// BBAN layout for `country`, if the registry table has one.
+pub fn layout(country: &str) -> Option<&'static str> {
+    BBAN_LAYOUTS
+        .iter()
+        .find(|(c, _)| *c == country)
+        .map(|(_, l)| *l)
+}
+
+/// Consecutive runs of `iban` (spaces ignored) by role, in order. A BBAN
+/// that doesn't fit its country's layout is shown as one account number.
+pub fn segments(iban: &str) -> Vec<(Role, String)> {
+    let chars: Vec<char> = iban
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if chars.len() < 4 {
+        return vec![(Role::Other, chars.into_iter().collect())];
+    }
+    let country: String = chars[..2].iter().collect();
+    let bban = &chars[4..];
+    let roles: Vec<Role> = match layout(&country) {
+        Some(l) if l.chars().count() == bban.len() => l.chars().map(Role::from_layout).collect(),
+        _ => vec![Role::Account; bban.len()],
+    };
+
+    let mut segments = vec![
+        (Role::Country, country),
+        (Role::CheckDigits, chars[2..4].iter().collect()),
+    ];
+    for (c, role) in bban.iter().zip(roles) {
+        match segments.last_mut() {
+            Some((last, text)) if *last == role => text.push(*c),
+            _ => segments.push((role, c.to_string())),
+        }
+    }
+    segments
+}
+
+/// Splits `iban` into [`IbanParts`]; runs of the same role are joined.
+pub fn parts(iban: &str) -> IbanParts {
+    let mut parts = IbanParts::default();
+    for (role, text) in segments(iban) {
+        let field = match role {
+            Role::Country => &mut parts.country,
+            Role::CheckDigits => &mut parts.check_digits,
+            Role::Bank => &mut parts.bank_code,
+            Role::Branch => &mut parts.branch_code,
+            Role::Account => &mut parts.account_number,
+            Role::NationalCheck => &mut parts.national_check,
+            Role::Other => continue,
+        };
+        field.push_str(&text);
+    }
+    parts
+}
+
+/// An IBAN in domestic form: the bank code as domestic payment forms ask
+/// for it (bank and branch identifiers together, with the sort code's own
+/// check digit in AL, HU and PL), the account, which is every other BBAN
+/// character in order, and the national check digits apart.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DomesticAccount {
+    pub country: String,
+    pub bank_code: String,
+    pub account: String,
+    pub national_check: String,
+}
+
+impl DomesticAccount {
+    /// The account as [`iban_from_account`] takes it back: national check
+    /// digits it can't compute, always the last characters, stay on.
+    pub fn written_account(&self) -> String {
+        match layout(&self.country) {
+            Some(l) if !computes_check(&self.country, l) => {
+                format!("{}{}", self.account, self.national_check)
+            }
+            _ => self.account.clone(),
+        }
+    }
+}
+
+/// `layout` as domestic forms split it: the lone check digit after the
+/// branch code (AL, HU, PL) checks the sort code and is written with it.
+fn domestic_layout(layout: &str) -> String {
+    layout.replacen("skc", "ssc", 1)
+}
+
+/// [`crate::diagnose`] computes the country's national check digits.
+fn computes_check(country: &str, layout: &str) -> bool {
+    layout.contains('k') && diagnose::national_check(country, &"0".repeat(layout.len())).is_some()
+}
+
+/// Uppercased `value` without the spaces, dashes, slashes and dots
+/// domestic account numbers are often written with.
+fn compact(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '/' | '.'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// The IBAN for a domestic account, e.g. from
+/// `bank_account::Registry::generate`. `bank_code` is the bank identifier,
+/// optionally followed by the branch code; whatever the bank code doesn't
+/// cover is filled from `account`, left-padded with zeros. Where
+/// [`crate::diagnose`] knows the national check digits, an account without
+/// them gets them computed, as [`crate::generate::ibans_at_bank`] does;
+/// elsewhere they are the account's last characters. An `account` that is
+/// already the whole BBAN, or carries its check digits, is kept as given, so
+/// an invalid domestic account gives an IBAN that fails the national check.
+pub fn iban_from_account(country: &str, bank_code: &str, account: &str) -> Result<String, String> {
+    let country = country.trim().to_ascii_uppercase();
+    let layout =
+        layout(&country).ok_or_else(|| format!("No BBAN layout is known for {} IBANs", country))?;
+    let computed = computes_check(&country, layout);
+    let layout = domestic_layout(layout);
+    let bank = compact(bank_code);
+    let account = compact(account);
+    for (label, value) in [("Bank code", &bank), ("Account", &account)] {
+        if let Some(c) = value.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(format!(
+                "{} has '{}', which an IBAN can't contain",
+                label, c
+            ));
+        }
+    }
+
+    let bank_len = layout.chars().filter(|&c| c == 'b').count();
+    let branch_len = layout.chars().filter(|&c| c == 's').count();
+    let bban = if account.len() == layout.len() && account.starts_with(&bank) {
+        account
+    } else {
+        let with_branch = match bank.len() {
+            n if n == bank_len + branch_len => true,
+            n if n == bank_len => false,
+            n if branch_len == 0 => {
+                return Err(format!(
+                    "{} bank codes are {} characters, got {}",
+                    country, bank_len, n
+                ));
+            }
+            n => {
+                return Err(format!(
+                    "{} bank codes are {} characters, or {} with the branch code, got {}",
+                    country,
+                    bank_len,
+                    bank_len + branch_len,
+                    n
+                ));
+            }
+        };
+        let in_bank = |role: char| role == 'b' || (with_branch && role == 's');
+        let rest = layout.chars().filter(|&c| !in_bank(c)).count();
+        let checks = layout.chars().filter(|&c| c == 'k').count();
+        if account.len() > rest {
+            return Err(format!(
+                "{} accounts are at most {} characters after that bank code, got {}",
+                country,
+                rest,
+                account.len()
+            ));
+        }
+        let compute = computed && account.len() <= rest - checks;
+        let width = if compute { rest - checks } else { rest };
+        let mut bank = bank.chars();
+        let padded = format!("{:0>width$}", account, width = width);
+        let mut account = padded.chars();
+        let bban: String = layout
+            .chars()
+            .map(|role| {
+                if in_bank(role) {
+                    bank.next()
+                } else if compute && role == 'k' {
+                    None
+                } else {
+                    account.next()
+                }
+                .unwrap_or('0')
+            })
+            .collect();
+        match diagnose::national_check(&country, &bban) {
+            Some((_, expected)) if compute => {
+                if !expected.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(format!(
+                        "No {} account number can have those digits: no national check fits",
+                        country
+                    ));
+                }
+                let mut expected = expected.chars();
+                layout
+                    .chars()
+                    .zip(bban.chars())
+                    .map(|(role, c)| match role {
+                        'k' => expected.next().unwrap_or(c),
+                        _ => c,
+                    })
+                    .collect()
+            }
+            _ => bban,
+        }
+    };
+
+    let check = 98 - mod97(&format!("{}{}00", bban, country));
+    Ok(format!("{}{:02}{}", country, check, bban))
+}
+
+/// The domestic bank code, account and national check digits behind
+/// `iban`; the inverse of [`iban_from_account`]. Errs if the IBAN's length
+/// or check digits are wrong, or no layout is known for its country.
+pub fn account_from_iban(iban: &str) -> Result<DomesticAccount, String> {
+    let iban: String = iban
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if iban.len() < 5 || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Not an IBAN".to_string());
+    }
+    let (country, rest) = iban.split_at(2);
+    let (check, bban) = rest.split_at(2);
+    let layout =
+        layout(country).ok_or_else(|| format!("No BBAN layout is known for {} IBANs", country))?;
+    if bban.len() != layout.len() {
+        return Err(format!(
+            "{} IBANs are {} characters, got {}",
+            country,
+            layout.len() + 4,
+            iban.len()
+        ));
+    }
+    if mod97(&format!("{}{}{}", bban, country, check)) != 1 {
+        let expected = 98 - mod97(&format!("{}{}00", bban, country));
+        return Err(format!(
+            "Check digits are {}, expected {:02}",
+            check, expected
+        ));
+    }
+
+    let mut domestic = DomesticAccount {
+        country: country.to_string(),
+        ..Default::default()
+    };
+    for (role, c) in domestic_layout(layout).chars().zip(bban.chars()) {
+        match role {
+            'b' | 's' => domestic.bank_code.push(c),
+            'k' => domestic.national_check.push(c),
+            _ => domestic.account.push(c),
+        }
+    }
+    Ok(domestic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_follow_the_registry_layout() {
+        assert_eq!(
+            segments("GB29 NWBK 6016 1331 9268 19"),
+            vec![
+                (Role::Country, "GB".to_string()),
+                (Role::CheckDigits, "29".to_string()),
+                (Role::Bank, "NWBK".to_string()),
+                (Role::Branch, "601613".to_string()),
+                (Role::Account, "31926819".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parts_join_runs_of_one_role() {
+        let parts = parts("it60x0542811101000000123456");
+        assert_eq!(parts.country, "IT");
+        assert_eq!(parts.check_digits, "60");
+        assert_eq!(parts.national_check, "X");
+        assert_eq!(parts.bank_code, "05428");
+        assert_eq!(parts.branch_code, "11101");
+        assert_eq!(parts.account_number, "000000123456");
+    }
+
+    #[test]
+    fn unknown_layouts_show_one_account_number() {
+        // Too short for the German layout.
+        let short = segments("DE89370400440532013");
+        assert_eq!(short[2], (Role::Account, "370400440532013".to_string()));
+        assert_eq!(short.len(), 3);
+        assert_eq!(segments("DE"), vec![(Role::Other, "DE".to_string())]);
+    }
+
+    #[test]
+    fn national_check_digits_are_computed() {
+        for (country, bank, account, expected) in [
+            (
+                "IT",
+                "0542811101",
+                "000000123456",
+                "IT60X0542811101000000123456",
+            ),
+            (
+                "FR",
+                "2004101005",
+                "0500013M026",
+                "FR1420041010050500013M02606",
+            ),
+            (
+                "FR",
+                "3000600001",
+                "12345678901",
+                "FR7630006000011234567890189",
+            ),
+            ("ES", "21000418", "0200051332", "ES9121000418450200051332"),
+            ("BE", "539", "0075470", "BE68539007547034"),
+            ("NO", "8601", "111794", "NO9386011117947"),
+            ("PT", "00020123", "12345678901", "PT50000201231234567890154"),
+        ] {
+            assert_eq!(iban_from_account(country, bank, account).unwrap(), expected);
+        }
+        let mut rng = crate::generate::seeded_rng(2);
+        for country in ["FR", "ES", "BE", "NO", "FI", "PT", "IT", "MC", "SM"] {
+            for _ in 0..20 {
+                let code = idsmith::iban::generate_iban(Some(country), &mut rng).unwrap();
+                let domestic = account_from_iban(&code).unwrap();
+                let iban =
+                    iban_from_account(country, &domestic.bank_code, &domestic.account).unwrap();
+                let failed: Vec<_> = crate::diagnose::iban(&iban)
+                    .into_iter()
+                    .filter(|check| !check.passed)
+                    .collect();
+                assert!(failed.is_empty(), "{iban}: {failed:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn check_digits_given_with_the_account_are_kept() {
+        // 35 is not the account's check, 34 is.
+        let iban = iban_from_account("BE", "539", "007547035").unwrap();
+        assert_eq!(&iban[4..], "539007547035");
+        assert_eq!(iban_from_account("BE", "", "539007547035").unwrap(), iban);
+    }
+
+    #[test]
+    fn domestic_accounts_split_out_the_national_check() {
+        let domestic = account_from_iban("FR14 2004 1010 0505 0001 3M02 606").unwrap();
+        assert_eq!(domestic.bank_code, "2004101005");
+        assert_eq!(domestic.account, "0500013M026");
+        assert_eq!(domestic.national_check, "06");
+        assert_eq!(domestic.written_account(), "0500013M026");
+
+        // The sort code's check digit stays with the sort code.
+        let domestic = account_from_iban("PL61109010140000071219812874").unwrap();
+        assert_eq!(domestic.bank_code, "10901014");
+        assert_eq!(domestic.account, "0000071219812874");
+        assert!(domestic.national_check.is_empty());
+
+        // No computed check: the digits go back with the account.
+        let domestic = account_from_iban("SI56263300012039086").unwrap();
+        assert_eq!(domestic.national_check, "86");
+        assert_eq!(domestic.written_account(), "0012039086");
+    }
+
+    #[test]
+    fn domestic_accounts_round_trip() {
+        let mut rng = crate::generate::seeded_rng(3);
+        for (country, _) in BBAN_LAYOUTS {
+            let Ok(code) = idsmith::iban::generate_iban(Some(country), &mut rng) else {
+                continue;
+            };
+            let domestic = account_from_iban(&code).unwrap();
+            let back = iban_from_account(country, &domestic.bank_code, &domestic.written_account());
+            // Generated IBANs don't always carry a valid national check.
+            if crate::diagnose::national_check(country, &code[4..])
+                .is_none_or(|(found, expected)| found == expected)
+            {
+                assert_eq!(back.unwrap(), code, "{country}");
+            }
+        }
+        assert!(iban_from_account("PL", "1090101", "1").is_err());
+        assert!(account_from_iban("DE89370400440532013001").is_err());
+    }
+
+    #[test]
+    fn layouts_match_generated_ibans() {
+        let mut rng = crate::generate::seeded_rng(1);
+        for (country, layout) in BBAN_LAYOUTS {
+            if let Ok(code) = idsmith::iban::generate_iban(Some(country), &mut rng) {
+                assert_eq!(code.len() - 4, layout.len(), "{country}");
+            }
+        }
+    }
+}
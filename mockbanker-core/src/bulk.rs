@@ -0,0 +1,342 @@
+//! Large generation runs for the web app's background worker.
+//!
+//! Rows are generated a chunk at a time from one seeded RNG and rendered
+//! straight into export text, so 100k+ rows never sit in memory together.
+//! Each generator draws its rows one after another, so the rows match a
+//! single run of the same size with the same seed; near-miss runs break
+//! each chunk as it comes and draw differently.
+
+use idsmith::{
+    company_id, credit_card, driver_license, lei, passport, personal_id, swift, tax_id, vat,
+};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::export::{self, CsvOptions, Record, SqlOptions, Stream};
+use crate::{CompanyGenerator, PersonGenerator, generate, negative};
+
+/// Rows generated and rendered per chunk.
+pub const CHUNK: u32 = 1000;
+
+/// Formats a run can write chunk by chunk.
+pub const FORMATS: [&str; 6] = ["csv", "json", "sql", "ndjson", "xml", "yaml"];
+
+/// What to generate: the tab and its options as the form holds them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Job {
+    /// Web app tab key, e.g. `iban`, `id` or `entity`.
+    pub tab: String,
+    /// `Random` or empty where the tab allows any country.
+    pub country: String,
+    pub count: u32,
+    pub seed: u64,
+    /// One of [`FORMATS`].
+    pub format: String,
+    /// Near-miss defects, see [`negative::parse`].
+    pub defects: String,
+    pub gender: String,
+    pub year: Option<u16>,
+    pub brand: String,
+    /// Two-digit year for card expiry dates.
+    pub current_year: u16,
+    pub state: String,
+    pub bank: String,
+    pub branch: String,
+    /// IBANs grouped in blocks of four in CSV output.
+    pub spaces: bool,
+    #[serde(default)]
+    pub csv: CsvOptions,
+    #[serde(default)]
+    pub sql: SqlOptions,
+}
+
+/// A [`Job`] in progress.
+pub struct Run {
+    job: Job,
+    defects: Vec<negative::Defect>,
+    rng: StdRng,
+    done: u32,
+    table: &'static str,
+    stream: Stream,
+}
+
+impl Run {
+    pub fn new(job: Job) -> Result<Self, String> {
+        let defects = negative::parse(&job.defects)?;
+        if !FORMATS.contains(&job.format.as_str()) {
+            return Err(format!(
+                "'{}' files can't be written chunk by chunk; use {}",
+                job.format,
+                FORMATS.join(", ")
+            ));
+        }
+        if job.format == "sql" && job.sql.separate_data() {
+            return Err(
+                "MySQL LOAD DATA reads its rows from a separate file; use INSERT for large runs"
+                    .to_string(),
+            );
+        }
+        Ok(Self {
+            rng: generate::seeded_rng(job.seed),
+            stream: Stream::new(&job.format, &job.csv, &job.sql, Some(job.seed)),
+            defects,
+            done: 0,
+            table: "rows",
+            job,
+        })
+    }
+
+    /// Rows requested so far, including any a generator skipped.
+    pub fn done(&self) -> u32 {
+        self.done
+    }
+
+    pub fn total(&self) -> u32 {
+        self.job.count
+    }
+
+    /// Download name, e.g. `ibans_seed_42.csv`, once a chunk has run.
+    pub fn file_name(&self) -> String {
+        format!("{}_seed_{}.{}", self.table, self.job.seed, self.job.format)
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self.job.format.as_str() {
+            "json" => "application/json;charset=utf-8;",
+            "sql" => "text/plain;charset=utf-8;",
+            "ndjson" => "application/x-ndjson;charset=utf-8;",
+            "xml" => "application/xml;charset=utf-8;",
+            "yaml" => "application/yaml;charset=utf-8;",
+            _ => "text/csv;charset=utf-8;",
+        }
+    }
+
+    /// Export text for the next chunk, or `None` once every row is done.
+    pub fn next_chunk(&mut self) -> Result<Option<String>, String> {
+        if self.done >= self.job.count {
+            return Ok(None);
+        }
+        let n = CHUNK.min(self.job.count - self.done);
+        self.done += n;
+
+        let job = &self.job;
+        let rng = &mut self.rng;
+        let defects = &self.defects;
+        let any_country = |c: &str| {
+            if c.is_empty() || c == "Random" {
+                None
+            } else {
+                Some(c.to_string())
+            }
+        };
+        let text = match job.tab.as_str() {
+            "iban" => {
+                let country = any_country(&job.country);
+                let rows = if job.bank.trim().is_empty() && job.branch.trim().is_empty() {
+                    generate::ibans(country.as_deref(), n, rng)
+                } else {
+                    let country = country.ok_or("Pick a country to fix a bank or branch code")?;
+                    generate::ibans_at_bank(&country, &job.bank, &job.branch, n, rng)?
+                };
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                let spaces = job.spaces;
+                self.table = <crate::IbanRow as Record>::TABLE;
+                self.stream
+                    .push_with(&rows, |row| export::iban_csv_fields(row, spaces))
+            }
+            "id" => {
+                let reg = personal_id::Registry::new();
+                let rows =
+                    generate::personal_ids(&reg, &job.country, &job.gender, job.year, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "bank" => {
+                let rows = generate::bank_accounts(&job.country, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "card" => {
+                let reg = credit_card::Registry::new();
+                let rows = generate::credit_cards(&reg, &job.brand, job.current_year, n, rng);
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                self.render(&rows)
+            }
+            "swift" => {
+                let reg = swift::Registry::new();
+                let rows = generate::swift_codes(&reg, &job.country, n, rng);
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                self.render(&rows)
+            }
+            "company" => {
+                let reg = company_id::Registry::new();
+                let rows = generate::company_ids(&reg, &job.country, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "driver_license" => {
+                let reg = driver_license::Registry::new();
+                let state = Some(job.state.as_str()).filter(|s| !s.is_empty());
+                let rows = generate::driver_licenses(&reg, &job.country, state, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "passport" => {
+                let reg = passport::Registry::new();
+                let rows = generate::passports(&reg, &job.country, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "tax_id" => {
+                let reg = tax_id::Registry::new();
+                let rows = generate::tax_ids(&reg, &job.country, None, n, rng);
+                let rows = negative::break_rows(rows, &job.country, defects, rng)?;
+                self.render(&rows)
+            }
+            "vat" => {
+                let reg = vat::Registry::new();
+                let rows = generate::vat_numbers(&reg, &job.country, n, rng);
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                self.render(&rows)
+            }
+            "lei" => {
+                let reg = lei::Registry::new();
+                let country = any_country(&job.country);
+                let rows = generate::leis(&reg, country.as_deref(), n, rng);
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                self.render(&rows)
+            }
+            "creditor_id" => {
+                let country = any_country(&job.country);
+                let rows = generate::creditor_ids(country.as_deref(), n, rng);
+                let rows = negative::break_rows(rows, "", defects, rng)?;
+                self.render(&rows)
+            }
+            "person" => {
+                let rows =
+                    PersonGenerator::new().generate(&job.country, &job.gender, job.year, n, rng);
+                self.render(&rows)
+            }
+            "entity" => {
+                let rows = CompanyGenerator::new().generate(&job.country, n, rng);
+                self.render(&rows)
+            }
+            other => return Err(format!("'{}' can't be generated in bulk", other)),
+        };
+        Ok(Some(text))
+    }
+
+    /// What closes the file after the last chunk.
+    pub fn finish(&mut self) -> String {
+        self.stream.finish()
+    }
+
+    fn render<T: Record>(&mut self, rows: &[T]) -> String {
+        self.table = T::TABLE;
+        self.stream.push(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    fn job(tab: &str, format: &str) -> Job {
+        Job {
+            tab: tab.to_string(),
+            country: "PL".to_string(),
+            count: 2 * CHUNK + 500,
+            seed: 42,
+            format: format.to_string(),
+            sql: SqlOptions {
+                batch: 300,
+                ..SqlOptions::default()
+            },
+            ..Job::default()
+        }
+    }
+
+    fn run(job: Job) -> String {
+        let mut run = Run::new(job).unwrap();
+        let mut text = String::new();
+        while let Some(chunk) = run.next_chunk().unwrap() {
+            text.push_str(&chunk);
+        }
+        assert_eq!(run.done(), run.total());
+        text + &run.finish()
+    }
+
+    #[test]
+    fn chunks_join_into_one_export() {
+        let rows = generate::personal_ids(
+            &personal_id::Registry::new(),
+            "PL",
+            "",
+            None,
+            2 * CHUNK + 500,
+            &mut seeded_rng(42),
+        );
+        for format in FORMATS {
+            let job = job("id", format);
+            let expected = match format {
+                "csv" => export::to_csv(&rows, &job.csv),
+                "json" => export::to_json(&rows),
+                "sql" => export::to_sql(&rows, Some(42), &job.sql),
+                other => String::from_utf8(export::to_bytes(&rows, other).unwrap()).unwrap(),
+            };
+            assert!(run(job) == expected, "{format}");
+        }
+    }
+
+    #[test]
+    fn csv_options_hold_across_chunks() {
+        let rows = generate::ibans(Some("PL"), 2 * CHUNK + 500, &mut seeded_rng(42));
+        let csv = CsvOptions {
+            delimiter: ';',
+            header: false,
+            bom: true,
+            crlf: true,
+        };
+        for spaces in [false, true] {
+            let job = Job {
+                spaces,
+                csv: csv.clone(),
+                ..job("iban", "csv")
+            };
+            assert!(run(job) == export::ibans_csv(&rows, spaces, &csv));
+        }
+    }
+
+    #[test]
+    fn near_miss_rows_keep_the_defect_column() {
+        let job = Job {
+            defects: "check_digit".to_string(),
+            ..job("iban", "csv")
+        };
+        let text = run(job.clone());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), job.count as usize + 1);
+        assert!(lines[0].ends_with(",Defect"));
+        assert!(
+            lines[1..]
+                .iter()
+                .all(|line| line.ends_with(",Wrong check digit"))
+        );
+        assert_eq!(text, run(job));
+    }
+
+    #[test]
+    fn rejects_what_cant_be_streamed() {
+        assert!(Run::new(job("id", "xlsx")).is_err());
+        let job = Job {
+            sql: SqlOptions {
+                dialect: export::Dialect::MySql,
+                load: true,
+                ..SqlOptions::default()
+            },
+            ..job("id", "sql")
+        };
+        assert!(Run::new(job).is_err());
+    }
+}
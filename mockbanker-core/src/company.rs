@@ -0,0 +1,221 @@
+//! Composite synthetic company: one legal entity whose identifiers agree.
+//!
+//! The registration number comes first. Where the country's VAT number is
+//! that number with a country prefix (CVR, IČO, NIPC, OIB, ...), or is
+//! computed from it (the French SIREN key, the Swedish `01` suffix), the VAT
+//! number is derived from it and only generated independently otherwise. The
+//! LEI carries the same country code, and in countries whose IBAN embeds the
+//! SWIFT bank code (NL, GB, IE, ...) the corporate IBAN is issued by the bank
+//! of the generated BIC.
+
+use idsmith::{company_id, iban, lei, swift, vat};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::generate::iban_for_bic;
+use crate::seeded;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompanyRow {
+    pub country: String,
+    pub company_id: String,
+    pub vat: String,
+    /// The VAT number was derived from the registration number rather than
+    /// generated on its own.
+    pub vat_derived: bool,
+    pub lei: String,
+    pub bic: String,
+    pub iban: String,
+    /// Every generated identifier passed its validator.
+    pub valid: bool,
+}
+
+pub struct CompanyGenerator {
+    company_id: company_id::Registry,
+    vat: vat::Registry,
+    lei: lei::Registry,
+    swift: swift::Registry,
+}
+
+impl Default for CompanyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompanyGenerator {
+    pub fn new() -> Self {
+        Self {
+            company_id: company_id::Registry::new(),
+            vat: vat::Registry::new(),
+            lei: lei::Registry::new(),
+            swift: swift::Registry::new(),
+        }
+    }
+
+    /// Countries with a company registration format, as `(code, name)`.
+    pub fn countries(&self) -> Vec<(String, String)> {
+        self.company_id
+            .list_countries()
+            .iter()
+            .map(|(c, n, _)| (c.to_string(), n.to_string()))
+            .collect()
+    }
+
+    pub fn generate<R: Rng>(&self, country: &str, count: u32, rng: &mut R) -> Vec<CompanyRow> {
+        let mut rows = Vec::new();
+        for _ in 0..count {
+            if let Some(row) = self.company(country, rng) {
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    fn company<R: Rng>(&self, country: &str, rng: &mut R) -> Option<CompanyRow> {
+        let company = seeded::company_id(&self.company_id, country, rng)?;
+        let mut valid = company.valid;
+
+        let derived = vat_candidates(country, &company.code)
+            .into_iter()
+            .find(|candidate| self.vat.validate(candidate));
+        let vat_derived = derived.is_some();
+        let vat = match derived {
+            Some(code) => code,
+            None => self
+                .vat
+                .generate(
+                    &vat::GenOptions {
+                        country: Some(country.to_string()),
+                    },
+                    rng,
+                )
+                .map(|res| {
+                    valid &= res.valid;
+                    res.code
+                })
+                .unwrap_or_default(),
+        };
+
+        let lei = self.lei.generate(
+            &lei::GenOptions {
+                country: Some(country.to_string()),
+            },
+            rng,
+        );
+        valid &= lei.valid;
+
+        let bic = self.swift.generate(
+            &swift::GenOptions {
+                country: Some(country.to_string()),
+            },
+            rng,
+        );
+        valid &= bic.valid;
+
+        let iban = match iban_for_bic(country, &bic.code, rng) {
+            Some(code) => {
+                valid &= iban::validate_iban(&code);
+                code
+            }
+            None => String::new(),
+        };
+
+        Some(CompanyRow {
+            country: country.to_string(),
+            company_id: company.code,
+            vat,
+            vat_derived,
+            lei: lei.code,
+            bic: bic.code,
+            iban,
+            valid,
+        })
+    }
+}
+
+/// VAT numbers the registration number could stand behind, most likely
+/// first. Callers keep the first one the VAT validator accepts.
+fn vat_candidates(country: &str, company_id: &str) -> Vec<String> {
+    let digits: String = company_id
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    // Greece files VAT under EL, not its ISO code.
+    let prefix = if country == "GR" { "EL" } else { country };
+    // Some registration numbers are already written as the VAT number.
+    let digits = digits.strip_prefix(prefix).unwrap_or(&digits);
+    let mut candidates = vec![format!("{}{}", prefix, digits)];
+    match country {
+        "FR" => {
+            if let Some(siren) = digits.get(..9)
+                && let Ok(n) = siren.parse::<u64>()
+            {
+                candidates.push(format!("FR{:02}{}", (12 + 3 * (n % 97)) % 97, siren));
+            }
+        }
+        "SE" => candidates.push(format!("SE{}01", digits)),
+        "BE" => candidates.push(format!("BE0{}", digits)),
+        _ => {}
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    fn companies(country: &str) -> Vec<CompanyRow> {
+        CompanyGenerator::new().generate(country, 30, &mut seeded_rng(5))
+    }
+
+    #[test]
+    fn vat_number_is_derived_from_the_registration_number() {
+        for country in ["DK", "CZ", "FR", "SE", "PT", "HR", "AT", "DE"] {
+            for row in companies(country) {
+                assert!(row.vat_derived, "{row:?}");
+                let digits: String = row
+                    .company_id
+                    .chars()
+                    .filter(char::is_ascii_digit)
+                    .collect();
+                let siren = digits.get(..9).unwrap_or(&digits);
+                assert!(row.vat.contains(siren), "{row:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn greek_vat_uses_el() {
+        assert_eq!(vat_candidates("GR", "094014201")[0], "EL094014201");
+    }
+
+    #[test]
+    fn french_vat_key_follows_the_siren() {
+        let candidates = vat_candidates("FR", "404 833 048 00022");
+        assert_eq!(candidates[1], "FR83404833048");
+        assert!(vat::Registry::new().validate(&candidates[1]));
+    }
+
+    #[test]
+    fn iban_is_issued_by_the_bic_bank() {
+        for country in ["NL", "GB", "IE"] {
+            for row in companies(country) {
+                assert_eq!(row.iban[4..8], row.bic[..4], "{row:?}");
+                assert_eq!(&row.bic[4..6], country, "{row:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rows_validate_and_follow_the_seed() {
+        for country in ["DE", "PL", "NL", "US", "BR"] {
+            let rows = companies(country);
+            assert_eq!(rows.len(), 30, "{country}");
+            assert!(rows.iter().all(|row| row.valid), "{country}");
+            assert_eq!(format!("{rows:?}"), format!("{:?}", companies(country)));
+        }
+    }
+}
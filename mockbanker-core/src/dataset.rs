@@ -0,0 +1,594 @@
+//! Multi-column datasets built from a [`Schema`]: each column is bound to
+//! one of the generators with its own options, and rows are generated
+//! jointly from one seeded RNG, column by column.
+//!
+//! Columns reading different fields of the same generator with the same
+//! options share one draw per row, so a "PESEL" column and a "Date of birth"
+//! column taken from it describe the same person. A column repeating a field
+//! gets a draw of its own, so debtor and creditor IBAN columns differ.
+
+use idsmith::{
+    company_id, credit_card, driver_license, lei, passport, personal_id, swift, tax_id, vat,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::export::{self, CsvOptions, Record, SqlOptions, SqlType, SqlValue};
+use crate::generate;
+use crate::rows::*;
+
+/// Most rows a dataset is generated with.
+pub const MAX_ROWS: u32 = 10_000;
+
+/// A generator a column can be bound to.
+pub struct Kind {
+    pub key: &'static str,
+    pub label: &'static str,
+    /// Options it reads: `country`, `gender`, `year`, `brand`, `state`
+    /// and `holder_type`.
+    pub options: &'static [&'static str],
+    /// Values one draw yields, the first being the code itself.
+    pub fields: &'static [(&'static str, SqlType)],
+}
+
+const NAME_FIELDS: &[(&str, SqlType)] = &[
+    ("name", SqlType::Text),
+    ("first_name", SqlType::Text),
+    ("last_name", SqlType::Text),
+];
+
+pub const KINDS: [Kind; 13] = [
+    Kind {
+        key: "name",
+        label: "Customer name",
+        options: &["gender"],
+        fields: NAME_FIELDS,
+    },
+    Kind {
+        key: "iban",
+        label: "IBAN",
+        options: &["country"],
+        fields: IbanRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "personal_id",
+        label: "Personal ID",
+        options: &["country", "gender", "year"],
+        fields: IdRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "bank_account",
+        label: "Bank account",
+        options: &["country"],
+        fields: BankAccountRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "credit_card",
+        label: "Card number",
+        options: &["brand"],
+        fields: CreditCardRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "swift",
+        label: "SWIFT/BIC",
+        options: &["country"],
+        fields: SwiftRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "company_id",
+        label: "Company ID",
+        options: &["country"],
+        fields: CompanyIdRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "driver_license",
+        label: "Driver's license",
+        options: &["country", "state"],
+        fields: DriverLicenseRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "passport",
+        label: "Passport",
+        options: &["country"],
+        fields: PassportRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "tax_id",
+        label: "Tax ID",
+        options: &["country", "holder_type"],
+        fields: TaxIdRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "vat",
+        label: "VAT number",
+        options: &["country"],
+        fields: VatRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "lei",
+        label: "LEI",
+        options: &["country"],
+        fields: LeiRow::SQL_COLUMNS,
+    },
+    Kind {
+        key: "creditor_id",
+        label: "Creditor ID",
+        options: &["country"],
+        fields: CreditorIdRow::SQL_COLUMNS,
+    },
+];
+
+pub fn kind(key: &str) -> Option<&'static Kind> {
+    KINDS.iter().find(|k| k.key == key)
+}
+
+/// One column of a [`Schema`]. Options the generator doesn't read are
+/// ignored.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Column {
+    /// CSV header, JSON key and SQL column name.
+    pub name: String,
+    /// Generator, a [`Kind::key`].
+    pub kind: String,
+    /// Which of the generator's [`Kind::fields`], e.g. `dob` of a personal
+    /// ID; empty for the code itself.
+    pub field: String,
+    /// Empty or `Random` for any country where the generator allows it.
+    pub country: String,
+    /// `male`, `female` or empty for either.
+    pub gender: String,
+    pub year: Option<u16>,
+    pub brand: String,
+    pub state: String,
+    pub holder_type: String,
+}
+
+impl Column {
+    pub fn new(name: &str, kind: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `other` reads the same generator with the same options, so
+    /// both can take their values from one draw.
+    fn same_options(&self, other: &Column) -> bool {
+        self.kind == other.kind
+            && self.country == other.country
+            && self.gender == other.gender
+            && self.year == other.year
+            && self.brand == other.brand
+            && self.state == other.state
+            && self.holder_type == other.holder_type
+    }
+
+    /// Position and type of the chosen field among its generator's.
+    fn field(&self) -> Result<(usize, SqlType), String> {
+        let kind = kind(&self.kind)
+            .ok_or_else(|| format!("Column '{}': unknown generator '{}'", self.name, self.kind))?;
+        if self.field.is_empty() {
+            return Ok((0, kind.fields[0].1));
+        }
+        kind.fields
+            .iter()
+            .position(|(name, _)| *name == self.field)
+            .map(|i| (i, kind.fields[i].1))
+            .ok_or_else(|| {
+                let fields: Vec<&str> = kind.fields.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "Column '{}': {} has no field '{}', use {}",
+                    self.name,
+                    kind.label,
+                    self.field,
+                    fields.join(", ")
+                )
+            })
+    }
+}
+
+/// Columns of a dataset, saved and reloaded as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schema {
+    /// SQL table name and download file stem; empty for `dataset`.
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+impl Schema {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("not a dataset schema: {}", e))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn table(&self) -> &str {
+        let name = self.name.trim();
+        if name.is_empty() { "dataset" } else { name }
+    }
+
+    /// `count` rows, each filling every column. `current_year` is the
+    /// two-digit year card expiry dates are picked after.
+    pub fn generate<R: Rng>(
+        &self,
+        count: u32,
+        current_year: u16,
+        rng: &mut R,
+    ) -> Result<Dataset, String> {
+        if self.columns.is_empty() {
+            return Err("Add a column to generate".to_string());
+        }
+        if count > MAX_ROWS {
+            return Err(format!("A dataset holds at most {} rows", MAX_ROWS));
+        }
+        let mut fields = Vec::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            if column.name.trim().is_empty() {
+                return Err(format!("Column {} needs a name", i + 1));
+            }
+            if self.columns[..i].iter().any(|c| c.name == column.name) {
+                return Err(format!("Two columns are named '{}'", column.name));
+            }
+            fields.push(column.field()?);
+        }
+
+        // The column whose draw each column reads: the first earlier one with
+        // the same options whose draw no column has taken this field from yet.
+        let mut draw_of: Vec<usize> = Vec::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            let shared = (0..i).find(|&j| {
+                draw_of[j] == j
+                    && self.columns[j].same_options(column)
+                    && !(0..i).any(|k| draw_of[k] == j && fields[k].0 == fields[i].0)
+            });
+            draw_of.push(shared.unwrap_or(i));
+        }
+
+        let generators = Generators::new();
+        let mut rows = Vec::new();
+        for _ in 0..count {
+            let mut draws: Vec<Option<Vec<SqlValue>>> = Vec::new();
+            let mut row = Vec::new();
+            for (i, (column, (field, _))) in self.columns.iter().zip(&fields).enumerate() {
+                let draw = if draw_of[i] == i {
+                    generators.draw(column, current_year, rng)
+                } else {
+                    draws[draw_of[i]].clone()
+                };
+                row.push(
+                    draw.as_ref()
+                        .and_then(|values| values.get(*field).cloned())
+                        .unwrap_or(SqlValue::Null),
+                );
+                draws.push(draw);
+            }
+            rows.push(row);
+        }
+
+        for (i, column) in self.columns.iter().enumerate() {
+            if count > 0 && rows.iter().all(|row| row[i] == SqlValue::Null) {
+                return Err(format!(
+                    "Nothing generated for column '{}'; check that its country, brand or state is supported",
+                    column.name
+                ));
+            }
+        }
+        Ok(Dataset {
+            table: self.table().to_string(),
+            columns: self
+                .columns
+                .iter()
+                .zip(fields)
+                .map(|(c, (_, ty))| (c.name.clone(), ty))
+                .collect(),
+            rows,
+        })
+    }
+}
+
+/// Registries for every generator, built once per dataset or
+/// pseudonymized file.
+pub(crate) struct Generators {
+    personal_id: personal_id::Registry,
+    credit_card: credit_card::Registry,
+    swift: swift::Registry,
+    company_id: company_id::Registry,
+    driver_license: driver_license::Registry,
+    passport: passport::Registry,
+    tax_id: tax_id::Registry,
+    vat: vat::Registry,
+    lei: lei::Registry,
+}
+
+impl Generators {
+    pub(crate) fn new() -> Self {
+        Self {
+            personal_id: personal_id::Registry::new(),
+            credit_card: credit_card::Registry::new(),
+            swift: swift::Registry::new(),
+            company_id: company_id::Registry::new(),
+            driver_license: driver_license::Registry::new(),
+            passport: passport::Registry::new(),
+            tax_id: tax_id::Registry::new(),
+            vat: vat::Registry::new(),
+            lei: lei::Registry::new(),
+        }
+    }
+
+    /// Card brands the credit card generator accepts.
+    pub(crate) fn brands(&self) -> Vec<String> {
+        self.credit_card
+            .list_brands()
+            .iter()
+            .map(|b| b.to_string())
+            .collect()
+    }
+
+    /// One draw's [`Kind::fields`], or `None` if the generator made nothing
+    /// for these options.
+    pub(crate) fn draw<R: Rng>(
+        &self,
+        column: &Column,
+        current_year: u16,
+        rng: &mut R,
+    ) -> Option<Vec<SqlValue>> {
+        let country = column.country.trim();
+        let any_country =
+            Some(country).filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case("random"));
+        let option = |value: &str| {
+            Some(value.trim())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        match column.kind.as_str() {
+            "name" => Some(name(&column.gender, rng)),
+            "iban" => first(generate::ibans(any_country, 1, rng)),
+            "personal_id" => first(generate::personal_ids(
+                &self.personal_id,
+                country,
+                &column.gender,
+                column.year,
+                1,
+                rng,
+            )),
+            "bank_account" => first(generate::bank_accounts(country, 1, rng)),
+            "credit_card" => {
+                let brand = option(&column.brand).unwrap_or_else(|| "visa".to_string());
+                first(generate::credit_cards(
+                    &self.credit_card,
+                    &brand,
+                    current_year,
+                    1,
+                    rng,
+                ))
+            }
+            "swift" => first(generate::swift_codes(&self.swift, country, 1, rng)),
+            "company_id" => first(generate::company_ids(&self.company_id, country, 1, rng)),
+            "driver_license" => {
+                let state = option(&column.state);
+                first(generate::driver_licenses(
+                    &self.driver_license,
+                    country,
+                    state.as_deref(),
+                    1,
+                    rng,
+                ))
+            }
+            "passport" => first(generate::passports(&self.passport, country, 1, rng)),
+            "tax_id" => {
+                let holder_type = option(&column.holder_type);
+                first(generate::tax_ids(
+                    &self.tax_id,
+                    country,
+                    holder_type.as_deref(),
+                    1,
+                    rng,
+                ))
+            }
+            "vat" => first(generate::vat_numbers(&self.vat, country, 1, rng)),
+            "lei" => first(generate::leis(&self.lei, any_country, 1, rng)),
+            "creditor_id" => first(generate::creditor_ids(any_country, 1, rng)),
+            _ => None,
+        }
+    }
+}
+
+fn first<T: Record>(rows: Vec<T>) -> Option<Vec<SqlValue>> {
+    rows.first().map(Record::sql_values)
+}
+
+const FEMALE_NAMES: [&str; 16] = [
+    "Anna", "Maria", "Sofia", "Emma", "Laura", "Julia", "Elena", "Sara", "Eva", "Nina", "Clara",
+    "Lena", "Ines", "Marta", "Olivia", "Hanna",
+];
+const MALE_NAMES: [&str; 16] = [
+    "Jan", "Lukas", "Marco", "David", "Tomas", "Peter", "Daniel", "Adam", "Mateo", "Leon", "Erik",
+    "Paul", "Hugo", "Oscar", "Victor", "Felix",
+];
+const LAST_NAMES: [&str; 24] = [
+    "Novak",
+    "Schmidt",
+    "Rossi",
+    "Garcia",
+    "Jansen",
+    "Nielsen",
+    "Kowalski",
+    "Dubois",
+    "Silva",
+    "Horvat",
+    "Popescu",
+    "Virtanen",
+    "Murphy",
+    "Papadopoulos",
+    "Berg",
+    "Meyer",
+    "Costa",
+    "Fischer",
+    "Lindqvist",
+    "Moreau",
+    "Ferrari",
+    "Kovacs",
+    "Walsh",
+    "Peeters",
+];
+
+/// A made-up customer: full name, first name and last name.
+fn name<R: Rng>(gender: &str, rng: &mut R) -> Vec<SqlValue> {
+    let female = match gender {
+        "female" => true,
+        "male" => false,
+        _ => rng.gen_bool(0.5),
+    };
+    let first_names = if female { &FEMALE_NAMES } else { &MALE_NAMES };
+    let first = first_names[rng.gen_range(0..first_names.len())];
+    let last = LAST_NAMES[rng.gen_range(0..LAST_NAMES.len())];
+    vec![
+        SqlValue::Text(format!("{} {}", first, last)),
+        SqlValue::Text(first.to_string()),
+        SqlValue::Text(last.to_string()),
+    ]
+}
+
+/// Rows generated from a [`Schema`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dataset {
+    pub table: String,
+    /// `(name, type)` per column.
+    pub columns: Vec<(String, SqlType)>,
+    pub rows: Vec<Vec<SqlValue>>,
+}
+
+/// A value as the table and CSV show it: booleans as Yes/No, NULL empty.
+pub fn cell(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Text(s) | SqlValue::Date(s) => s.clone(),
+        SqlValue::Bool(b) => if *b { "Yes" } else { "No" }.to_string(),
+        SqlValue::Null => String::new(),
+    }
+}
+
+impl Dataset {
+    pub fn to_csv(&self, options: &CsvOptions) -> String {
+        let headers: Vec<&str> = self.columns.iter().map(|(name, _)| name.as_str()).collect();
+        export::write_csv(
+            &headers,
+            self.rows.iter().map(|row| row.iter().map(cell).collect()),
+            options,
+        )
+    }
+
+    /// A pretty-printed JSON array with one object per row, its keys in
+    /// column order.
+    pub fn to_json(&self) -> String {
+        if self.rows.is_empty() {
+            return "[]".to_string();
+        }
+        let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+        let objects: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let entries: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|((name, _), value)| {
+                        let value = match value {
+                            SqlValue::Text(s) | SqlValue::Date(s) => quote(s),
+                            SqlValue::Bool(b) => b.to_string(),
+                            SqlValue::Null => "null".to_string(),
+                        };
+                        format!("    {}: {}", quote(name), value)
+                    })
+                    .collect();
+                format!("  {{\n{}\n  }}", entries.join(",\n"))
+            })
+            .collect();
+        format!("[\n{}\n]", objects.join(",\n"))
+    }
+
+    pub fn to_sql(&self, seed: Option<u64>, options: &SqlOptions) -> String {
+        let columns: Vec<(&str, SqlType)> = self
+            .columns
+            .iter()
+            .map(|(name, ty)| (name.as_str(), *ty))
+            .collect();
+        export::write_sql(&self.table, &columns, &self.rows, seed, options)
+    }
+
+    /// Rows for MySQL's `LOAD DATA`, see [`export::load_data`].
+    pub fn load_data(&self) -> String {
+        export::write_load_data(&self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    fn iban_column(name: &str, field: &str) -> Column {
+        Column {
+            country: "DE".to_string(),
+            field: field.to_string(),
+            ..Column::new(name, "iban")
+        }
+    }
+
+    fn text(value: &SqlValue) -> &str {
+        match value {
+            SqlValue::Text(s) => s,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn columns_repeating_a_field_draw_separately() {
+        let schema = Schema {
+            name: String::new(),
+            columns: vec![
+                iban_column("debtor_iban", "iban"),
+                iban_column("creditor_iban", "iban"),
+            ],
+        };
+        let dataset = schema.generate(50, 26, &mut seeded_rng(7)).unwrap();
+        for row in &dataset.rows {
+            assert_ne!(row[0], row[1]);
+        }
+    }
+
+    #[test]
+    fn columns_reading_different_fields_share_a_draw() {
+        let schema = Schema {
+            name: String::new(),
+            columns: vec![
+                iban_column("debtor_iban", "iban"),
+                iban_column("debtor_check", "check_digits"),
+                iban_column("creditor_iban", "iban"),
+                iban_column("creditor_check", "check_digits"),
+            ],
+        };
+        let dataset = schema.generate(50, 26, &mut seeded_rng(7)).unwrap();
+        for row in &dataset.rows {
+            assert_eq!(&text(&row[0])[2..4], text(&row[1]));
+            assert_eq!(&text(&row[2])[2..4], text(&row[3]));
+            assert_ne!(row[0], row[2]);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_rows() {
+        let schema = Schema {
+            name: String::new(),
+            columns: vec![iban_column("iban", ""), Column::new("holder", "name")],
+        };
+        let a = schema.generate(20, 26, &mut seeded_rng(42)).unwrap();
+        let b = schema.generate(20, 26, &mut seeded_rng(42)).unwrap();
+        assert_eq!(a.rows, b.rows);
+    }
+}
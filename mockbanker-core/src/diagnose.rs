@@ -0,0 +1,552 @@
+//! Rule-by-rule diagnosis of a value, so a rejection names the broken rule:
+//! wrong characters, wrong length for the country, wrong check digits (and
+//! which ones were expected) or a failed national BBAN check.
+
+use serde::{Deserialize, Serialize};
+
+use crate::generate::mod97;
+use crate::sepa;
+
+/// IBAN length per country, from the SWIFT IBAN registry.
+pub const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AE", 23),
+    ("AL", 28),
+    ("AT", 20),
+    ("AZ", 28),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BI", 27),
+    ("BR", 29),
+    ("BY", 28),
+    ("CH", 21),
+    ("CR", 22),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DJ", 27),
+    ("DK", 18),
+    ("DO", 28),
+    ("EE", 20),
+    ("EG", 29),
+    ("ES", 24),
+    ("FI", 18),
+    ("FK", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GE", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("GT", 28),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IQ", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("JO", 30),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LC", 32),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("LY", 25),
+    ("MC", 27),
+    ("MD", 24),
+    ("ME", 22),
+    ("MK", 19),
+    ("MN", 20),
+    ("MR", 27),
+    ("MT", 31),
+    ("MU", 30),
+    ("NI", 28),
+    ("NL", 18),
+    ("NO", 15),
+    ("OM", 23),
+    ("PK", 24),
+    ("PL", 28),
+    ("PS", 29),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("RU", 33),
+    ("SA", 24),
+    ("SC", 31),
+    ("SD", 18),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("SO", 23),
+    ("ST", 25),
+    ("SV", 28),
+    ("TL", 23),
+    ("TN", 24),
+    ("TR", 26),
+    ("UA", 29),
+    ("VA", 22),
+    ("VG", 24),
+    ("XK", 20),
+    ("YE", 30),
+];
+
+/// Expected IBAN length for `country`, if it issues IBANs.
+pub fn iban_length(country: &str) -> Option<usize> {
+    IBAN_LENGTHS
+        .iter()
+        .find(|(c, _)| *c == country)
+        .map(|(_, len)| *len)
+}
+
+/// One rule and whether the value passed it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Check {
+    pub label: String,
+    pub passed: bool,
+    /// What was found and, on failure, what was expected.
+    pub detail: String,
+}
+
+impl Check {
+    pub(crate) fn new(label: &str, passed: bool, detail: String) -> Self {
+        Self {
+            label: label.to_string(),
+            passed,
+            detail,
+        }
+    }
+}
+
+/// The checks run on a value, in order. Checks that depend on an earlier
+/// failure (check digits on a value of the wrong length) are left out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Diagnosis {
+    pub valid: bool,
+    /// One line for reports: the validity message, or the first failure.
+    pub summary: String,
+    pub checks: Vec<Check>,
+}
+
+impl Diagnosis {
+    /// Valid when every check passed; the summary names the first failure.
+    pub(crate) fn from_checks(name: &str, valid_message: &str, checks: Vec<Check>) -> Self {
+        let summary = match checks.iter().find(|c| !c.passed) {
+            Some(failed) => format!("Invalid {}: {}", name, failed.detail),
+            None => valid_message.to_string(),
+        };
+        Self {
+            valid: checks.iter().all(|c| c.passed),
+            summary,
+            checks,
+        }
+    }
+}
+
+/// Spaces removed, as IBANs are compared electronically.
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Passes when every character is `allowed`, else names the first one that
+/// isn't.
+fn charset(value: &str, allowed: impl Fn(char) -> bool, expected: &str) -> Check {
+    match value.chars().enumerate().find(|(_, c)| !allowed(*c)) {
+        Some((i, c)) => Check::new(
+            "Character set",
+            false,
+            format!(
+                "'{}' at position {} is not allowed, expected {}",
+                c,
+                i + 1,
+                expected
+            ),
+        ),
+        None => Check::new("Character set", true, expected.to_string()),
+    }
+}
+
+fn check_digits(found: &str, expected: &str) -> Check {
+    if found == expected {
+        Check::new("Check digits", true, format!("{} match", found))
+    } else {
+        Check::new(
+            "Check digits",
+            false,
+            format!("found {}, expected {}", found, expected),
+        )
+    }
+}
+
+/// Diagnoses an IBAN: character set, country, length for the country,
+/// mod-97 check digits and, where the country has one, the national BBAN
+/// check.
+pub fn iban(value: &str) -> Vec<Check> {
+    let iban = compact(value);
+    let mut checks = vec![charset(
+        &iban,
+        |c| c.is_ascii_alphanumeric(),
+        "letters and digits only",
+    )];
+    if !checks[0].passed {
+        return checks;
+    }
+    let iban = iban.to_ascii_uppercase();
+
+    let country = iban.get(..2).unwrap_or("");
+    let Some(length) = iban_length(country) else {
+        checks.push(Check::new(
+            "Country code",
+            false,
+            format!("'{}' is not an IBAN country", country),
+        ));
+        return checks;
+    };
+    checks.push(Check::new("Country code", true, country.to_string()));
+
+    if iban.len() != length {
+        checks.push(Check::new(
+            "Length",
+            false,
+            format!(
+                "{} characters, expected {} for {}",
+                iban.len(),
+                length,
+                country
+            ),
+        ));
+        return checks;
+    }
+    checks.push(Check::new("Length", true, format!("{} characters", length)));
+
+    let bban = &iban[4..];
+    let expected = format!("{:02}", 98 - mod97(&format!("{}{}00", bban, country)));
+    checks.push(check_digits(&iban[2..4], &expected));
+
+    if let Some((found, expected)) = national_check(country, bban) {
+        checks.push(if found == expected {
+            Check::new(
+                "National check",
+                true,
+                format!("BBAN check {} matches", found),
+            )
+        } else {
+            Check::new(
+                "National check",
+                false,
+                format!("BBAN check {}, expected {}", found, expected),
+            )
+        });
+    }
+    checks
+}
+
+/// `(found, expected)` national check characters of a BBAN, for countries
+/// whose account numbers carry their own check. `None` when the country has
+/// none or the BBAN isn't laid out as that check needs.
+pub(crate) fn national_check(country: &str, bban: &str) -> Option<(String, String)> {
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    match country {
+        // Account number mod 97, 97 in place of 0.
+        "BE" if bban.len() == 12 && digits(bban) => {
+            let n: u64 = bban[..10].parse().ok()?;
+            let check = match n % 97 {
+                0 => 97,
+                r => r,
+            };
+            Some((bban[10..].to_string(), format!("{:02}", check)))
+        }
+        // RIB key over bank, branch and account, letters folded to digits.
+        "FR" | "MC" if bban.len() == 23 => {
+            let folded: String = bban[..21]
+                .chars()
+                .map(|c| match c {
+                    '0'..='9' => Some(c),
+                    'A'..='I' => char::from_digit(c as u32 - 'A' as u32 + 1, 10),
+                    'J'..='R' => char::from_digit(c as u32 - 'J' as u32 + 1, 10),
+                    'S'..='Z' => char::from_digit(c as u32 - 'S' as u32 + 2, 10),
+                    _ => None,
+                })
+                .collect::<Option<String>>()?;
+            let bank: u64 = folded[..5].parse().ok()?;
+            let branch: u64 = folded[5..10].parse().ok()?;
+            let account: u64 = folded[10..].parse().ok()?;
+            let key = 97 - (89 * bank + 15 * branch + 3 * account) % 97;
+            Some((bban[21..].to_string(), format!("{:02}", key)))
+        }
+        // Two mod-11 digits: one over bank and branch, one over the account.
+        "ES" if bban.len() == 20 && digits(bban) => {
+            let digit = |s: &str| {
+                const WEIGHTS: [u32; 10] = [1, 2, 4, 8, 5, 10, 9, 7, 3, 6];
+                let sum: u32 = s
+                    .chars()
+                    .zip(WEIGHTS)
+                    .map(|(c, w)| c.to_digit(10).unwrap_or(0) * w)
+                    .sum();
+                match 11 - sum % 11 {
+                    11 => 0,
+                    10 => 1,
+                    d => d,
+                }
+            };
+            let expected = format!(
+                "{}{}",
+                digit(&format!("00{}", &bban[..8])),
+                digit(&bban[10..])
+            );
+            Some((bban[8..10].to_string(), expected))
+        }
+        // CIN letter over ABI, CAB and account, odd and even positions
+        // weighted differently.
+        "IT" | "SM" if bban.len() == 23 => {
+            const ODD: [u32; 26] = [
+                1, 0, 5, 7, 9, 13, 15, 17, 19, 21, 2, 4, 18, 20, 11, 3, 6, 8, 12, 14, 16, 10, 22,
+                25, 24, 23,
+            ];
+            let mut sum = 0;
+            for (i, c) in bban[1..].chars().enumerate() {
+                let v = match c {
+                    '0'..='9' => c as u32 - '0' as u32,
+                    'A'..='Z' => c as u32 - 'A' as u32,
+                    _ => return None,
+                };
+                sum += if i % 2 == 0 { ODD[v as usize] } else { v };
+            }
+            let cin = char::from(b'A' + (sum % 26) as u8);
+            Some((bban[..1].to_string(), cin.to_string()))
+        }
+        // NIB: 98 minus the account mod 97, as for IBAN check digits.
+        "PT" if bban.len() == 21 && digits(bban) => {
+            let expected = 98 - mod97(&format!("{}00", &bban[..19]));
+            Some((bban[19..].to_string(), format!("{:02}", expected)))
+        }
+        // Mod 11 with weights 5,4,3,2,7,6,5,4,3,2.
+        "NO" if bban.len() == 11 && digits(bban) => {
+            const WEIGHTS: [u32; 10] = [5, 4, 3, 2, 7, 6, 5, 4, 3, 2];
+            let sum: u32 = bban
+                .chars()
+                .zip(WEIGHTS)
+                .map(|(c, w)| c.to_digit(10).unwrap_or(0) * w)
+                .sum();
+            let expected = match 11 - sum % 11 {
+                11 => "0".to_string(),
+                // No valid account number leaves remainder 1.
+                10 => "-".to_string(),
+                d => d.to_string(),
+            };
+            Some((bban[10..].to_string(), expected))
+        }
+        // Luhn over the 14-digit account.
+        "FI" if bban.len() == 14 && digits(bban) => {
+            Some((bban[13..].to_string(), luhn_digit(&bban[..13]).to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Luhn check digit to append to `payload`.
+fn luhn_digit(payload: &str) -> u32 {
+    let sum: u32 = payload
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 0 {
+                let d = d * 2;
+                if d > 9 { d - 9 } else { d }
+            } else {
+                d
+            }
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Diagnoses a card number: digits only (spaces and hyphens ignored),
+/// 12 to 19 digits and the Luhn check digit.
+pub fn card(value: &str) -> Vec<Check> {
+    let number: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    let mut checks = vec![charset(&number, |c| c.is_ascii_digit(), "digits only")];
+    if !checks[0].passed {
+        return checks;
+    }
+    if !(12..=19).contains(&number.len()) {
+        checks.push(Check::new(
+            "Length",
+            false,
+            format!("{} digits, expected 12 to 19", number.len()),
+        ));
+        return checks;
+    }
+    checks.push(Check::new(
+        "Length",
+        true,
+        format!("{} digits", number.len()),
+    ));
+    let (payload, last) = number.split_at(number.len() - 1);
+    checks.push(check_digits(last, &luhn_digit(payload).to_string()));
+    checks
+}
+
+/// Diagnoses an LEI: 20 upper-case letters and digits ending in mod-97
+/// check digits.
+pub fn lei(value: &str) -> Vec<Check> {
+    let lei = compact(value);
+    let mut checks = vec![charset(
+        &lei,
+        |c| c.is_ascii_alphanumeric(),
+        "letters and digits only",
+    )];
+    if !checks[0].passed {
+        return checks;
+    }
+    let lei = lei.to_ascii_uppercase();
+    if lei.len() != 20 {
+        checks.push(Check::new(
+            "Length",
+            false,
+            format!("{} characters, expected 20", lei.len()),
+        ));
+        return checks;
+    }
+    checks.push(Check::new("Length", true, "20 characters".to_string()));
+    let expected = format!("{:02}", 98 - mod97(&format!("{}00", &lei[..18])));
+    checks.push(check_digits(&lei[18..], &expected));
+    checks
+}
+
+/// Diagnoses a SEPA Creditor Identifier: character set, length, SEPA
+/// country and the check digits over the national identifier.
+pub fn creditor_id(value: &str) -> Vec<Check> {
+    let ci = compact(value);
+    let mut checks = vec![charset(
+        &ci,
+        |c| c.is_ascii_alphanumeric(),
+        "letters and digits only",
+    )];
+    if !checks[0].passed {
+        return checks;
+    }
+    let ci = ci.to_ascii_uppercase();
+    if !(8..=35).contains(&ci.len()) {
+        checks.push(Check::new(
+            "Length",
+            false,
+            format!("{} characters, expected 8 to 35", ci.len()),
+        ));
+        return checks;
+    }
+    checks.push(Check::new(
+        "Length",
+        true,
+        format!("{} characters", ci.len()),
+    ));
+    let country = &ci[..2];
+    if !sepa::SEPA_COUNTRIES.contains(&country) {
+        checks.push(Check::new(
+            "Country code",
+            false,
+            format!("'{}' is not in the SEPA scheme", country),
+        ));
+        return checks;
+    }
+    checks.push(Check::new("Country code", true, country.to_string()));
+    let expected = format!("{:02}", 98 - mod97(&format!("{}{}00", &ci[7..], country)));
+    checks.push(check_digits(&ci[2..4], &expected));
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(checks: &[Check]) -> Vec<&str> {
+        checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn iban_check_digits() {
+        assert!(failed(&iban("DE89 3704 0044 0532 0130 00")).is_empty());
+        assert!(failed(&iban("gb82west12345698765432")).is_empty());
+
+        let checks = iban("DE88370400440532013000");
+        assert_eq!(failed(&checks), ["Check digits"]);
+        assert_eq!(checks.last().unwrap().detail, "found 88, expected 89");
+    }
+
+    #[test]
+    fn iban_stops_at_the_first_structural_failure() {
+        assert_eq!(failed(&iban("DE89-3704")), ["Character set"]);
+        assert_eq!(failed(&iban("XX89370400440532013000")), ["Country code"]);
+        assert_eq!(failed(&iban("DE8937040044053201300")), ["Length"]);
+    }
+
+    #[test]
+    fn national_checks_pass_on_valid_ibans() {
+        for valid in [
+            "BE68539007547034",
+            "FR1420041010050500013M02606",
+            "ES9121000418450200051332",
+            "IT60X0542811101000000123456",
+            "PT50000201231234567890154",
+            "NO9386011117947",
+            "FI2112345600000785",
+        ] {
+            let checks = iban(valid);
+            assert!(
+                checks.iter().any(|c| c.label == "National check"),
+                "{} has no national check",
+                valid
+            );
+            assert!(failed(&checks).is_empty(), "{}: {:?}", valid, checks);
+        }
+    }
+
+    #[test]
+    fn national_check_names_the_expected_digits() {
+        assert_eq!(
+            national_check("BE", "539007547035"),
+            Some(("35".to_string(), "34".to_string()))
+        );
+        assert_eq!(
+            national_check("FI", "12345600000784"),
+            Some(("4".to_string(), "5".to_string()))
+        );
+        assert_eq!(national_check("DE", "370400440532013000"), None);
+    }
+
+    #[test]
+    fn card_luhn() {
+        assert!(failed(&card("4111 1111 1111 1111")).is_empty());
+        assert_eq!(failed(&card("4111111111111112")), ["Check digits"]);
+        assert_eq!(failed(&card("41111")), ["Length"]);
+    }
+
+    #[test]
+    fn lei_check_digits() {
+        assert!(failed(&lei("5493001KJTIIGC8Y1R12")).is_empty());
+        assert_eq!(failed(&lei("5493001KJTIIGC8Y1R13")), ["Check digits"]);
+    }
+
+    #[test]
+    fn creditor_id_check_digits() {
+        assert!(failed(&creditor_id("DE98ZZZ09999999999")).is_empty());
+        assert_eq!(failed(&creditor_id("DE97ZZZ09999999999")), ["Check digits"]);
+    }
+}
@@ -0,0 +1,1685 @@
+//! CSV, JSON, SQL, NDJSON, XML, YAML, XLSX and Parquet exporters shared by
+//! every row type; code snippets are in [`crate::snippet`].
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::anatomy::IbanParts;
+use crate::company::CompanyRow;
+use crate::negative::Defect;
+use crate::person::PersonRow;
+use crate::rows::*;
+use crate::sepa::escape;
+use crate::{parquet, xlsx};
+
+/// A row type that can be exported as a table.
+pub trait Record: Serialize {
+    /// SQL table name, also used as the download file stem.
+    const TABLE: &'static str;
+    /// Type name of one row in code snippets, e.g. `PersonalId`.
+    const NAME: &'static str;
+    /// CSV header cells, in the order returned by [`Record::csv_fields`].
+    const CSV_HEADERS: &'static [&'static str];
+    /// SQL `(column, type)` pairs, in the order returned by [`Record::sql_values`].
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)];
+
+    fn csv_fields(&self) -> Vec<String>;
+
+    /// Values in [`Record::SQL_COLUMNS`] order.
+    fn sql_values(&self) -> Vec<SqlValue>;
+
+    /// How a near-miss row was broken; exports gain a Defect column when
+    /// any row has one.
+    fn defect(&self) -> Option<Defect> {
+        None
+    }
+}
+
+fn yes_no(valid: bool) -> String {
+    if valid { "Yes" } else { "No" }.to_string()
+}
+
+fn text(value: &str) -> SqlValue {
+    SqlValue::Text(value.to_string())
+}
+
+/// An ISO date, or NULL when the row has none.
+fn date(value: &str) -> SqlValue {
+    if value.is_empty() {
+        SqlValue::Null
+    } else {
+        SqlValue::Date(value.to_string())
+    }
+}
+
+/// How CSV files are written. The default is a comma-separated file with a
+/// header row, LF line endings and no byte-order mark.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    /// `,`, `;` or a tab, see [`CsvOptions::DELIMITERS`].
+    pub delimiter: char,
+    pub header: bool,
+    /// Start with a UTF-8 byte-order mark so Excel detects the encoding.
+    pub bom: bool,
+    /// CRLF line endings, as RFC 4180 specifies, instead of LF.
+    pub crlf: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            bom: false,
+            crlf: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// `(key, label, delimiter)` for each supported delimiter.
+    pub const DELIMITERS: [(&'static str, &'static str, char); 3] = [
+        ("comma", "Comma", ','),
+        ("semicolon", "Semicolon", ';'),
+        ("tab", "Tab", '\t'),
+    ];
+
+    /// The delimiter for a key from [`CsvOptions::DELIMITERS`].
+    pub fn delimiter_for(key: &str) -> Result<char, String> {
+        Self::DELIMITERS
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, _, c)| *c)
+            .ok_or_else(|| format!("unknown delimiter '{}', use comma, semicolon or tab", key))
+    }
+
+    /// Key of the current delimiter, `comma` if it isn't a supported one.
+    pub fn delimiter_key(&self) -> &'static str {
+        Self::DELIMITERS
+            .iter()
+            .find(|(_, _, c)| *c == self.delimiter)
+            .map_or("comma", |(k, _, _)| k)
+    }
+
+    pub fn line_ending(&self) -> &'static str {
+        if self.crlf { "\r\n" } else { "\n" }
+    }
+}
+
+/// `value` as a CSV field: wrapped in double quotes, with inner quotes
+/// doubled, when it holds the delimiter, a quote or a line break.
+pub fn csv_field(value: &str, delimiter: char) -> Cow<'_, str> {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn csv_line(
+    csv: &mut String,
+    fields: impl IntoIterator<Item = impl AsRef<str>>,
+    options: &CsvOptions,
+) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            csv.push(options.delimiter);
+        }
+        csv.push_str(&csv_field(field.as_ref(), options.delimiter));
+    }
+    csv.push_str(options.line_ending());
+}
+
+/// An RFC 4180 CSV file from header cells and rows of fields.
+pub fn write_csv(
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+    options: &CsvOptions,
+) -> String {
+    let mut csv = csv_head(headers, options);
+    for fields in rows {
+        csv_line(&mut csv, &fields, options);
+    }
+    csv
+}
+
+/// The byte-order mark and header line a CSV file starts with, where the
+/// options ask for them.
+fn csv_head(headers: &[&str], options: &CsvOptions) -> String {
+    let mut csv = String::new();
+    if options.bom {
+        csv.push('\u{feff}');
+    }
+    if options.header {
+        csv_line(&mut csv, headers, options);
+    }
+    csv
+}
+
+/// Header and rows of an RFC 4180 CSV file, with the layout it was
+/// written in: the delimiter (the most common of comma, semicolon and tab
+/// on the first line), byte-order mark and line endings. Quoted fields may
+/// hold delimiters, doubled quotes and line breaks; blank lines are skipped.
+pub fn read_csv(text: &str) -> (Vec<String>, Vec<Vec<String>>, CsvOptions) {
+    let bom = text.starts_with('\u{feff}');
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = CsvOptions::DELIMITERS
+        .iter()
+        .map(|(_, _, d)| *d)
+        .max_by_key(|d| first_line.matches(*d).count())
+        .filter(|d| first_line.contains(*d))
+        .unwrap_or(',');
+    let options = CsvOptions {
+        delimiter,
+        header: true,
+        bom,
+        crlf: text.contains("\r\n"),
+    };
+
+    let mut lines = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                lines.push(std::mem::take(&mut fields));
+            }
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        lines.push(fields);
+    }
+    lines.retain(|fields| !(fields.len() == 1 && fields[0].trim().is_empty()));
+    let mut lines = lines.into_iter();
+    let header = lines.next().unwrap_or_default();
+    (header, lines.collect(), options)
+}
+
+pub(crate) fn has_defects<T: Record>(rows: &[T]) -> bool {
+    rows.iter().any(|r| r.defect().is_some())
+}
+
+fn csv_headers<T: Record>(rows: &[T]) -> Vec<&'static str> {
+    let mut headers = T::CSV_HEADERS.to_vec();
+    if has_defects(rows) {
+        headers.push("Defect");
+    }
+    headers
+}
+
+/// `row`'s CSV fields, plus its defect label when the export has that column.
+fn csv_row<T: Record>(row: &T, defects: bool) -> Vec<String> {
+    labelled(row, row.csv_fields(), defects)
+}
+
+fn labelled<T: Record>(row: &T, mut fields: Vec<String>, defects: bool) -> Vec<String> {
+    if defects {
+        fields.push(row.defect().map(Defect::label).unwrap_or("").to_string());
+    }
+    fields
+}
+
+pub fn to_csv<T: Record>(rows: &[T], options: &CsvOptions) -> String {
+    let defects = has_defects(rows);
+    write_csv(
+        &csv_headers(rows),
+        rows.iter().map(|r| csv_row(r, defects)),
+        options,
+    )
+}
+
+/// IBAN CSV honouring the "Spaces" toggle instead of always using
+/// [`IbanRow::formatted`].
+pub fn ibans_csv(rows: &[IbanRow], spaces: bool, options: &CsvOptions) -> String {
+    let defects = has_defects(rows);
+    write_csv(
+        &csv_headers(rows),
+        rows.iter()
+            .map(|r| labelled(r, iban_csv_fields(r, spaces), defects)),
+        options,
+    )
+}
+
+/// An IBAN row's CSV fields, the IBAN grouped in blocks of four or not.
+pub fn iban_csv_fields(row: &IbanRow, spaces: bool) -> Vec<String> {
+    let mut fields = row.csv_fields();
+    fields[0] = row.display(spaces).to_string();
+    fields
+}
+
+pub fn to_json<T: Serialize>(rows: &[T]) -> String {
+    serde_json::to_string_pretty(rows).unwrap_or_default()
+}
+
+/// `row` as an element of a [`to_json`] array, indented one level.
+fn json_item<T: Serialize>(row: &T) -> String {
+    let json = serde_json::to_string_pretty(row).unwrap_or_default();
+    let lines: Vec<String> = json.lines().map(|line| format!("  {}", line)).collect();
+    lines.join("\n")
+}
+
+/// Database an SQL export is written for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dialect {
+    #[default]
+    Postgres,
+    MySql,
+    Sqlite,
+    SqlServer,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 4] = [
+        Dialect::Postgres,
+        Dialect::MySql,
+        Dialect::Sqlite,
+        Dialect::SqlServer,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Dialect::Postgres => "postgres",
+            Dialect::MySql => "mysql",
+            Dialect::Sqlite => "sqlite",
+            Dialect::SqlServer => "sqlserver",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Dialect::Postgres => "PostgreSQL",
+            Dialect::MySql => "MySQL",
+            Dialect::Sqlite => "SQLite",
+            Dialect::SqlServer => "SQL Server",
+        }
+    }
+
+    pub fn parse(key: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.key().eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                format!(
+                    "unknown SQL dialect '{}', use postgres, mysql, sqlite or sqlserver",
+                    key
+                )
+            })
+    }
+
+    /// `COPY` or `LOAD DATA`, for the dialects with a bulk load a script can use.
+    pub fn load_statement(self) -> Option<&'static str> {
+        match self {
+            Dialect::Postgres => Some("COPY"),
+            Dialect::MySql => Some("LOAD DATA"),
+            Dialect::Sqlite | Dialect::SqlServer => None,
+        }
+    }
+
+    /// `name` as a quoted identifier.
+    pub fn identifier(self, name: &str) -> String {
+        match self {
+            Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+            Dialect::SqlServer => format!("[{}]", name.replace(']', "]]")),
+            Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    pub fn column_type(self, ty: SqlType) -> String {
+        match (ty, self) {
+            (SqlType::Text, Dialect::SqlServer) => "NVARCHAR(MAX)".to_string(),
+            (SqlType::Text, _) => "TEXT".to_string(),
+            (SqlType::VarChar(n), Dialect::SqlServer) => format!("NVARCHAR({})", n),
+            (SqlType::VarChar(n), _) => format!("VARCHAR({})", n),
+            (SqlType::Char(n), _) => format!("CHAR({})", n),
+            (SqlType::Date, _) => "DATE".to_string(),
+            (SqlType::Boolean, Dialect::SqlServer) => "BIT".to_string(),
+            (SqlType::Boolean, _) => "BOOLEAN".to_string(),
+        }
+    }
+
+    /// `value` as a literal. MySQL also escapes backslashes, as it reads
+    /// them as escapes inside strings by default.
+    pub fn literal(self, value: &SqlValue) -> String {
+        match (value, self) {
+            (SqlValue::Null, _) => "NULL".to_string(),
+            (SqlValue::Bool(b), Dialect::Postgres | Dialect::MySql) => {
+                if *b { "TRUE" } else { "FALSE" }.to_string()
+            }
+            (SqlValue::Bool(b), _) => if *b { "1" } else { "0" }.to_string(),
+            (SqlValue::Text(s) | SqlValue::Date(s), Dialect::MySql) => {
+                format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+            }
+            (SqlValue::Text(s), Dialect::SqlServer) => format!("N'{}'", s.replace('\'', "''")),
+            (SqlValue::Text(s) | SqlValue::Date(s), _) => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+}
+
+/// Column type of an exported field, spelled per [`Dialect`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SqlType {
+    Text,
+    VarChar(u16),
+    Char(u16),
+    Date,
+    Boolean,
+}
+
+/// One field of a row for an SQL export.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Text(String),
+    /// `YYYY-MM-DD`.
+    Date(String),
+    Bool(bool),
+    Null,
+}
+
+/// How [`to_sql`] writes a script. The default is PostgreSQL with one
+/// `INSERT` per row into the row type's own table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlOptions {
+    pub dialect: Dialect,
+    /// Table to create and fill; empty for the row type's [`Record::TABLE`].
+    pub table: String,
+    /// Rows per `INSERT` statement.
+    pub batch: u32,
+    /// Bulk-load the rows with the dialect's [`Dialect::load_statement`]
+    /// instead of inserting them; ignored where there is none.
+    pub load: bool,
+}
+
+impl Default for SqlOptions {
+    fn default() -> Self {
+        Self {
+            dialect: Dialect::Postgres,
+            table: String::new(),
+            batch: 1,
+            load: false,
+        }
+    }
+}
+
+impl SqlOptions {
+    /// The chosen table name, or `default` if none was.
+    pub fn table<'a>(&'a self, default: &'a str) -> &'a str {
+        let table = self.table.trim();
+        if table.is_empty() { default } else { table }
+    }
+
+    /// Whether rows are bulk-loaded rather than inserted.
+    pub fn loads(&self) -> bool {
+        self.load && self.dialect.load_statement().is_some()
+    }
+
+    /// Whether the rows go in a separate file, see [`load_data`]: MySQL's
+    /// `LOAD DATA` can only read them from one.
+    pub fn separate_data(&self) -> bool {
+        self.loads() && self.dialect == Dialect::MySql
+    }
+
+    /// File the MySQL `LOAD DATA` statement reads its rows from.
+    pub fn data_file(&self, default: &str) -> String {
+        format!("{}.tsv", self.table(default))
+    }
+
+    /// Rows per `INSERT`, within SQL Server's limit of 1000 per `VALUES` list.
+    fn rows_per_insert(&self) -> usize {
+        let limit = if self.dialect == Dialect::SqlServer {
+            1000
+        } else {
+            u32::MAX
+        };
+        self.batch.clamp(1, limit) as usize
+    }
+}
+
+/// `row`'s SQL values, plus its defect key when the export has that column.
+pub(crate) fn sql_row<T: Record>(row: &T, defects: bool) -> Vec<SqlValue> {
+    let mut values = row.sql_values();
+    if defects {
+        values.push(row.defect().map_or(SqlValue::Null, |d| text(d.key())));
+    }
+    values
+}
+
+/// [`Record::SQL_COLUMNS`], plus the defect column when the export has it.
+pub(crate) fn sql_columns<T: Record>(defects: bool) -> Vec<(&'static str, SqlType)> {
+    let mut columns = T::SQL_COLUMNS.to_vec();
+    if defects {
+        columns.push(("defect", SqlType::VarChar(32)));
+    }
+    columns
+}
+
+/// SQL script creating and filling the table; a seed is recorded as a
+/// leading comment so the file says how to regenerate it. Bulk-loaded
+/// PostgreSQL rows follow a `COPY ... FROM STDIN` in the script itself,
+/// while MySQL's `LOAD DATA` reads them from [`load_data`]'s file.
+pub fn to_sql<T: Record>(rows: &[T], seed: Option<u64>, options: &SqlOptions) -> String {
+    let defects = has_defects(rows);
+    let values: Vec<Vec<SqlValue>> = rows.iter().map(|r| sql_row(r, defects)).collect();
+    write_sql(
+        T::TABLE,
+        &sql_table_columns::<T>(defects),
+        &values,
+        seed,
+        options,
+    )
+}
+
+/// The columns [`to_sql`] creates, widened to hold near-miss values.
+fn sql_table_columns<T: Record>(defects: bool) -> Vec<(&'static str, SqlType)> {
+    sql_columns::<T>(defects)
+        .into_iter()
+        .map(|(column, ty)| match ty {
+            // Impossible dates of near-miss rows would be rejected by a DATE
+            // column, and a wrong-length value can be a character too long.
+            SqlType::Date if defects => (column, SqlType::VarChar(10)),
+            SqlType::Char(n) | SqlType::VarChar(n) if defects && column != "defect" => {
+                (column, SqlType::VarChar(n + 1))
+            }
+            ty => (column, ty),
+        })
+        .collect()
+}
+
+/// [`to_sql`] for any table: `columns` are `(name, type)` pairs and each
+/// row holds one value per column. `default_table` is the name used when
+/// the options don't set one.
+pub fn write_sql(
+    default_table: &str,
+    columns: &[(&str, SqlType)],
+    rows: &[Vec<SqlValue>],
+    seed: Option<u64>,
+    options: &SqlOptions,
+) -> String {
+    let mut sql = sql_head(default_table, columns, seed, options);
+    if options.separate_data() {
+        // The rows go in load_data's file.
+    } else if options.loads() {
+        sql.push_str(&write_load_data(rows));
+        sql.push_str("\\.\n");
+    } else {
+        let insert = insert_into(default_table, columns, options);
+        let tuples: Vec<String> = rows
+            .iter()
+            .map(|row| sql_tuple(row, options.dialect))
+            .collect();
+        for batch in tuples.chunks(options.rows_per_insert()) {
+            sql.push_str(&insert_statement(&insert, batch));
+        }
+    }
+    sql
+}
+
+/// What comes before the rows of a [`write_sql`] script: the seed comment,
+/// the `CREATE TABLE` and, for bulk loads, the statement reading the rows.
+fn sql_head(
+    default_table: &str,
+    columns: &[(&str, SqlType)],
+    seed: Option<u64>,
+    options: &SqlOptions,
+) -> String {
+    let dialect = options.dialect;
+    let name = options.table(default_table);
+    let table = dialect.identifier(name);
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|(column, ty)| {
+            format!(
+                "{} {}",
+                dialect.identifier(column),
+                dialect.column_type(*ty)
+            )
+        })
+        .collect();
+    let definitions = definitions.join(", ");
+    let columns = column_list(columns, dialect);
+
+    let mut sql = seed
+        .map(|s| format!("-- MockBanker seed: {}\n", s))
+        .unwrap_or_default();
+    sql.push_str(&match dialect {
+        Dialect::SqlServer => format!(
+            "IF OBJECT_ID(N'{}', N'U') IS NULL CREATE TABLE {} ({});\n",
+            name.replace('\'', "''"),
+            table,
+            definitions
+        ),
+        _ => format!("CREATE TABLE IF NOT EXISTS {} ({});\n", table, definitions),
+    });
+    if options.separate_data() {
+        sql.push_str(&format!(
+            "LOAD DATA LOCAL INFILE {} INTO TABLE {} CHARACTER SET utf8mb4 ({});\n",
+            dialect.literal(&text(&options.data_file(default_table))),
+            table,
+            columns
+        ));
+    } else if options.loads() {
+        sql.push_str(&format!("COPY {} ({}) FROM STDIN;\n", table, columns));
+    }
+    sql
+}
+
+fn column_list(columns: &[(&str, SqlType)], dialect: Dialect) -> String {
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|(column, _)| dialect.identifier(column))
+        .collect();
+    columns.join(", ")
+}
+
+/// `INSERT INTO table (columns) VALUES`, to be followed by a batch of
+/// [`sql_tuple`]s.
+fn insert_into(default_table: &str, columns: &[(&str, SqlType)], options: &SqlOptions) -> String {
+    let dialect = options.dialect;
+    format!(
+        "INSERT INTO {} ({}) VALUES",
+        dialect.identifier(options.table(default_table)),
+        column_list(columns, dialect)
+    )
+}
+
+fn sql_tuple(row: &[SqlValue], dialect: Dialect) -> String {
+    let values: Vec<String> = row.iter().map(|v| dialect.literal(v)).collect();
+    format!("({})", values.join(", "))
+}
+
+fn insert_statement(insert: &str, batch: &[String]) -> String {
+    let separator = if batch.len() == 1 { " " } else { "\n" };
+    format!("{}{}{};\n", insert, separator, batch.join(",\n"))
+}
+
+/// Rows as tab-separated text in the form PostgreSQL's `COPY` and MySQL's
+/// `LOAD DATA` read by default: backslash escapes, `\N` for NULL and 1/0
+/// for booleans.
+pub fn load_data<T: Record>(rows: &[T]) -> String {
+    let defects = has_defects(rows);
+    let values: Vec<Vec<SqlValue>> = rows.iter().map(|r| sql_row(r, defects)).collect();
+    write_load_data(&values)
+}
+
+/// [`load_data`] for rows of values.
+pub fn write_load_data(rows: &[Vec<SqlValue>]) -> String {
+    let mut data = String::new();
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                SqlValue::Null => "\\N".to_string(),
+                SqlValue::Bool(b) => if *b { "1" } else { "0" }.to_string(),
+                SqlValue::Text(s) | SqlValue::Date(s) => s
+                    .replace('\\', "\\\\")
+                    .replace('\t', "\\t")
+                    .replace('\n', "\\n")
+                    .replace('\r', "\\r"),
+            })
+            .collect();
+        data.push_str(&fields.join("\t"));
+        data.push('\n');
+    }
+    data
+}
+
+/// Formats beyond CSV, JSON and SQL: `(key, label, MIME type)`, with the
+/// key doubling as the file extension.
+pub const MORE_FORMATS: [(&str, &str, &str); 5] = [
+    ("ndjson", "NDJSON", "application/x-ndjson"),
+    ("xml", "XML", "application/xml"),
+    ("yaml", "YAML", "application/yaml"),
+    (
+        "xlsx",
+        "XLSX",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("parquet", "Parquet", "application/vnd.apache.parquet"),
+];
+
+/// `rows` as a file in one of [`MORE_FORMATS`].
+pub fn to_bytes<T: Record>(rows: &[T], format: &str) -> Result<Vec<u8>, String> {
+    match format {
+        "ndjson" => Ok(to_ndjson(rows).into_bytes()),
+        "xml" => Ok(to_xml(rows).into_bytes()),
+        "yaml" => Ok(to_yaml(rows).into_bytes()),
+        "xlsx" => Ok(to_xlsx(rows)),
+        "parquet" => Ok(to_parquet(rows)),
+        other => Err(format!("unknown export format '{}'", other)),
+    }
+}
+
+/// Newline-delimited JSON: one compact object per line.
+pub fn to_ndjson<T: Record>(rows: &[T]) -> String {
+    rows.iter().map(ndjson_line).collect()
+}
+
+fn ndjson_line<T: Record>(row: &T) -> String {
+    serde_json::to_string(row).unwrap_or_default() + "\n"
+}
+
+/// An XML document with the table as root element and one `<row>` per
+/// row, holding an element per SQL column; NULLs are empty elements.
+pub fn to_xml<T: Record>(rows: &[T]) -> String {
+    let defects = has_defects(rows);
+    let columns = sql_columns::<T>(defects);
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{}>\n",
+        T::TABLE
+    );
+    for row in rows {
+        xml.push_str(&xml_row(&columns, sql_row(row, defects)));
+    }
+    xml.push_str(&format!("</{}>\n", T::TABLE));
+    xml
+}
+
+fn xml_row(columns: &[(&str, SqlType)], values: Vec<SqlValue>) -> String {
+    let mut xml = String::from("  <row>\n");
+    for ((name, _), value) in columns.iter().zip(values) {
+        xml.push_str(&match value {
+            SqlValue::Null => format!("    <{}/>\n", name),
+            SqlValue::Bool(b) => format!("    <{0}>{1}</{0}>\n", name, b),
+            SqlValue::Text(s) | SqlValue::Date(s) => {
+                format!("    <{0}>{1}</{0}>\n", name, escape(&s))
+            }
+        });
+    }
+    xml.push_str("  </row>\n");
+    xml
+}
+
+/// A YAML sequence of mappings keyed by SQL column. Strings are always
+/// double-quoted so codes like `0012` or `NO` stay strings.
+pub fn to_yaml<T: Record>(rows: &[T]) -> String {
+    if rows.is_empty() {
+        return "[]\n".to_string();
+    }
+    let defects = has_defects(rows);
+    let columns = sql_columns::<T>(defects);
+    rows.iter()
+        .map(|row| yaml_item(&columns, sql_row(row, defects)))
+        .collect()
+}
+
+fn yaml_item(columns: &[(&str, SqlType)], values: Vec<SqlValue>) -> String {
+    let mut yaml = String::new();
+    for (i, ((name, _), value)) in columns.iter().zip(values).enumerate() {
+        let value = match value {
+            SqlValue::Null => "null".to_string(),
+            SqlValue::Bool(b) => b.to_string(),
+            SqlValue::Text(s) | SqlValue::Date(s) => yaml_string(&s),
+        };
+        let indent = if i == 0 { "- " } else { "  " };
+        yaml.push_str(&format!("{}{}: {}\n", indent, name, value));
+    }
+    yaml
+}
+
+fn yaml_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// An Excel workbook with the CSV columns, every cell as text.
+pub fn to_xlsx<T: Record>(rows: &[T]) -> Vec<u8> {
+    let defects = has_defects(rows);
+    let fields: Vec<Vec<String>> = rows.iter().map(|r| csv_row(r, defects)).collect();
+    xlsx::workbook(T::TABLE, &csv_headers(rows), &fields)
+}
+
+/// A Parquet file with the SQL columns and their types.
+pub fn to_parquet<T: Record>(rows: &[T]) -> Vec<u8> {
+    let defects = has_defects(rows);
+    let values: Vec<Vec<SqlValue>> = rows.iter().map(|r| sql_row(r, defects)).collect();
+    parquet::file(&sql_columns::<T>(defects), &values)
+}
+
+/// Writes consecutive chunks of rows as one file, so a large run can be
+/// written out chunk by chunk with the same result as exporting every row
+/// at once. The first chunk brings the CSV byte-order mark and header, the
+/// SQL preamble, the JSON array or the XML root element, and decides
+/// whether the file has a Defect column; [`Stream::finish`] closes it.
+pub struct Stream {
+    format: String,
+    csv: CsvOptions,
+    sql: SqlOptions,
+    seed: Option<u64>,
+    /// Defect column, once the first chunk is in.
+    defects: Option<bool>,
+    rows: usize,
+    table: &'static str,
+    /// `INSERT INTO ... VALUES` of the table being written.
+    insert: String,
+    /// SQL rows still to be written, short of a full `INSERT` batch.
+    tuples: Vec<String>,
+}
+
+impl Stream {
+    /// `format` is `csv`, `json`, `sql`, `ndjson`, `xml` or `yaml`, written
+    /// with `csv` or `sql`; `seed` is recorded in SQL scripts as [`to_sql`]
+    /// does. MySQL `LOAD DATA` scripts can't be streamed, as their rows go
+    /// in a separate file.
+    pub fn new(format: &str, csv: &CsvOptions, sql: &SqlOptions, seed: Option<u64>) -> Self {
+        Self {
+            format: format.to_string(),
+            csv: csv.clone(),
+            sql: sql.clone(),
+            seed,
+            defects: None,
+            rows: 0,
+            table: "rows",
+            insert: String::new(),
+            tuples: Vec::new(),
+        }
+    }
+
+    /// The text continuing the file with `rows`.
+    pub fn push<T: Record>(&mut self, rows: &[T]) -> String {
+        self.push_with(rows, T::csv_fields)
+    }
+
+    /// [`Stream::push`] with `csv_fields` in place of [`Record::csv_fields`],
+    /// as [`ibans_csv`] writes IBANs.
+    pub fn push_with<T: Record>(
+        &mut self,
+        rows: &[T],
+        csv_fields: impl Fn(&T) -> Vec<String>,
+    ) -> String {
+        let mut text = String::new();
+        let defects = match self.defects {
+            Some(defects) => defects,
+            None => {
+                let defects = has_defects(rows);
+                self.defects = Some(defects);
+                self.table = T::TABLE;
+                text.push_str(&self.head::<T>(defects));
+                defects
+            }
+        };
+        let columns = sql_columns::<T>(defects);
+        for row in rows {
+            match self.format.as_str() {
+                "json" => {
+                    text.push_str(if self.rows == 0 { "[\n" } else { ",\n" });
+                    text.push_str(&json_item(row));
+                }
+                "sql" if self.sql.separate_data() => {}
+                "sql" if self.sql.loads() => {
+                    text.push_str(&write_load_data(&[sql_row(row, defects)]));
+                }
+                "sql" => {
+                    self.tuples
+                        .push(sql_tuple(&sql_row(row, defects), self.sql.dialect));
+                    if self.tuples.len() == self.sql.rows_per_insert() {
+                        text.push_str(&insert_statement(&self.insert, &self.tuples));
+                        self.tuples.clear();
+                    }
+                }
+                "ndjson" => text.push_str(&ndjson_line(row)),
+                "xml" => text.push_str(&xml_row(&columns, sql_row(row, defects))),
+                "yaml" => text.push_str(&yaml_item(&columns, sql_row(row, defects))),
+                _ => csv_line(
+                    &mut text,
+                    labelled(row, csv_fields(row), defects),
+                    &self.csv,
+                ),
+            }
+            self.rows += 1;
+        }
+        text
+    }
+
+    fn head<T: Record>(&mut self, defects: bool) -> String {
+        match self.format.as_str() {
+            "sql" => {
+                let columns = sql_table_columns::<T>(defects);
+                self.insert = insert_into(T::TABLE, &columns, &self.sql);
+                sql_head(T::TABLE, &columns, self.seed, &self.sql)
+            }
+            "xml" => format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{}>\n",
+                T::TABLE
+            ),
+            "json" | "ndjson" | "yaml" => String::new(),
+            _ => {
+                let mut headers = T::CSV_HEADERS.to_vec();
+                if defects {
+                    headers.push("Defect");
+                }
+                csv_head(&headers, &self.csv)
+            }
+        }
+    }
+
+    /// What closes the file once every chunk is pushed.
+    pub fn finish(&mut self) -> String {
+        match self.format.as_str() {
+            "json" if self.rows == 0 => "[]".to_string(),
+            "json" => "\n]".to_string(),
+            "yaml" if self.rows == 0 => "[]\n".to_string(),
+            "xml" if self.defects.is_some() => format!("</{}>\n", self.table),
+            "sql" if self.defects.is_none() || self.sql.separate_data() => String::new(),
+            "sql" if self.sql.loads() => "\\.\n".to_string(),
+            "sql" if !self.tuples.is_empty() => {
+                let text = insert_statement(&self.insert, &self.tuples);
+                self.tuples.clear();
+                text
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+/// IBAN parts in [`IbanRow`] export column order, after the IBAN itself.
+fn iban_parts(parts: &IbanParts) -> [&str; 6] {
+    [
+        &parts.country,
+        &parts.check_digits,
+        &parts.bank_code,
+        &parts.branch_code,
+        &parts.account_number,
+        &parts.national_check,
+    ]
+}
+
+impl Record for IbanRow {
+    const TABLE: &'static str = "ibans";
+    const NAME: &'static str = "Iban";
+    const CSV_HEADERS: &'static [&'static str] = &[
+        "IBAN",
+        "Country",
+        "Check Digits",
+        "Bank Code",
+        "Branch Code",
+        "Account Number",
+        "National Check",
+        "Valid",
+    ];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("iban", SqlType::Char(34)),
+        ("country", SqlType::Char(2)),
+        ("check_digits", SqlType::Char(2)),
+        ("bank_code", SqlType::VarChar(34)),
+        ("branch_code", SqlType::VarChar(34)),
+        ("account_number", SqlType::VarChar(34)),
+        ("national_check", SqlType::VarChar(34)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        let mut fields = vec![self.formatted.clone()];
+        fields.extend(iban_parts(&self.parts).map(str::to_string));
+        fields.push(yes_no(self.valid));
+        fields
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        let mut values = vec![text(&self.raw)];
+        values.extend(iban_parts(&self.parts).map(text));
+        values.push(SqlValue::Bool(self.valid));
+        values
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for IdRow {
+    const TABLE: &'static str = "personal_ids";
+    const NAME: &'static str = "PersonalId";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Gender", "Date of Birth", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(32)),
+        ("gender", SqlType::VarChar(16)),
+        ("dob", SqlType::Date),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.gender.clone(),
+            self.dob.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.gender),
+            date(&self.dob),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for BankAccountRow {
+    const TABLE: &'static str = "bank_accounts";
+    const NAME: &'static str = "BankAccount";
+    const CSV_HEADERS: &'static [&'static str] = &["Account", "Routing", "IBAN", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("account", SqlType::VarChar(34)),
+        ("routing", SqlType::VarChar(34)),
+        ("iban", SqlType::Char(34)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.account.clone(),
+            self.routing.clone(),
+            self.iban.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.account),
+            text(&self.routing),
+            text(&self.iban),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for CreditCardRow {
+    const TABLE: &'static str = "credit_cards";
+    const NAME: &'static str = "CreditCard";
+    const CSV_HEADERS: &'static [&'static str] = &["Number", "Brand", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("number", SqlType::VarChar(19)),
+        ("brand", SqlType::VarChar(32)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![self.number.clone(), self.brand.clone(), yes_no(self.valid)]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.number),
+            text(&self.brand),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for SwiftRow {
+    const TABLE: &'static str = "swift_codes";
+    const NAME: &'static str = "SwiftCode";
+    const CSV_HEADERS: &'static [&'static str] =
+        &["SWIFT/BIC", "Bank", "Country", "Location", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(11)),
+        ("bank", SqlType::Text),
+        ("country", SqlType::Text),
+        ("location", SqlType::Text),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.bank.clone(),
+            self.country.clone(),
+            self.location.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.bank),
+            text(&self.country),
+            text(&self.location),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for CompanyIdRow {
+    const TABLE: &'static str = "company_ids";
+    const NAME: &'static str = "CompanyId";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Name", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(32)),
+        ("name", SqlType::Text),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![self.code.clone(), self.name.clone(), yes_no(self.valid)]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.name),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for DriverLicenseRow {
+    const TABLE: &'static str = "driver_licenses";
+    const NAME: &'static str = "DriverLicense";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Name", "Country", "State", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(32)),
+        ("name", SqlType::Text),
+        ("country", SqlType::Text),
+        ("state", SqlType::VarChar(32)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.name.clone(),
+            self.country.clone(),
+            self.state.clone().unwrap_or_default(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.name),
+            text(&self.country),
+            text(self.state.as_deref().unwrap_or("")),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for PassportRow {
+    const TABLE: &'static str = "passports";
+    const NAME: &'static str = "Passport";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Name", "Country", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(32)),
+        ("name", SqlType::Text),
+        ("country", SqlType::Text),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.name.clone(),
+            self.country.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.name),
+            text(&self.country),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for TaxIdRow {
+    const TABLE: &'static str = "tax_ids";
+    const NAME: &'static str = "TaxId";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Name", "Type", "Country", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(32)),
+        ("name", SqlType::Text),
+        ("holder_type", SqlType::VarChar(32)),
+        ("country", SqlType::Text),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.name.clone(),
+            self.holder_type.clone().unwrap_or_default(),
+            self.country.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.name),
+            text(self.holder_type.as_deref().unwrap_or("")),
+            text(&self.country),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for VatRow {
+    const TABLE: &'static str = "vat_numbers";
+    const NAME: &'static str = "VatNumber";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "Country Code", "Country Name", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(20)),
+        ("country_code", SqlType::Char(2)),
+        ("country_name", SqlType::Text),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.country_code.clone(),
+            self.country_name.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.country_code),
+            text(&self.country_name),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for LeiRow {
+    const TABLE: &'static str = "lei_codes";
+    const NAME: &'static str = "LeiCode";
+    const CSV_HEADERS: &'static [&'static str] = &["Code", "LOU", "Country", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::Char(20)),
+        ("lou", SqlType::Text),
+        ("country_code", SqlType::Char(2)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.code.clone(),
+            self.lou.clone(),
+            self.country_code.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.lou),
+            text(&self.country_code),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for PersonRow {
+    const TABLE: &'static str = "people";
+    const NAME: &'static str = "Person";
+    const CSV_HEADERS: &'static [&'static str] = &[
+        "Country",
+        "Gender",
+        "Date of Birth",
+        "Personal ID",
+        "Passport",
+        "Driver's License",
+        "Tax ID",
+        "IBAN",
+        "Bank Account",
+        "Routing",
+        "Valid",
+    ];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("country", SqlType::Char(2)),
+        ("gender", SqlType::VarChar(16)),
+        ("dob", SqlType::Date),
+        ("personal_id", SqlType::VarChar(32)),
+        ("passport", SqlType::VarChar(32)),
+        ("driver_license", SqlType::VarChar(32)),
+        ("tax_id", SqlType::VarChar(32)),
+        ("iban", SqlType::Char(34)),
+        ("bank_account", SqlType::VarChar(34)),
+        ("routing", SqlType::VarChar(34)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.country.clone(),
+            self.gender.clone(),
+            self.dob.clone(),
+            self.personal_id.clone(),
+            self.passport.clone(),
+            self.driver_license.clone(),
+            self.tax_id.clone(),
+            self.iban.clone(),
+            self.bank_account.clone(),
+            self.routing.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.country),
+            text(&self.gender),
+            date(&self.dob),
+            text(&self.personal_id),
+            text(&self.passport),
+            text(&self.driver_license),
+            text(&self.tax_id),
+            text(&self.iban),
+            text(&self.bank_account),
+            text(&self.routing),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+}
+
+impl Record for CompanyRow {
+    const TABLE: &'static str = "companies";
+    const NAME: &'static str = "Company";
+    const CSV_HEADERS: &'static [&'static str] = &[
+        "Country",
+        "Company ID",
+        "VAT",
+        "VAT Derived",
+        "LEI",
+        "BIC",
+        "IBAN",
+        "Valid",
+    ];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("country", SqlType::Char(2)),
+        ("company_id", SqlType::VarChar(32)),
+        ("vat", SqlType::VarChar(20)),
+        ("vat_derived", SqlType::Boolean),
+        ("lei", SqlType::Char(20)),
+        ("bic", SqlType::VarChar(11)),
+        ("iban", SqlType::Char(34)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.country.clone(),
+            self.company_id.clone(),
+            self.vat.clone(),
+            yes_no(self.vat_derived),
+            self.lei.clone(),
+            self.bic.clone(),
+            self.iban.clone(),
+            yes_no(self.valid),
+        ]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.country),
+            text(&self.company_id),
+            text(&self.vat),
+            SqlValue::Bool(self.vat_derived),
+            text(&self.lei),
+            text(&self.bic),
+            text(&self.iban),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+}
+
+impl Record for CreditorIdRow {
+    const TABLE: &'static str = "creditor_ids";
+    const NAME: &'static str = "CreditorId";
+    const CSV_HEADERS: &'static [&'static str] = &["Creditor ID", "Country", "Valid"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("code", SqlType::VarChar(35)),
+        ("country", SqlType::Char(2)),
+        ("valid", SqlType::Boolean),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![self.code.clone(), self.country.clone(), yes_no(self.valid)]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.code),
+            text(&self.country),
+            SqlValue::Bool(self.valid),
+        ]
+    }
+
+    fn defect(&self) -> Option<Defect> {
+        self.defect
+    }
+}
+
+impl Record for ValidationRow {
+    const TABLE: &'static str = "validations";
+    const NAME: &'static str = "Validation";
+    const CSV_HEADERS: &'static [&'static str] = &["Value", "Valid", "Reason"];
+    const SQL_COLUMNS: &'static [(&'static str, SqlType)] = &[
+        ("value", SqlType::Text),
+        ("valid", SqlType::Boolean),
+        ("reason", SqlType::Text),
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![self.value.clone(), yes_no(self.valid), self.reason.clone()]
+    }
+
+    fn sql_values(&self) -> Vec<SqlValue> {
+        vec![
+            text(&self.value),
+            SqlValue::Bool(self.valid),
+            text(&self.reason),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain", ','), "plain");
+        assert_eq!(csv_field("a,b", ','), "\"a,b\"");
+        assert_eq!(csv_field("a,b", ';'), "a,b");
+        assert_eq!(csv_field("a;b", ';'), "\"a;b\"");
+        assert_eq!(csv_field("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines", ','), "\"two\nlines\"");
+        assert_eq!(csv_field("cr\r", '\t'), "\"cr\r\"");
+    }
+
+    #[test]
+    fn write_csv_layout() {
+        let options = CsvOptions {
+            delimiter: ';',
+            header: false,
+            bom: true,
+            crlf: true,
+        };
+        let csv = write_csv(
+            &["a"],
+            vec![vec!["1".to_string(), "x;y".to_string()]].into_iter(),
+            &options,
+        );
+        assert_eq!(csv, "\u{feff}1;\"x;y\"\r\n");
+    }
+
+    fn iban_row(raw: &str) -> IbanRow {
+        IbanRow::new(raw.to_string(), raw.to_string(), true)
+    }
+
+    fn id_rows() -> Vec<IdRow> {
+        [
+            "85010112345",
+            "two\nlines, \"quoted\"",
+            "O'Brien\t\\",
+            "",
+            "90123",
+        ]
+        .iter()
+        .map(|code| IdRow {
+            code: code.to_string(),
+            gender: "female".to_string(),
+            dob: "1985-01-01".to_string(),
+            valid: true,
+            defect: None,
+        })
+        .collect()
+    }
+
+    fn streamed(rows: &[IdRow], format: &str, csv: &CsvOptions, sql: &SqlOptions) -> String {
+        let mut stream = Stream::new(format, csv, sql, Some(9));
+        let mut text = String::new();
+        for chunk in [&rows[..1], &rows[1..3], &rows[3..3], &rows[3..]] {
+            text.push_str(&stream.push(chunk));
+        }
+        text + &stream.finish()
+    }
+
+    #[test]
+    fn stream_matches_one_export() {
+        let rows = id_rows();
+        let csv = CsvOptions::default();
+        let sql = SqlOptions::default();
+        assert_eq!(streamed(&rows, "json", &csv, &sql), to_json(&rows));
+        assert_eq!(streamed(&rows, "ndjson", &csv, &sql), to_ndjson(&rows));
+        assert_eq!(streamed(&rows, "xml", &csv, &sql), to_xml(&rows));
+        assert_eq!(streamed(&rows, "yaml", &csv, &sql), to_yaml(&rows));
+        for options in [
+            CsvOptions::default(),
+            CsvOptions {
+                delimiter: ';',
+                header: false,
+                bom: true,
+                crlf: true,
+            },
+        ] {
+            assert_eq!(
+                streamed(&rows, "csv", &options, &sql),
+                to_csv(&rows, &options)
+            );
+        }
+        for options in [
+            SqlOptions::default(),
+            SqlOptions {
+                batch: 2,
+                ..SqlOptions::default()
+            },
+            SqlOptions {
+                dialect: Dialect::SqlServer,
+                batch: 10,
+                ..SqlOptions::default()
+            },
+            SqlOptions {
+                load: true,
+                ..SqlOptions::default()
+            },
+        ] {
+            assert_eq!(
+                streamed(&rows, "sql", &csv, &options),
+                to_sql(&rows, Some(9), &options)
+            );
+        }
+    }
+
+    #[test]
+    fn stream_of_no_rows() {
+        let none: &[IdRow] = &[];
+        for format in ["csv", "json", "ndjson", "xml", "yaml"] {
+            let mut stream =
+                Stream::new(format, &CsvOptions::default(), &SqlOptions::default(), None);
+            let text = stream.push(none) + &stream.finish();
+            assert_eq!(text, to_bytes_or_csv(none, format), "{format}");
+        }
+    }
+
+    fn to_bytes_or_csv(rows: &[IdRow], format: &str) -> String {
+        match format {
+            "csv" => to_csv(rows, &CsvOptions::default()),
+            "json" => to_json(rows),
+            other => String::from_utf8(to_bytes(rows, other).unwrap()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn sql_literals_per_dialect() {
+        let value = text("O'Brien \\ Co");
+        assert_eq!(Dialect::Postgres.literal(&value), "'O''Brien \\ Co'");
+        assert_eq!(Dialect::Sqlite.literal(&value), "'O''Brien \\ Co'");
+        assert_eq!(Dialect::MySql.literal(&value), "'O''Brien \\\\ Co'");
+        assert_eq!(Dialect::SqlServer.literal(&value), "N'O''Brien \\ Co'");
+
+        let date = SqlValue::Date("1990-01-31".to_string());
+        assert_eq!(Dialect::SqlServer.literal(&date), "'1990-01-31'");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Null), "NULL");
+        assert_eq!(Dialect::Postgres.literal(&SqlValue::Bool(true)), "TRUE");
+        assert_eq!(Dialect::MySql.literal(&SqlValue::Bool(false)), "FALSE");
+        assert_eq!(Dialect::Sqlite.literal(&SqlValue::Bool(true)), "1");
+        assert_eq!(Dialect::SqlServer.literal(&SqlValue::Bool(false)), "0");
+    }
+
+    #[test]
+    fn sql_identifiers_per_dialect() {
+        assert_eq!(Dialect::Postgres.identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(Dialect::Sqlite.identifier("ibans"), "\"ibans\"");
+        assert_eq!(Dialect::MySql.identifier("a`b"), "`a``b`");
+        assert_eq!(Dialect::SqlServer.identifier("a]b"), "[a]]b]");
+    }
+
+    #[test]
+    fn sql_batches_inserts() {
+        let rows = [
+            iban_row("DE89370400440532013000"),
+            iban_row("GB82WEST12345698765432"),
+            iban_row("FR1420041010050500013M02606"),
+        ];
+        let options = SqlOptions {
+            dialect: Dialect::SqlServer,
+            batch: 2,
+            ..SqlOptions::default()
+        };
+        let sql = to_sql(&rows, Some(7), &options);
+        assert!(sql.starts_with(
+            "-- MockBanker seed: 7\nIF OBJECT_ID(N'ibans', N'U') IS NULL CREATE TABLE [ibans] ("
+        ));
+        assert_eq!(sql.matches("INSERT INTO [ibans]").count(), 2);
+        assert!(sql.contains("N'DE89370400440532013000', N'DE'"));
+    }
+
+    #[test]
+    fn load_data_escapes() {
+        let row = IdRow {
+            code: "a\tb\\c\nd".to_string(),
+            gender: String::new(),
+            dob: String::new(),
+            valid: false,
+            defect: None,
+        };
+        assert_eq!(load_data(&[row]), "a\\tb\\\\c\\nd\t\t\\N\t0\n");
+    }
+
+    #[test]
+    fn csv_round_trips_through_read_csv() {
+        let headers = ["name", "note"];
+        let rows = vec![
+            vec!["Smith, John".to_string(), "said \"hi\"".to_string()],
+            vec!["multi\nline".to_string(), String::new()],
+            vec!["tab\there".to_string(), "semi;colon".to_string()],
+        ];
+        for (_, _, delimiter) in CsvOptions::DELIMITERS {
+            for (bom, crlf) in [(false, false), (true, true)] {
+                let options = CsvOptions {
+                    delimiter,
+                    header: true,
+                    bom,
+                    crlf,
+                };
+                let csv = write_csv(&headers, rows.clone().into_iter(), &options);
+                let (read_headers, read_rows, read_options) = read_csv(&csv);
+                assert_eq!(read_headers, headers);
+                assert_eq!(read_rows, rows);
+                assert_eq!(read_options, options);
+            }
+        }
+    }
+
+    #[test]
+    fn sql_widens_bounded_columns_for_near_miss_rows() {
+        let mut broken = iban_row("DE893704004405320130001");
+        broken.valid = false;
+        broken.defect = Some(crate::negative::Defect::Length);
+        let sql = to_sql(&[broken], None, &SqlOptions::default());
+        assert!(sql.contains("\"iban\" VARCHAR(35)"), "{}", sql);
+        assert!(sql.contains("\"check_digits\" VARCHAR(3)"), "{}", sql);
+        assert!(sql.contains("\"defect\" VARCHAR(32)"), "{}", sql);
+
+        let sql = to_sql(
+            &[iban_row("DE89370400440532013000")],
+            None,
+            &SqlOptions::default(),
+        );
+        assert!(sql.contains("\"iban\" CHAR(34)"), "{}", sql);
+    }
+}
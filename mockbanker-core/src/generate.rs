@@ -0,0 +1,513 @@
+//! Batch generators, one per tab.
+//!
+//! Each function runs the registry's `generate` call `count` times and maps
+//! the results into rows. Generation failures (unsupported country, no
+//! format for the requested options) are skipped, so fewer than `count` rows
+//! may come back.
+
+use idsmith::{
+    bank_account, company_id, credit_card, driver_license, iban, lei, passport, personal_id, swift,
+    tax_id, vat,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::rows::*;
+use crate::{anatomy, diagnose, seeded, sepa};
+
+/// Turns the seed field into a numeric seed.
+///
+/// Numbers are used as-is, any other text is hashed (FNV-1a) so memorable
+/// seeds like `"onboarding-regression"` work too, and an empty field draws
+/// a fresh random seed. Either way the result can be shared to regenerate
+/// the same rows.
+pub fn resolve_seed(input: &str) -> u64 {
+    let input = input.trim();
+    if input.is_empty() {
+        return rand::random();
+    }
+    if let Ok(n) = input.parse() {
+        return n;
+    }
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The RNG every generator is driven by; identical seeds give identical rows.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// `country` of `None` picks a random IBAN country for every row.
+pub fn ibans<R: Rng>(country: Option<&str>, count: u32, rng: &mut R) -> Vec<IbanRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        if let Ok(code) = iban::generate_iban(country, rng) {
+            let valid = iban::validate_iban(&code);
+            rows.push(IbanRow::new(code.clone(), iban::format_iban(&code), valid));
+        }
+    }
+    rows
+}
+
+/// Positions and characters to pin in a BBAN for the `role` (`b` or `s`)
+/// positions of `layout`, checked against `sample` so digits go where the
+/// country has digits and letters where it has letters.
+fn pinned(
+    country: &str,
+    layout: &str,
+    role: char,
+    label: &str,
+    value: &str,
+    sample: &str,
+) -> Result<Vec<(usize, char)>, String> {
+    let value: Vec<char> = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+    let positions: Vec<usize> = layout
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c == role)
+        .map(|(i, _)| i)
+        .collect();
+    if positions.is_empty() {
+        return Err(format!("{} IBANs have no {}", country, label));
+    }
+    if value.len() != positions.len() {
+        return Err(format!(
+            "{} {}s are {} characters, got {}",
+            country,
+            label,
+            positions.len(),
+            value.len()
+        ));
+    }
+    let sample: Vec<char> = sample.chars().collect();
+    positions
+        .into_iter()
+        .zip(value)
+        .enumerate()
+        .map(|(n, (i, c))| {
+            let ok = if sample[i].is_ascii_digit() {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphanumeric()
+            };
+            if ok {
+                Ok((i, c))
+            } else if sample[i].is_ascii_digit() {
+                Err(format!(
+                    "{} {}s are digits only; '{}' at position {}",
+                    country,
+                    label,
+                    c,
+                    n + 1
+                ))
+            } else {
+                Err(format!(
+                    "'{}' at position {} of the {} is not a letter or digit",
+                    c,
+                    n + 1,
+                    label
+                ))
+            }
+        })
+        .collect()
+}
+
+/// IBANs at one bank: `bank_code` (and `branch_code`, if not empty) stay
+/// fixed in the BBAN and only the account part is random. National check
+/// digits, where [`crate::diagnose`] knows them, and the IBAN check digits
+/// are recomputed. Errs with a message for the user if either code doesn't
+/// fit the country's layout.
+pub fn ibans_at_bank<R: Rng>(
+    country: &str,
+    bank_code: &str,
+    branch_code: &str,
+    count: u32,
+    rng: &mut R,
+) -> Result<Vec<IbanRow>, String> {
+    let layout = anatomy::layout(country)
+        .ok_or_else(|| format!("No bank code layout is known for {} IBANs", country))?;
+    // Only the layout's character classes matter, so the sample comes from a
+    // fixed RNG: `rng` then draws the same rows whether a run is split into
+    // chunks or not.
+    let sample = iban::generate_iban(Some(country), &mut seeded_rng(0))
+        .map_err(|_| format!("{} has no IBAN format", country))?;
+    let sample_bban = sample.get(4..).unwrap_or("");
+    if sample_bban.len() != layout.len() {
+        return Err(format!(
+            "No bank code layout is known for {} IBANs",
+            country
+        ));
+    }
+    let mut pins = pinned(country, layout, 'b', "bank code", bank_code, sample_bban)?;
+    pins.extend(pinned(
+        country,
+        layout,
+        's',
+        "branch code",
+        branch_code,
+        sample_bban,
+    )?);
+
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        // A few tries, as some accounts have no valid national check (NO).
+        for _ in 0..10 {
+            let Ok(code) = iban::generate_iban(Some(country), rng) else {
+                break;
+            };
+            let mut bban: Vec<char> = code.chars().skip(4).collect();
+            if bban.len() != layout.len() {
+                break;
+            }
+            for &(i, c) in &pins {
+                bban[i] = c;
+            }
+            let mut bban: String = bban.into_iter().collect();
+            if let Some((_, expected)) = diagnose::national_check(country, &bban) {
+                if !expected.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    continue;
+                }
+                let mut expected = expected.chars();
+                bban = layout
+                    .chars()
+                    .zip(bban.chars())
+                    .map(|(role, c)| match role {
+                        'k' => expected.next().unwrap_or(c),
+                        _ => c,
+                    })
+                    .collect();
+            }
+            let check = 98 - mod97(&format!("{}{}00", bban, country));
+            let code = format!("{}{:02}{}", country, check, bban);
+            let valid = iban::validate_iban(&code);
+            rows.push(IbanRow::new(code.clone(), iban::format_iban(&code), valid));
+            break;
+        }
+    }
+    Ok(rows)
+}
+
+/// Countries whose BBAN starts with the four-letter bank code of the BIC.
+const BIC_BANK_CODE_COUNTRIES: &[&str] = &[
+    "BG", "BH", "GB", "GI", "IE", "IQ", "JO", "KW", "LC", "LV", "MT", "MU", "NL", "PS", "QA", "RO",
+    "SC", "SV", "VG",
+];
+
+/// An IBAN held at the bank behind `bic`. Where the country's BBAN embeds
+/// the SWIFT bank code (NL, GB, IE, ...) it is rewritten to the BIC's and
+/// the check digits recomputed; elsewhere the two only share a country.
+/// `None` if the country has no IBAN format.
+pub fn iban_for_bic<R: Rng>(country: &str, bic: &str, rng: &mut R) -> Option<String> {
+    if !iban::supported_countries().contains(&country) {
+        return None;
+    }
+    let code = iban::generate_iban(Some(country), rng).ok()?;
+    if !BIC_BANK_CODE_COUNTRIES.contains(&country) || bic.len() < 4 {
+        return Some(code);
+    }
+    let bban = format!("{}{}", &bic[..4], &code[8..]);
+    let check = 98 - mod97(&format!("{}{}00", bban, country));
+    Some(format!("{}{:02}{}", country, check, bban))
+}
+
+/// ISO 7064 mod 97-10 over an alphanumeric string, letters as 10..=35.
+pub(crate) fn mod97(s: &str) -> u32 {
+    s.chars().fold(0, |acc, c| {
+        let v = c.to_digit(36).unwrap_or(0);
+        if v >= 10 {
+            (acc * 100 + v) % 97
+        } else {
+            (acc * 10 + v) % 97
+        }
+    })
+}
+
+/// Parses `"male"` / `"female"` into a gender filter; anything else means any.
+pub fn parse_gender(gender: &str) -> Option<personal_id::date::Gender> {
+    match gender {
+        "male" => Some(personal_id::date::Gender::Male),
+        "female" => Some(personal_id::date::Gender::Female),
+        _ => None,
+    }
+}
+
+/// Drawn through [`seeded::personal_id`]; `reg` parses each code into its
+/// gender and date of birth.
+pub fn personal_ids<R: Rng>(
+    reg: &personal_id::Registry,
+    country: &str,
+    gender: &str,
+    year: Option<u16>,
+    count: u32,
+    rng: &mut R,
+) -> Vec<IdRow> {
+    let opts = personal_id::GenOptions {
+        gender: parse_gender(gender),
+        year,
+    };
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        if let Some(code) = seeded::personal_id(country, &opts, rng)
+            && let Some(parsed) = reg.parse(country, &code)
+        {
+            rows.push(IdRow {
+                code: parsed.code,
+                gender: parsed.gender.unwrap_or_default(),
+                dob: parsed.dob.unwrap_or_default(),
+                valid: parsed.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+/// Drawn through [`seeded::bank_account`].
+pub fn bank_accounts<R: Rng>(country: &str, count: u32, rng: &mut R) -> Vec<BankAccountRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = bank_account::GenOptions::default();
+        if let Some(res) = seeded::bank_account(country, &opts, rng) {
+            let routing = res.bank_code.unwrap_or_default();
+            let iban = anatomy::iban_from_account(country, &routing, &res.account_number)
+                .unwrap_or_default();
+            rows.push(BankAccountRow {
+                account: res.account_number,
+                routing,
+                iban,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+/// `current_year` is the two-digit year used to pick future expiry dates.
+pub fn credit_cards<R: Rng>(
+    reg: &credit_card::Registry,
+    brand: &str,
+    current_year: u16,
+    count: u32,
+    rng: &mut R,
+) -> Vec<CreditCardRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = credit_card::GenOptions {
+            brand: Some(brand.to_string()),
+            current_year: Some(current_year),
+        };
+        if let Some(res) = reg.generate(&opts, rng) {
+            rows.push(CreditCardRow {
+                number: res.number,
+                brand: res.brand,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+pub fn swift_codes<R: Rng>(
+    reg: &swift::Registry,
+    country: &str,
+    count: u32,
+    rng: &mut R,
+) -> Vec<SwiftRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = swift::GenOptions {
+            country: Some(country.to_string()),
+        };
+        let res = reg.generate(&opts, rng);
+        rows.push(SwiftRow {
+            code: res.code,
+            bank: res.bank,
+            country: res.country,
+            location: res.location,
+            valid: res.valid,
+            defect: None,
+        });
+    }
+    rows
+}
+
+/// Drawn through [`seeded::company_id`].
+pub fn company_ids<R: Rng>(
+    reg: &company_id::Registry,
+    country: &str,
+    count: u32,
+    rng: &mut R,
+) -> Vec<CompanyIdRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        if let Some(res) = seeded::company_id(reg, country, rng) {
+            rows.push(CompanyIdRow {
+                code: res.code,
+                name: res.name,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+pub fn driver_licenses<R: Rng>(
+    reg: &driver_license::Registry,
+    country: &str,
+    state: Option<&str>,
+    count: u32,
+    rng: &mut R,
+) -> Vec<DriverLicenseRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = driver_license::GenOptions {
+            country: Some(country.to_string()),
+            state: state.map(str::to_string),
+        };
+        if let Some(res) = reg.generate(&opts, rng) {
+            rows.push(DriverLicenseRow {
+                code: res.code,
+                name: res.name,
+                country: format!("{} — {}", res.country_code, res.country_name),
+                state: res.state,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+pub fn passports<R: Rng>(
+    reg: &passport::Registry,
+    country: &str,
+    count: u32,
+    rng: &mut R,
+) -> Vec<PassportRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = passport::GenOptions {
+            country: Some(country.to_string()),
+        };
+        if let Some(res) = reg.generate(&opts, rng) {
+            rows.push(PassportRow {
+                code: res.code,
+                name: res.name,
+                country: format!("{} — {}", res.country_code, res.country_name),
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+/// `holder_type` of `None` lets the registry pick one, where the format
+/// distinguishes holders.
+pub fn tax_ids<R: Rng>(
+    reg: &tax_id::Registry,
+    country: &str,
+    holder_type: Option<&str>,
+    count: u32,
+    rng: &mut R,
+) -> Vec<TaxIdRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = tax_id::GenOptions {
+            country: Some(country.to_string()),
+            holder_type: holder_type.map(str::to_string),
+        };
+        if let Some(res) = reg.generate(&opts, rng) {
+            rows.push(TaxIdRow {
+                code: res.code,
+                name: res.name,
+                country: format!("{} — {}", res.country_code, res.country_name),
+                holder_type: res.holder_type,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+pub fn vat_numbers<R: Rng>(
+    reg: &vat::Registry,
+    country: &str,
+    count: u32,
+    rng: &mut R,
+) -> Vec<VatRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = vat::GenOptions {
+            country: Some(country.to_string()),
+        };
+        if let Some(res) = reg.generate(&opts, rng) {
+            rows.push(VatRow {
+                code: res.code,
+                country_code: res.country_code,
+                country_name: res.country_name,
+                valid: res.valid,
+                defect: None,
+            });
+        }
+    }
+    rows
+}
+
+/// `country` of `None` lets the registry pick a jurisdiction per row.
+pub fn leis<R: Rng>(
+    reg: &lei::Registry,
+    country: Option<&str>,
+    count: u32,
+    rng: &mut R,
+) -> Vec<LeiRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let opts = lei::GenOptions {
+            country: country.map(str::to_string),
+        };
+        let res = reg.generate(&opts, rng);
+        rows.push(LeiRow {
+            code: res.code,
+            lou: res.lou,
+            country_code: res.country_code,
+            valid: res.valid,
+            defect: None,
+        });
+    }
+    rows
+}
+
+/// SEPA Creditor Identifiers; `country` of `None` picks a random SEPA
+/// country for every row.
+pub fn creditor_ids<R: Rng>(country: Option<&str>, count: u32, rng: &mut R) -> Vec<CreditorIdRow> {
+    let mut rows = Vec::new();
+    for _ in 0..count {
+        let country = match country {
+            Some(c) => c,
+            None => sepa::SEPA_COUNTRIES[rng.gen_range(0..sepa::SEPA_COUNTRIES.len())],
+        };
+        if let Some(code) = sepa::creditor_identifier(country, rng) {
+            rows.push(CreditorIdRow {
+                valid: sepa::validate_creditor_identifier(&code).is_ok(),
+                code,
+                country: country.to_string(),
+                defect: None,
+            });
+        }
+    }
+    rows
+}
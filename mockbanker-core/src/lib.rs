@@ -0,0 +1,34 @@
+//! Headless core of MockBanker.
+//!
+//! Everything the web app does that is not rendering lives here: the row
+//! types shown in each tab, the calls into `idsmith` that generate and
+//! validate them, and the exporters behind the download buttons. Depending
+//! on this crate gives a test suite exactly the data the browser produces.
+
+pub mod anatomy;
+pub mod bulk;
+pub mod company;
+pub mod dataset;
+pub mod dates;
+pub mod diagnose;
+pub mod export;
+pub mod generate;
+pub mod nacha;
+pub mod negative;
+pub mod parquet;
+pub mod person;
+pub mod pseudonymize;
+pub mod rows;
+pub mod seeded;
+pub mod sepa;
+pub mod snippet;
+pub mod statement;
+pub mod validate;
+pub mod xlsx;
+
+pub use company::{CompanyGenerator, CompanyRow};
+pub use export::Record;
+pub use idsmith;
+pub use person::{PersonGenerator, PersonRow};
+pub use rows::*;
+pub use validate::Validator;
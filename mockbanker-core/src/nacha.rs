@@ -0,0 +1,349 @@
+//! US ACH files in the NACHA format, built around generated routing and
+//! account numbers.
+//!
+//! Every line is a fixed-width 94-character record: a file header, then per
+//! batch a header, its entry details and a control record carrying the
+//! entry hash and totals, then the file control record. The file is padded
+//! with lines of `9` to a multiple of ten records, as the blocking factor
+//! requires.
+
+use idsmith::bank_account;
+use rand::Rng;
+
+use crate::dates;
+
+/// Standard Entry Class codes, as `(code, description)`.
+pub const SEC_CODES: &[(&str, &str)] = &[
+    ("PPD", "Prearranged Payment and Deposit"),
+    ("CCD", "Corporate Credit or Debit"),
+    ("WEB", "Internet-Initiated Entry"),
+];
+
+/// Batch service class codes, as `(code, description)`.
+pub const SERVICE_CLASSES: &[(u16, &str)] = &[
+    (200, "Mixed debits and credits"),
+    (220, "Credits only"),
+    (225, "Debits only"),
+];
+
+/// Options for [`ach_file`]. Amounts are in cents.
+#[derive(Clone, Debug)]
+pub struct AchOptions {
+    pub batches: u32,
+    /// Entry detail records per batch.
+    pub entries: u32,
+    /// One of [`SEC_CODES`]; `None` draws one per batch.
+    pub sec_code: Option<String>,
+    /// One of [`SERVICE_CLASSES`].
+    pub service_class: u16,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    /// Effective entry date, `YYYY-MM-DD`; the file is created the day
+    /// before.
+    pub effective_date: String,
+}
+
+impl Default for AchOptions {
+    fn default() -> Self {
+        Self {
+            batches: 1,
+            entries: 5,
+            sec_code: None,
+            service_class: 200,
+            min_amount: 100,
+            max_amount: 100_000,
+            effective_date: "2025-01-02".to_string(),
+        }
+    }
+}
+
+const FIRST_NAMES: &[&str] = &[
+    "ALEX", "JORDAN", "TAYLOR", "MORGAN", "CASEY", "RILEY", "JAMIE", "AVERY", "QUINN", "DREW",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "SMITH", "JOHNSON", "WILLIAMS", "BROWN", "JONES", "GARCIA", "MILLER", "DAVIS", "LOPEZ",
+    "WILSON",
+];
+
+const COMPANIES: &[&str] = &[
+    "NORTHWIND TRADERS",
+    "CONTOSO LTD",
+    "FABRIKAM INC",
+    "TAILSPIN TOYS",
+    "WIDE WORLD IMPORTERS",
+    "ADVENTURE WORKS",
+    "PROSEWARE INC",
+    "LITWARE INC",
+];
+
+/// Alphanumeric field: upper case, left-justified, space-padded and cut to
+/// `width`.
+fn alpha(value: &str, width: usize) -> String {
+    let value: String = value.to_uppercase().chars().take(width).collect();
+    format!("{:<width$}", value, width = width)
+}
+
+/// Numeric field: right-justified and zero-padded; keeps the rightmost
+/// `width` digits, as the entry hash requires.
+fn numeric(value: u64, width: usize) -> String {
+    let digits = format!("{:0width$}", value, width = width);
+    digits[digits.len() - width..].to_string()
+}
+
+/// YYMMDD for days since 1970-01-01.
+fn yymmdd(days: i64) -> String {
+    let (year, month, day) = dates::civil_from_days(days);
+    format!("{:02}{:02}{:02}", year % 100, month, day)
+}
+
+/// A generated US account as `(routing, account)`.
+///
+/// Calls the US generator directly: the registry only accepts a thread RNG,
+/// which would make the file unreproducible from its seed.
+fn us_account<R: Rng>(rng: &mut R) -> Option<(String, String)> {
+    let res = bank_account::us::generate(&bank_account::GenOptions::default(), rng);
+    let routing = res.bank_code?;
+    (routing.len() == 9).then_some((routing, res.account_number))
+}
+
+/// Builds a NACHA ACH file, or `None` if `effective_date` isn't a valid
+/// `YYYY-MM-DD` date or no US routing number could be generated.
+///
+/// Each batch originates from one generated company and ODFI; each entry
+/// pays or debits a generated receiver's account. Mixed batches (service
+/// class 200) draw a credit or debit per entry.
+pub fn ach_file<R: Rng>(opts: &AchOptions, rng: &mut R) -> Option<String> {
+    let effective = dates::parse(&opts.effective_date)?;
+    let (min, max) = (
+        opts.min_amount.min(opts.max_amount),
+        opts.min_amount.max(opts.max_amount),
+    );
+    let (destination, _) = us_account(rng)?;
+    let (origin, _) = us_account(rng)?;
+
+    let mut lines = vec![format!(
+        "101 {} {}{}{}A094101{}{}{}",
+        destination,
+        origin,
+        yymmdd(effective - 1),
+        numeric(rng.gen_range(0..24) * 100 + rng.gen_range(0..60), 4),
+        alpha("MOCKBANKER FED RESERVE", 23),
+        alpha("MOCKBANKER ORIGIN", 23),
+        alpha("", 8),
+    )];
+
+    let mut file_entries = 0u64;
+    let mut file_hash = 0u64;
+    let mut file_debit = 0u64;
+    let mut file_credit = 0u64;
+    for batch in 1..=opts.batches as u64 {
+        let sec = match &opts.sec_code {
+            Some(code) => code.to_uppercase(),
+            None => SEC_CODES[rng.gen_range(0..SEC_CODES.len())].0.to_string(),
+        };
+        let company = COMPANIES[rng.gen_range(0..COMPANIES.len())];
+        let company_id = format!("1{}", numeric(rng.gen_range(0..1_000_000_000), 9));
+        let (odfi, _) = us_account(rng)?;
+        let odfi = &odfi[..8];
+        let description = match sec.as_str() {
+            "PPD" => "PAYROLL",
+            "WEB" => "WEBPAYMENT",
+            _ => "SUPPLIER",
+        };
+        lines.push(format!(
+            "5{}{}{}{}{}{}{}{}   1{}{}",
+            opts.service_class,
+            alpha(company, 16),
+            alpha("", 20),
+            company_id,
+            alpha(&sec, 3),
+            alpha(description, 10),
+            yymmdd(effective),
+            yymmdd(effective),
+            odfi,
+            numeric(batch, 7),
+        ));
+
+        let mut entries = 0u64;
+        let mut hash = 0u64;
+        let mut debit = 0u64;
+        let mut credit = 0u64;
+        for n in 1..=opts.entries as u64 {
+            let Some((routing, account)) = us_account(rng) else {
+                continue;
+            };
+            let is_debit = match opts.service_class {
+                220 => false,
+                225 => true,
+                _ => rng.gen_bool(0.5),
+            };
+            let savings = rng.gen_bool(0.2);
+            let code = match (savings, is_debit) {
+                (false, false) => 22,
+                (false, true) => 27,
+                (true, false) => 32,
+                (true, true) => 37,
+            };
+            let amount = rng.gen_range(min..=max);
+            if is_debit {
+                debit += amount;
+            } else {
+                credit += amount;
+            }
+            let receiver = if sec == "CCD" {
+                COMPANIES[rng.gen_range(0..COMPANIES.len())].to_string()
+            } else {
+                format!(
+                    "{} {}",
+                    FIRST_NAMES[rng.gen_range(0..FIRST_NAMES.len())],
+                    LAST_NAMES[rng.gen_range(0..LAST_NAMES.len())]
+                )
+            };
+            // WEB entries carry the payment type (S = single) instead.
+            let discretionary = if sec == "WEB" { "S " } else { "  " };
+            hash += routing[..8].parse::<u64>().unwrap_or(0);
+            entries += 1;
+            lines.push(format!(
+                "6{}{}{}{}{}{}{}{}0{}{}",
+                code,
+                &routing[..8],
+                &routing[8..],
+                alpha(&account, 17),
+                numeric(amount, 10),
+                alpha(&format!("ID{:06}", n), 15),
+                alpha(&receiver, 22),
+                discretionary,
+                odfi,
+                numeric(n, 7),
+            ));
+        }
+
+        lines.push(format!(
+            "8{}{}{}{}{}{}{}{}{}",
+            opts.service_class,
+            numeric(entries, 6),
+            numeric(hash, 10),
+            numeric(debit, 12),
+            numeric(credit, 12),
+            company_id,
+            alpha("", 25),
+            odfi,
+            numeric(batch, 7),
+        ));
+        file_entries += entries;
+        file_hash += hash;
+        file_debit += debit;
+        file_credit += credit;
+    }
+
+    // The control record and padding still to come count towards the blocks.
+    let records = lines.len() as u64 + 1;
+    let blocks = records.div_ceil(10);
+    lines.push(format!(
+        "9{}{}{}{}{}{}{}",
+        numeric(opts.batches as u64, 6),
+        numeric(blocks, 6),
+        numeric(file_entries, 8),
+        numeric(file_hash, 10),
+        numeric(file_debit, 12),
+        numeric(file_credit, 12),
+        alpha("", 39),
+    ));
+    while lines.len() % 10 != 0 {
+        lines.push("9".repeat(94));
+    }
+
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    fn file(seed: u64) -> String {
+        let opts = AchOptions {
+            batches: 3,
+            entries: 7,
+            ..AchOptions::default()
+        };
+        ach_file(&opts, &mut seeded_rng(seed)).unwrap()
+    }
+
+    fn number(line: &str, range: std::ops::Range<usize>) -> u64 {
+        line[range].parse().unwrap()
+    }
+
+    #[test]
+    fn records_are_94_characters_in_blocks_of_ten() {
+        let file = file(1);
+        let lines: Vec<&str> = file.lines().collect();
+        assert!(lines.iter().all(|l| l.len() == 94));
+        assert_eq!(lines.len() % 10, 0);
+        assert!(lines[0].starts_with("101 "));
+        let control = lines
+            .iter()
+            .rfind(|l| l.starts_with('9') && !l.starts_with("99"))
+            .unwrap();
+        assert_eq!(number(control, 1..7), 3);
+        assert_eq!(number(control, 7..13), lines.len() as u64 / 10);
+    }
+
+    #[test]
+    fn control_records_carry_entry_hash_and_totals() {
+        let file = file(2);
+        let (mut entries, mut hash, mut debit, mut credit) = (0, 0, 0, 0);
+        let (mut file_entries, mut file_hash) = (0, 0);
+        for line in file.lines() {
+            match &line[..1] {
+                "5" => (entries, hash, debit, credit) = (0, 0, 0, 0),
+                "6" => {
+                    let routing = &line[3..12];
+                    let weighted: u32 = routing
+                        .chars()
+                        .zip([3, 7, 1, 3, 7, 1, 3, 7, 1])
+                        .map(|(c, w)| c.to_digit(10).unwrap() * w)
+                        .sum();
+                    assert_eq!(weighted % 10, 0, "routing {}", routing);
+                    entries += 1;
+                    hash += number(line, 3..11);
+                    let amount = number(line, 29..39);
+                    match &line[1..3] {
+                        "27" | "37" => debit += amount,
+                        _ => credit += amount,
+                    }
+                }
+                "8" => {
+                    assert_eq!(number(line, 4..10), entries);
+                    assert_eq!(number(line, 10..20), hash % 10_000_000_000);
+                    assert_eq!(number(line, 20..32), debit);
+                    assert_eq!(number(line, 32..44), credit);
+                    file_entries += entries;
+                    file_hash += hash;
+                }
+                "9" if !line.starts_with("99") => {
+                    assert_eq!(number(line, 13..21), file_entries);
+                    assert_eq!(number(line, 21..31), file_hash % 10_000_000_000);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(file_entries, 21);
+    }
+
+    #[test]
+    fn same_seed_same_file() {
+        assert_eq!(file(3), file(3));
+        assert_ne!(file(3), file(4));
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        let opts = AchOptions {
+            effective_date: "2025-02-30".to_string(),
+            ..AchOptions::default()
+        };
+        assert_eq!(ach_file(&opts, &mut seeded_rng(1)), None);
+    }
+}
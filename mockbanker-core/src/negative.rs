@@ -0,0 +1,818 @@
+//! Near-miss data for negative tests: generated identifiers broken in one
+//! controlled way each, then re-validated so `valid` is truthfully `false`.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use idsmith::personal_id::checksum::{iso7064_mod11_2, luhn_check, verhoeff_check, weighted_check};
+
+use crate::generate::mod97;
+use crate::rows::*;
+use crate::validate::Validator;
+
+/// One way of breaking an otherwise valid identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Defect {
+    CheckDigit,
+    Transposition,
+    Length,
+    IllegalCharacter,
+    CountryPrefix,
+    ImpossibleDate,
+}
+
+impl Defect {
+    pub const ALL: [Defect; 6] = [
+        Defect::CheckDigit,
+        Defect::Transposition,
+        Defect::Length,
+        Defect::IllegalCharacter,
+        Defect::CountryPrefix,
+        Defect::ImpossibleDate,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Defect::CheckDigit => "Wrong check digit",
+            Defect::Transposition => "Transposed digits",
+            Defect::Length => "Wrong length",
+            Defect::IllegalCharacter => "Illegal character",
+            Defect::CountryPrefix => "Wrong country prefix",
+            Defect::ImpossibleDate => "Impossible date of birth",
+        }
+    }
+
+    /// Key used in exports, permalinks and the CLI, e.g. `check_digit`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Defect::CheckDigit => "check_digit",
+            Defect::Transposition => "transposition",
+            Defect::Length => "length",
+            Defect::IllegalCharacter => "illegal_character",
+            Defect::CountryPrefix => "country_prefix",
+            Defect::ImpossibleDate => "impossible_date",
+        }
+    }
+
+    /// Whether identifiers of validator `kind` can carry this defect: only
+    /// IBANs, VAT numbers, Creditor Identifiers and BICs embed a country
+    /// code, and only personal IDs a date of birth.
+    pub fn applies_to(self, kind: &str) -> bool {
+        match self {
+            Defect::CountryPrefix => prefix_at(kind).is_some(),
+            Defect::ImpossibleDate => kind == "id",
+            _ => true,
+        }
+    }
+}
+
+/// Defects named by `spec`: empty for valid data, `any` for all of them, or
+/// a comma-separated list of [`Defect::key`]s.
+pub fn parse(spec: &str) -> Result<Vec<Defect>, String> {
+    match spec.trim() {
+        "" => Ok(Vec::new()),
+        "any" => Ok(Defect::ALL.to_vec()),
+        spec => spec
+            .split(',')
+            .map(|key| {
+                let key = key.trim();
+                Defect::ALL
+                    .into_iter()
+                    .find(|d| d.key() == key)
+                    .ok_or_else(|| format!("unknown defect '{}'", key))
+            })
+            .collect(),
+    }
+}
+
+/// A generated row whose identifier can be broken.
+pub trait Breakable {
+    /// Key from [`crate::validate::KINDS`] used to re-validate the value.
+    const KIND: &'static str;
+
+    fn value(&self) -> &str;
+
+    /// Date of birth as `YYYY-MM-DD`, for [`Defect::ImpossibleDate`].
+    fn dob(&self) -> Option<&str> {
+        None
+    }
+
+    /// Replaces the identifier with `value`, broken by `defect`.
+    fn set_broken(&mut self, value: String, defect: Defect);
+}
+
+/// Country codes swapped in for [`Defect::CountryPrefix`]: other European
+/// countries, so the rest of the value no longer fits.
+const PREFIXES: &[&str] = &[
+    "AT", "BE", "DE", "ES", "FI", "FR", "GB", "IE", "IT", "LU", "NL", "PL", "PT", "SE",
+];
+
+/// Swapped in for Creditor Identifiers, which don't differ by country
+/// beyond their prefix: countries outside SEPA.
+const NON_SEPA_PREFIXES: &[&str] = &["AU", "BR", "CA", "IN", "JP", "US"];
+
+/// Swapped in for BICs, which don't differ by country beyond their
+/// prefix: codes ISO 3166 doesn't assign.
+const UNASSIGNED_PREFIXES: &[&str] = &["AA", "QM", "XA", "XB", "ZZ"];
+
+/// Characters no identifier format allows.
+const ILLEGAL: &[char] = &['#', '*', '!', '?', '@', '$', '%', '&'];
+
+/// Where the country code sits in identifiers of `kind`, if anywhere.
+fn prefix_at(kind: &str) -> Option<usize> {
+    match kind {
+        "iban" | "vat" | "creditor_id" => Some(0),
+        "swift" => Some(4),
+        _ => None,
+    }
+}
+
+/// Breaks each row with one of `defects`, taking them in turn so a batch
+/// covers every selected defect. A defect that doesn't apply to the kind
+/// or value, or leaves the value valid (a transposition the checksum can't
+/// see), is retried or replaced by the next one. Errs if some rows can't be
+/// broken by any of them, rather than returning fewer rows than asked for.
+/// Rows come back unchanged when `defects` is empty.
+pub fn break_rows<T: Breakable, R: Rng>(
+    rows: Vec<T>,
+    country: &str,
+    defects: &[Defect],
+    rng: &mut R,
+) -> Result<Vec<T>, String> {
+    if defects.is_empty() {
+        return Ok(rows);
+    }
+    let usable: Vec<Defect> = defects
+        .iter()
+        .copied()
+        .filter(|d| d.applies_to(T::KIND))
+        .collect();
+
+    let validator = Validator::new();
+    let total = rows.len();
+    let mut broken = Vec::new();
+    for (i, mut row) in rows.into_iter().enumerate() {
+        for attempt in 0..usable.len() * 3 {
+            let defect = usable[(i + attempt) % usable.len()];
+            let Some(value) = corrupt(T::KIND, country, row.value(), row.dob(), defect, rng) else {
+                continue;
+            };
+            if let Some((false, _)) = validator.validate(T::KIND, country, &value) {
+                row.set_broken(value, defect);
+                broken.push(row);
+                break;
+            }
+        }
+    }
+    if broken.len() < total {
+        let labels: Vec<&str> = defects.iter().map(|d| d.label()).collect();
+        return Err(format!(
+            "{} of {} rows can't be broken with: {}",
+            total - broken.len(),
+            total,
+            labels.join(", ")
+        ));
+    }
+    Ok(broken)
+}
+
+/// `value` with `defect` applied, or `None` if it can't carry it. Check
+/// digits covering the changed part are recomputed, so the value carries
+/// only that one defect.
+pub fn corrupt<R: Rng>(
+    kind: &str,
+    country: &str,
+    value: &str,
+    dob: Option<&str>,
+    defect: Defect,
+    rng: &mut R,
+) -> Option<String> {
+    let mut chars: Vec<char> = value.chars().collect();
+    // Leave a leading country code alone unless it is the defect.
+    let start = if prefix_at(kind) == Some(0) { 2 } else { 0 };
+    let body: Vec<usize> = (start..chars.len())
+        .filter(|&i| chars[i].is_ascii_alphanumeric())
+        .collect();
+
+    match defect {
+        Defect::CheckDigit => {
+            let i = match kind {
+                "iban" | "creditor_id" => 3,
+                _ => *body.last()?,
+            };
+            let c = *chars.get(i)?;
+            chars[i] = different(c, rng)?;
+        }
+        Defect::Transposition => {
+            let swappable: Vec<usize> = body
+                .windows(2)
+                .filter(|w| w[1] == w[0] + 1)
+                .map(|w| w[0])
+                .filter(|&i| {
+                    chars[i].is_ascii_digit()
+                        && chars[i + 1].is_ascii_digit()
+                        && chars[i] != chars[i + 1]
+                })
+                .collect();
+            let &i = swappable.choose(rng)?;
+            chars.swap(i, i + 1);
+        }
+        Defect::Length => {
+            let &i = body.choose(rng)?;
+            if rng.gen_bool(0.5) {
+                chars.remove(i);
+            } else {
+                chars.insert(i, char::from(b'0' + rng.gen_range(0..10)));
+            }
+        }
+        Defect::IllegalCharacter => {
+            let &i = body.choose(rng)?;
+            chars[i] = *ILLEGAL.choose(rng)?;
+        }
+        Defect::CountryPrefix => {
+            let at = prefix_at(kind)?;
+            let current: String = chars.get(at..at + 2)?.iter().collect();
+            let prefixes = match kind {
+                "creditor_id" => NON_SEPA_PREFIXES,
+                "swift" => UNASSIGNED_PREFIXES,
+                _ => PREFIXES,
+            };
+            let others: Vec<&&str> = prefixes.iter().filter(|p| **p != current).collect();
+            let prefix = others.choose(rng)?;
+            for (offset, c) in prefix.chars().enumerate() {
+                chars[at + offset] = c;
+            }
+            // Both checksums cover the country code.
+            let code: String = chars.iter().collect();
+            let check = match kind {
+                "iban" => 98 - mod97(&format!("{}{}00", code.get(4..)?, prefix)),
+                "creditor_id" => 98 - mod97(&format!("{}{}00", code.get(7..)?, prefix)),
+                _ => return Some(code),
+            };
+            return Some(format!("{}{:02}{}", prefix, check, code.get(4..)?));
+        }
+        Defect::ImpossibleDate => return impossible_date(country, value, dob?),
+    }
+    Some(chars.into_iter().collect())
+}
+
+/// A different character of the same class: digit for digit, letter for
+/// letter.
+fn different<R: Rng>(c: char, rng: &mut R) -> Option<char> {
+    let (base, span) = if c.is_ascii_digit() {
+        (b'0', 10)
+    } else if c.is_ascii_uppercase() {
+        (b'A', 26)
+    } else if c.is_ascii_lowercase() {
+        (b'a', 26)
+    } else {
+        return None;
+    };
+    let shift = rng.gen_range(1..span);
+    Some(char::from(base + (c as u8 - base + shift) % span))
+}
+
+/// Dates no calendar has, as `(month, day)`, tried in turn until the
+/// check digits of the moved date fit.
+const IMPOSSIBLE_DATES: [(&str, &str); 6] = [
+    ("02", "30"),
+    ("02", "31"),
+    ("04", "31"),
+    ("06", "31"),
+    ("09", "31"),
+    ("11", "31"),
+];
+
+/// Month offsets formats add for the century or gender: PESEL 20 from
+/// 2000, EGN 40, and the Czech and Slovak birth numbers 50 for women.
+const MONTH_OFFSETS: [u8; 5] = [0, 20, 40, 50, 70];
+
+/// Positions in `value` of the month and day digits of `dob`, and the
+/// offset its month is written with. The date is looked for among the
+/// digits as YYYYMMDD, DDMMYYYY, YYMMDD or DDMMYY.
+fn date_positions(value: &str, dob: &str) -> Option<([usize; 4], u8)> {
+    let mut fields = dob.split('-');
+    let (year, month, day) = (fields.next()?, fields.next()?, fields.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let month: u8 = month.parse().ok()?;
+    let yy = &year[2..];
+    let patterns = MONTH_OFFSETS.iter().flat_map(|&offset| {
+        let mm = format!("{:02}", month + offset);
+        [
+            (format!("{}{}{}", year, mm, day), 4, 6, offset),
+            (format!("{}{}{}", day, mm, year), 2, 0, offset),
+            (format!("{}{}{}", yy, mm, day), 2, 4, offset),
+            (format!("{}{}{}", day, mm, yy), 2, 0, offset),
+        ]
+    });
+
+    let positions: Vec<usize> = value
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_digit())
+        .map(|(i, _)| i)
+        .collect();
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    let (at, month_at, day_at, offset) = patterns
+        .into_iter()
+        .find_map(|(pattern, m, d, o)| digits.find(pattern.as_str()).map(|at| (at, m, d, o)))?;
+    let at = [
+        positions[at + month_at],
+        positions[at + month_at + 1],
+        positions[at + day_at],
+        positions[at + day_at + 1],
+    ];
+    Some((at, offset))
+}
+
+/// `value` with its encoded date of birth moved to the first of
+/// [`IMPOSSIBLE_DATES`] its check digits can be recomputed for.
+fn impossible_date(country: &str, value: &str, dob: &str) -> Option<String> {
+    let (at, offset) = date_positions(value, dob)?;
+    IMPOSSIBLE_DATES.iter().find_map(|(month, day)| {
+        let month = format!("{:02}", month.parse::<u8>().ok()? + offset);
+        let mut bytes = value.as_bytes().to_vec();
+        for (i, c) in at.iter().zip(month.bytes().chain(day.bytes())) {
+            bytes[*i] = c;
+        }
+        recheck(country, &String::from_utf8(bytes).ok()?, value)
+    })
+}
+
+/// The impossible date of birth `value` was moved to from `dob`.
+fn moved_dob(value: &str, dob: &str) -> Option<String> {
+    IMPOSSIBLE_DATES.iter().find_map(|(month, day)| {
+        let moved = format!("{}-{}-{}", dob.get(..4)?, month, day);
+        date_positions(value, &moved).map(|_| moved)
+    })
+}
+
+/// Personal ID `value` with its check digits recomputed from the rest, as
+/// `original` had them. `None` for formats whose check isn't known here, or
+/// when no check digit fits.
+fn recheck(country: &str, value: &str, original: &str) -> Option<String> {
+    let mut chars: Vec<char> = value.chars().collect();
+    match country {
+        // No check digit covers the date.
+        "DK" | "FO" | "GL" | "MY" | "CU" => return Some(value.to_string()),
+        // Mod 31 over the date and serial, as a character.
+        "FI" if chars.len() == 11 => {
+            const CHECK: &[u8] = b"0123456789ABCDEFHJKLMNPRSTUVWXY";
+            let n: u32 = format!("{}{}", value.get(..6)?, value.get(7..10)?)
+                .parse()
+                .ok()?;
+            chars[10] = CHECK[(n % 31) as usize] as char;
+            return Some(chars.into_iter().collect());
+        }
+        // ISO 7064 mod 11-2, `X` for 10.
+        "CN" if chars.len() == 18 => {
+            let digits = digits_of(&chars[..17])?;
+            chars[17] = iso7064_mod11_2(&digits);
+            return Some(chars.into_iter().collect());
+        }
+        // CURP: letters count as well, weighted 18 down to 2.
+        "MX" if chars.len() == 18 => {
+            let sum: u32 = chars[..17]
+                .iter()
+                .zip((2..=18).rev())
+                .map(|(&c, w)| {
+                    let v = match c {
+                        '0'..='9' => c as u32 - '0' as u32,
+                        'A'..='N' => c as u32 - 'A' as u32 + 10,
+                        'O'..='Z' => c as u32 - 'A' as u32 + 11,
+                        _ => 0,
+                    };
+                    v * w
+                })
+                .sum();
+            chars[17] = char::from_digit((10 - sum % 10) % 10, 10)?;
+            return Some(chars.into_iter().collect());
+        }
+        _ => {}
+    }
+
+    let at: Vec<usize> = (0..chars.len())
+        .filter(|&i| chars[i].is_ascii_digit())
+        .collect();
+    let mut d = digits_of(&at.iter().map(|&i| chars[i]).collect::<Vec<_>>())?;
+    let mod11 = |digits: &[u8], weights: &[u8]| weighted_check(digits, weights, 11) as u8;
+    match (country, d.len()) {
+        ("SE", 10) | ("ZA", 13) | ("GR", 11) | ("EG", 14) => {
+            let n = d.len() - 1;
+            d[n] = luhn_check(&d[..n]);
+        }
+        ("LU", 13) => {
+            d[11] = luhn_check(&d[..11]);
+            d[12] = verhoeff_check(&d[..11]);
+        }
+        ("PL", 11) => {
+            d[10] = (10 - weighted_check(&d[..10], &[1, 3, 7, 9, 1, 3, 7, 9, 1, 3], 10) as u8) % 10
+        }
+        // Weights 1-9,1, then 3-9,1-3 if that leaves 10.
+        ("EE" | "LT", 11) => {
+            d[10] = match mod11(&d[..10], &[1, 2, 3, 4, 5, 6, 7, 8, 9, 1]) {
+                10 => match mod11(&d[..10], &[3, 4, 5, 6, 7, 8, 9, 1, 2, 3]) {
+                    10 => 0,
+                    r => r,
+                },
+                r => r,
+            }
+        }
+        ("KZ", 12) => {
+            d[11] = match weighted_sum(&d[..11], &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]) % 11 {
+                10 => match weighted_sum(&d[..11], &[3, 4, 5, 6, 7, 8, 9, 10, 11, 1, 2]) % 11 {
+                    10 => 0,
+                    r => r as u8,
+                },
+                r => r as u8,
+            }
+        }
+        ("RO", 13) => {
+            d[12] = match mod11(&d[..12], &[2, 7, 9, 1, 4, 6, 3, 5, 8, 2, 7, 9]) {
+                10 => 1,
+                r => r,
+            }
+        }
+        ("BG", 10) => {
+            d[9] = match mod11(&d[..9], &[2, 4, 8, 5, 10, 9, 7, 3, 6]) {
+                10 => 0,
+                r => r,
+            }
+        }
+        ("IS", 10) => {
+            d[8] = match mod11(&d[..8], &[3, 2, 7, 6, 5, 4, 3, 2]) {
+                0 => 0,
+                1 => return None,
+                r => 11 - r,
+            }
+        }
+        ("KR", 13) => {
+            let sum = weighted_sum(&d[..12], &[2, 3, 4, 5, 6, 7, 8, 9, 2, 3, 4, 5]);
+            d[12] = ((11 - sum % 11) % 10) as u8;
+        }
+        // The whole number is a multiple of 11.
+        ("CZ" | "SK", 10) => {
+            let body: u64 = d[..9].iter().fold(0, |n, &x| n * 10 + x as u64);
+            d[9] = match body % 11 {
+                10 => return None,
+                r => r as u8,
+            }
+        }
+        // Serial, check digit, then the date.
+        ("AT", 10) => {
+            let rest: Vec<u8> = d[..3].iter().chain(&d[4..]).copied().collect();
+            d[3] = match mod11(&rest, &[3, 7, 9, 5, 8, 4, 2, 1, 6]) {
+                10 => return None,
+                r => r,
+            }
+        }
+        // Two mod-11 digits, the second over the first.
+        ("NO" | "SJ" | "BV", 11) => {
+            for (n, weights) in [
+                (9, &[3, 7, 6, 1, 8, 9, 4, 5, 2][..]),
+                (10, &[5, 4, 3, 2, 7, 6, 5, 4, 3, 2][..]),
+            ] {
+                d[n] = match 11 - mod11(&d[..n], weights) {
+                    11 => 0,
+                    10 => return None,
+                    r => r,
+                };
+            }
+        }
+        // 97 minus the date and serial mod 97, with a 2 in front for people
+        // born from 2000, as the original was.
+        ("BE", 11) => {
+            let body: u64 = d[..9].iter().fold(0, |n, &x| n * 10 + x as u64);
+            let old: Vec<char> = original.chars().filter(char::is_ascii_digit).collect();
+            let old_body: u64 = old.get(..9)?.iter().collect::<String>().parse().ok()?;
+            let old_check: u64 = old.get(9..)?.iter().collect::<String>().parse().ok()?;
+            let century = if 97 - old_body % 97 == old_check {
+                0
+            } else {
+                2_000_000_000
+            };
+            let check = 97 - (century + body) % 97;
+            d[9] = (check / 10) as u8;
+            d[10] = (check % 10) as u8;
+        }
+        _ => return None,
+    }
+    for (&i, &digit) in at.iter().zip(&d) {
+        chars[i] = char::from(b'0' + digit);
+    }
+    Some(chars.into_iter().collect())
+}
+
+/// Digit values of `chars`, `None` if any isn't a digit.
+fn digits_of(chars: &[char]) -> Option<Vec<u8>> {
+    chars
+        .iter()
+        .map(|c| c.to_digit(10).map(|d| d as u8))
+        .collect()
+}
+
+fn weighted_sum(digits: &[u8], weights: &[u32]) -> u32 {
+    digits
+        .iter()
+        .zip(weights)
+        .map(|(&d, &w)| d as u32 * w)
+        .sum()
+}
+
+impl Breakable for CreditCardRow {
+    const KIND: &'static str = "card";
+
+    fn value(&self) -> &str {
+        &self.number
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.number = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for SwiftRow {
+    const KIND: &'static str = "swift";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for CompanyIdRow {
+    const KIND: &'static str = "company";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for DriverLicenseRow {
+    const KIND: &'static str = "driver_license";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for PassportRow {
+    const KIND: &'static str = "passport";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for TaxIdRow {
+    const KIND: &'static str = "tax_id";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for VatRow {
+    const KIND: &'static str = "vat";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for LeiRow {
+    const KIND: &'static str = "lei";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for CreditorIdRow {
+    const KIND: &'static str = "creditor_id";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for IbanRow {
+    const KIND: &'static str = "iban";
+
+    fn value(&self) -> &str {
+        &self.raw
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        let chars: Vec<char> = value.chars().collect();
+        let formatted = chars
+            .chunks(4)
+            .map(|chunk| chunk.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join(" ");
+        *self = IbanRow::new(value, formatted, false);
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for IdRow {
+    const KIND: &'static str = "id";
+
+    fn value(&self) -> &str {
+        &self.code
+    }
+
+    fn dob(&self) -> Option<&str> {
+        Some(&self.dob)
+    }
+
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        if defect == Defect::ImpossibleDate
+            && let Some(dob) = moved_dob(&value, &self.dob)
+        {
+            self.dob = dob;
+        }
+        self.code = value;
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+impl Breakable for BankAccountRow {
+    const KIND: &'static str = "bank";
+
+    fn value(&self) -> &str {
+        &self.account
+    }
+
+    /// The IBAN is cleared: it would carry the broken account inside a valid
+    /// checksum.
+    fn set_broken(&mut self, value: String, defect: Defect) {
+        self.account = value;
+        self.iban.clear();
+        self.valid = false;
+        self.defect = Some(defect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnose;
+    use crate::generate::{self, seeded_rng};
+
+    fn failed(checks: Vec<diagnose::Check>) -> Vec<String> {
+        checks
+            .into_iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.label)
+            .collect()
+    }
+
+    #[test]
+    fn every_row_is_broken_and_labelled() {
+        let mut rng = seeded_rng(1);
+        let rows = generate::ibans(Some("DE"), 60, &mut rng);
+        let broken = break_rows(rows, "", &Defect::ALL, &mut rng).unwrap();
+        assert_eq!(broken.len(), 60);
+        let validator = Validator::new();
+        for row in &broken {
+            assert!(!row.valid);
+            let (valid, _) = validator.validate("iban", "", &row.raw).unwrap();
+            assert!(!valid, "{row:?}");
+        }
+        for defect in [
+            Defect::CheckDigit,
+            Defect::Transposition,
+            Defect::Length,
+            Defect::IllegalCharacter,
+            Defect::CountryPrefix,
+        ] {
+            assert!(
+                broken.iter().any(|row| row.defect == Some(defect)),
+                "{defect:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn country_prefix_keeps_the_checksum() {
+        let mut rng = seeded_rng(2);
+        let rows = generate::ibans(Some("DE"), 30, &mut rng);
+        for row in break_rows(rows, "", &[Defect::CountryPrefix], &mut rng).unwrap() {
+            assert_ne!(&row.raw[..2], "DE");
+            let failed = failed(diagnose::iban(&row.raw));
+            assert!(!failed.contains(&"Check digits".to_string()), "{row:?}");
+        }
+        let rows = generate::creditor_ids(Some("DE"), 30, &mut rng);
+        for row in break_rows(rows, "", &[Defect::CountryPrefix], &mut rng).unwrap() {
+            assert_eq!(failed(diagnose::creditor_id(&row.code)), ["Country code"]);
+        }
+        let rows = generate::swift_codes(&idsmith::swift::Registry::new(), "DE", 30, &mut rng);
+        assert_eq!(
+            break_rows(rows, "", &[Defect::CountryPrefix], &mut rng)
+                .unwrap()
+                .len(),
+            30
+        );
+    }
+
+    #[test]
+    fn impossible_date_keeps_the_checksum() {
+        let reg = idsmith::personal_id::Registry::new();
+        let mut rng = seeded_rng(3);
+        for country in ["PL", "SE", "NO", "FI", "EE", "BE", "CZ", "RO", "DK", "ZA"] {
+            let rows = generate::personal_ids(&reg, country, "any", None, 30, &mut rng);
+            let broken = break_rows(rows, country, &[Defect::ImpossibleDate], &mut rng).unwrap();
+            assert_eq!(broken.len(), 30, "{country}");
+            for row in broken {
+                assert!(crate::dates::parse(&row.dob).is_none(), "{row:?}");
+                // idsmith checks the digits only, so the check still passes.
+                assert_eq!(reg.validate(country, &row.code), Some(true), "{row:?}");
+                let (valid, message) = Validator::new().validate("id", country, &row.code).unwrap();
+                assert!(!valid, "{row:?}");
+                assert!(message.contains(&row.dob), "{message}");
+            }
+        }
+    }
+
+    #[test]
+    fn rows_that_cant_carry_the_defect_are_reported() {
+        let reg = idsmith::personal_id::Registry::new();
+        let mut rng = seeded_rng(4);
+        // British National Insurance numbers don't encode a date.
+        let rows = generate::personal_ids(&reg, "GB", "any", None, 10, &mut rng);
+        let err = break_rows(rows, "GB", &[Defect::ImpossibleDate], &mut rng).unwrap_err();
+        assert!(err.starts_with("10 of 10 rows"), "{err}");
+        let rows = generate::ibans(Some("DE"), 10, &mut rng);
+        assert_eq!(
+            break_rows(rows, "", &[Defect::ImpossibleDate], &mut rng).unwrap_err(),
+            "10 of 10 rows can't be broken with: Impossible date of birth"
+        );
+        let rows = generate::ibans(Some("DE"), 10, &mut rng);
+        assert_eq!(break_rows(rows, "", &[], &mut rng).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn parses_defect_lists() {
+        assert!(parse("").unwrap().is_empty());
+        assert_eq!(parse("any").unwrap().len(), Defect::ALL.len());
+        assert_eq!(
+            parse("check_digit, length").unwrap(),
+            [Defect::CheckDigit, Defect::Length]
+        );
+        assert!(parse("typo").is_err());
+    }
+}
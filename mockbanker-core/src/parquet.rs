@@ -0,0 +1,453 @@
+//! Minimal Apache Parquet files: one row group with one uncompressed,
+//! PLAIN-encoded data page per column, and the footer metadata written in
+//! Thrift's compact protocol by hand, so no Arrow or Thrift crate is needed.
+//!
+//! Every column is optional. Text is `BYTE_ARRAY` annotated `UTF8`,
+//! booleans `BOOLEAN`, and dates `INT32` annotated `DATE` unless a
+//! near-miss row holds an impossible one, which keeps the column as text.
+
+use crate::dates;
+use crate::export::{SqlType, SqlValue};
+
+// Physical types.
+const BOOLEAN: i32 = 0;
+const INT32: i32 = 1;
+const BYTE_ARRAY: i32 = 6;
+// Converted types.
+const UTF8: i32 = 0;
+const DATE: i32 = 6;
+// Encodings.
+const PLAIN: i32 = 0;
+const RLE: i32 = 3;
+const OPTIONAL: i32 = 1;
+const DATA_PAGE: i32 = 0;
+
+/// How a column's values are stored.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Boolean,
+    Date,
+    Text,
+}
+
+impl Kind {
+    fn of(ty: SqlType, values: &[&SqlValue]) -> Self {
+        match ty {
+            SqlType::Boolean => Kind::Boolean,
+            SqlType::Date
+                if values.iter().all(|v| match v {
+                    SqlValue::Date(d) => dates::parse(d).is_some(),
+                    SqlValue::Null => true,
+                    _ => false,
+                }) =>
+            {
+                Kind::Date
+            }
+            _ => Kind::Text,
+        }
+    }
+
+    fn physical(self) -> i32 {
+        match self {
+            Kind::Boolean => BOOLEAN,
+            Kind::Date => INT32,
+            Kind::Text => BYTE_ARRAY,
+        }
+    }
+
+    fn converted(self) -> Option<i32> {
+        match self {
+            Kind::Boolean => None,
+            Kind::Date => Some(DATE),
+            Kind::Text => Some(UTF8),
+        }
+    }
+}
+
+/// A Parquet file of `rows`, each holding one value per `(name, type)` in
+/// `columns`.
+pub fn file(columns: &[(&str, SqlType)], rows: &[Vec<SqlValue>]) -> Vec<u8> {
+    let mut file = b"PAR1".to_vec();
+    let mut chunks = Vec::new();
+    for (i, (_, ty)) in columns.iter().enumerate() {
+        let values: Vec<&SqlValue> = rows
+            .iter()
+            .map(|r| r.get(i).unwrap_or(&SqlValue::Null))
+            .collect();
+        let kind = Kind::of(*ty, &values);
+        let page = page(kind, &values);
+        chunks.push((kind, file.len() as i64, page.len() as i64));
+        file.extend_from_slice(&page);
+    }
+
+    let num_rows = rows.len() as i64;
+    let mut meta = Compact::default();
+    meta.i32(1, 1);
+    meta.list(2, STRUCT, columns.len() + 1);
+    meta.begin();
+    meta.string(4, "schema");
+    meta.i32(5, columns.len() as i32);
+    meta.end();
+    for ((name, _), (kind, _, _)) in columns.iter().zip(&chunks) {
+        meta.begin();
+        meta.i32(1, kind.physical());
+        meta.i32(3, OPTIONAL);
+        meta.string(4, name);
+        if let Some(converted) = kind.converted() {
+            meta.i32(6, converted);
+        }
+        meta.end();
+    }
+    meta.i64(3, num_rows);
+    meta.list(4, STRUCT, 1);
+    meta.begin();
+    meta.list(1, STRUCT, columns.len());
+    for ((name, _), (kind, offset, size)) in columns.iter().zip(&chunks) {
+        meta.begin();
+        meta.i64(2, *offset);
+        meta.field(3, STRUCT);
+        meta.begin();
+        meta.i32(1, kind.physical());
+        meta.list(2, I32, 2);
+        meta.varint(zigzag(PLAIN as i64));
+        meta.varint(zigzag(RLE as i64));
+        meta.list(3, BINARY, 1);
+        meta.bytes(name.as_bytes());
+        meta.i32(4, 0);
+        meta.i64(5, num_rows);
+        meta.i64(6, *size);
+        meta.i64(7, *size);
+        meta.i64(9, *offset);
+        meta.end();
+        meta.end();
+    }
+    meta.i64(2, chunks.iter().map(|(_, _, size)| size).sum());
+    meta.i64(3, num_rows);
+    meta.end();
+    meta.string(6, "MockBanker");
+    meta.end();
+
+    file.extend_from_slice(&meta.out);
+    file.extend_from_slice(&(meta.out.len() as u32).to_le_bytes());
+    file.extend_from_slice(b"PAR1");
+    file
+}
+
+/// A data page header followed by the definition levels and the PLAIN
+/// encoded values that are not null.
+fn page(kind: Kind, values: &[&SqlValue]) -> Vec<u8> {
+    let present: Vec<bool> = values
+        .iter()
+        .map(|v| match (kind, v) {
+            (_, SqlValue::Null) => false,
+            (Kind::Boolean, value) => matches!(value, SqlValue::Bool(_)),
+            (Kind::Date | Kind::Text, _) => true,
+        })
+        .collect();
+    // Definition levels as a single bit-packed run of 1-bit values.
+    let mut levels = Vec::new();
+    write_varint(&mut levels, ((present.len() as u64).div_ceil(8) << 1) | 1);
+    levels.extend(bit_pack(&present));
+
+    let mut data = (levels.len() as u32).to_le_bytes().to_vec();
+    data.extend(levels);
+    match kind {
+        Kind::Boolean => {
+            let bools: Vec<bool> = values
+                .iter()
+                .filter_map(|v| match v {
+                    SqlValue::Bool(b) => Some(*b),
+                    _ => None,
+                })
+                .collect();
+            data.extend(bit_pack(&bools));
+        }
+        Kind::Date => {
+            for value in values {
+                if let SqlValue::Date(d) = value {
+                    let days = dates::parse(d).unwrap_or_default() as i32;
+                    data.extend_from_slice(&days.to_le_bytes());
+                }
+            }
+        }
+        Kind::Text => {
+            for value in values {
+                let text = match value {
+                    SqlValue::Text(s) | SqlValue::Date(s) => s.as_str(),
+                    SqlValue::Bool(b) => {
+                        if *b {
+                            "true"
+                        } else {
+                            "false"
+                        }
+                    }
+                    SqlValue::Null => continue,
+                };
+                data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+                data.extend_from_slice(text.as_bytes());
+            }
+        }
+    }
+
+    let mut header = Compact::default();
+    header.i32(1, DATA_PAGE);
+    header.i32(2, data.len() as i32);
+    header.i32(3, data.len() as i32);
+    header.field(5, STRUCT);
+    header.begin();
+    header.i32(1, values.len() as i32);
+    header.i32(2, PLAIN);
+    header.i32(3, RLE);
+    header.i32(4, RLE);
+    header.end();
+    header.end();
+
+    let mut page = header.out;
+    page.extend(data);
+    page
+}
+
+/// Booleans packed eight to a byte, least significant bit first.
+fn bit_pack(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, bit)| acc | ((*bit as u8) << i))
+        })
+        .collect()
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+// Compact protocol field types.
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+/// Thrift compact protocol writer. Starts inside the outermost struct;
+/// each struct, including that one, is closed with [`Compact::end`].
+struct Compact {
+    out: Vec<u8>,
+    /// Last field id written in each open struct.
+    last: Vec<i16>,
+}
+
+impl Default for Compact {
+    fn default() -> Self {
+        Self {
+            out: Vec::new(),
+            last: vec![0],
+        }
+    }
+}
+
+impl Compact {
+    fn field(&mut self, id: i16, ty: u8) {
+        let last = self.last.last_mut().expect("field outside a struct");
+        let delta = id - *last;
+        if (1..=15).contains(&delta) {
+            self.out.push(((delta as u8) << 4) | ty);
+        } else {
+            self.out.push(ty);
+            write_varint(&mut self.out, zigzag(id as i64));
+        }
+        *last = id;
+    }
+
+    fn varint(&mut self, n: u64) {
+        write_varint(&mut self.out, n);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u64);
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        self.varint(zigzag(value as i64));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        self.varint(zigzag(value));
+    }
+
+    fn string(&mut self, id: i16, value: &str) {
+        self.field(id, BINARY);
+        self.bytes(value.as_bytes());
+    }
+
+    /// A list field header; its `len` elements are written next.
+    fn list(&mut self, id: i16, element: u8, len: usize) {
+        self.field(id, LIST);
+        if len < 15 {
+            self.out.push(((len as u8) << 4) | element);
+        } else {
+            self.out.push(0xF0 | element);
+            self.varint(len as u64);
+        }
+    }
+
+    /// Opens a struct, after its field header or as a list element.
+    fn begin(&mut self) {
+        self.last.push(0);
+    }
+
+    fn end(&mut self) {
+        self.out.push(0);
+        self.last.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> SqlValue {
+        SqlValue::Text(s.to_string())
+    }
+
+    #[test]
+    fn file_is_framed_by_magic_and_footer() {
+        let columns = [
+            ("iban", SqlType::Char(34)),
+            ("dob", SqlType::Date),
+            ("valid", SqlType::Boolean),
+        ];
+        let rows = vec![
+            vec![
+                text("DE89370400440532013000"),
+                SqlValue::Date("1990-01-31".to_string()),
+                SqlValue::Bool(true),
+            ],
+            vec![
+                text("GB82WEST12345698765432"),
+                SqlValue::Null,
+                SqlValue::Bool(false),
+            ],
+        ];
+        let file = file(&columns, &rows);
+        assert_eq!(&file[..4], b"PAR1");
+        assert_eq!(&file[file.len() - 4..], b"PAR1");
+
+        let footer = u32::from_le_bytes(file[file.len() - 8..file.len() - 4].try_into().unwrap());
+        let meta = &file[file.len() - 8 - footer as usize..file.len() - 8];
+        for name in ["schema", "iban", "dob", "valid", "MockBanker"] {
+            assert!(
+                meta.windows(name.len()).any(|w| w == name.as_bytes()),
+                "footer lacks {}",
+                name
+            );
+        }
+        // The first page header follows the magic: field 1, i32, DATA_PAGE.
+        assert_eq!(&file[4..6], &[0x15, 0x00]);
+        assert!(file.windows(22).any(|w| w == b"DE89370400440532013000"));
+    }
+
+    #[test]
+    fn parquet_reader_reads_the_rows_back() {
+        use ::parquet::file::reader::{FileReader, SerializedFileReader};
+        use ::parquet::record::Field;
+
+        let columns = [
+            ("code", SqlType::VarChar(20)),
+            ("dob", SqlType::Date),
+            ("valid", SqlType::Boolean),
+        ];
+        let rows = vec![
+            vec![
+                text("85010112345"),
+                SqlValue::Date("1985-01-01".to_string()),
+                SqlValue::Bool(true),
+            ],
+            vec![text("Zoë"), SqlValue::Null, SqlValue::Null],
+            vec![
+                SqlValue::Null,
+                SqlValue::Date("1969-12-31".to_string()),
+                SqlValue::Bool(false),
+            ],
+        ];
+        let reader = SerializedFileReader::new(bytes::Bytes::from(file(&columns, &rows))).unwrap();
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let names: Vec<&str> = schema.columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["code", "dob", "valid"]);
+
+        let read: Vec<Vec<Field>> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                row.unwrap()
+                    .get_column_iter()
+                    .map(|(_, field)| field.clone())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            read,
+            [
+                vec![
+                    Field::Str("85010112345".to_string()),
+                    Field::Date(5479),
+                    Field::Bool(true),
+                ],
+                vec![Field::Str("Zoë".to_string()), Field::Null, Field::Null],
+                vec![Field::Null, Field::Date(-1), Field::Bool(false)],
+            ]
+        );
+    }
+
+    #[test]
+    fn impossible_dates_keep_the_column_as_text() {
+        let valid = SqlValue::Date("2000-02-29".to_string());
+        let impossible = SqlValue::Date("1999-02-29".to_string());
+        assert!(Kind::of(SqlType::Date, &[&valid, &SqlValue::Null]) == Kind::Date);
+        assert!(Kind::of(SqlType::Date, &[&valid, &impossible]) == Kind::Text);
+        assert!(Kind::of(SqlType::Boolean, &[&SqlValue::Null]) == Kind::Boolean);
+    }
+
+    #[test]
+    fn page_holds_definition_levels_then_plain_values() {
+        let values = [text("ab"), SqlValue::Null, text("c")];
+        let refs: Vec<&SqlValue> = values.iter().collect();
+        let page = page(Kind::Text, &refs);
+        // Levels: 4-byte length, bit-packed run header (1 group), bits 101.
+        let data = [
+            &2u32.to_le_bytes()[..],
+            &[0x03, 0b101],
+            &2u32.to_le_bytes(),
+            b"ab",
+            &1u32.to_le_bytes(),
+            b"c",
+        ]
+        .concat();
+        assert!(page.ends_with(&data));
+    }
+
+    #[test]
+    fn encodings() {
+        assert_eq!(
+            bit_pack(&[true, false, true, true, false, false, false, false, true]),
+            [0b1101, 1]
+        );
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        let mut out = Vec::new();
+        write_varint(&mut out, 300);
+        assert_eq!(out, [0xac, 0x02]);
+    }
+}
@@ -0,0 +1,377 @@
+//! Composite synthetic person: one country, gender and date of birth behind
+//! every document.
+//!
+//! The personal ID is generated for the chosen gender and birth year and
+//! parsed back for the exact date of birth, drawn where the ID doesn't encode
+//! it. Where the country's tax ID is the personal ID itself (PESEL,
+//! isikukood, personnummer, ...) the same number is reused, and the documents
+//! whose formats encode a date of birth or gender (the British, Swedish, South
+//! African and Mexican driving licences, the Luxembourg and Mexican tax IDs)
+//! are built from the person, so the record never contradicts itself.
+//! Passports encode neither. Documents the country has no format for are left
+//! empty.
+
+use idsmith::{bank_account, driver_license, iban, passport, personal_id, tax_id};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::generate::parse_gender;
+use crate::seeded;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersonRow {
+    pub country: String,
+    pub gender: String,
+    pub dob: String,
+    pub personal_id: String,
+    pub passport: String,
+    pub driver_license: String,
+    pub tax_id: String,
+    pub iban: String,
+    pub bank_account: String,
+    pub routing: String,
+    /// Every generated identifier passed its validator.
+    pub valid: bool,
+}
+
+/// Birth years drawn when none is requested: adults of working age.
+const YEARS: std::ops::RangeInclusive<u16> = 1950..=2005;
+
+pub struct PersonGenerator {
+    personal_id: personal_id::Registry,
+    passport: passport::Registry,
+    driver_license: driver_license::Registry,
+    tax_id: tax_id::Registry,
+}
+
+impl Default for PersonGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PersonGenerator {
+    pub fn new() -> Self {
+        Self {
+            personal_id: personal_id::Registry::new(),
+            passport: passport::Registry::new(),
+            driver_license: driver_license::Registry::new(),
+            tax_id: tax_id::Registry::new(),
+        }
+    }
+
+    /// Countries with a personal ID format, as `(code, name)`; the personal
+    /// ID anchors the gender and date of birth so it can't be missing.
+    pub fn countries(&self) -> Vec<(String, String)> {
+        self.personal_id
+            .list_countries()
+            .iter()
+            .map(|(c, n, _)| (c.to_string(), n.to_string()))
+            .collect()
+    }
+
+    /// `gender` is `"male"`, `"female"` or anything else for a random pick
+    /// per person; `year` of `None` draws a birth year per person.
+    pub fn generate<R: Rng>(
+        &self,
+        country: &str,
+        gender: &str,
+        year: Option<u16>,
+        count: u32,
+        rng: &mut R,
+    ) -> Vec<PersonRow> {
+        let mut rows = Vec::new();
+        for _ in 0..count {
+            if let Some(row) = self.person(country, gender, year, rng) {
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    fn person<R: Rng>(
+        &self,
+        country: &str,
+        gender: &str,
+        year: Option<u16>,
+        rng: &mut R,
+    ) -> Option<PersonRow> {
+        let gender = match parse_gender(gender) {
+            Some(g) => g,
+            None if rng.gen_bool(0.5) => personal_id::date::Gender::Male,
+            None => personal_id::date::Gender::Female,
+        };
+        let year = year.unwrap_or_else(|| rng.gen_range(YEARS));
+        let opts = personal_id::GenOptions {
+            gender: Some(gender),
+            year: Some(year),
+        };
+        let code = seeded::personal_id(country, &opts, rng)?;
+        let parsed = self.personal_id.parse(country, &code)?;
+        let female = gender == personal_id::date::Gender::Female;
+        let born = Born::new(parsed.dob.as_deref(), year, female, rng);
+        let mut valid = parsed.valid;
+
+        let passport = self
+            .passport
+            .generate(
+                &passport::GenOptions {
+                    country: Some(country.to_string()),
+                },
+                rng,
+            )
+            .map(|res| {
+                valid &= res.valid;
+                res.code
+            })
+            .unwrap_or_default();
+
+        let driver_license = self
+            .driver_license
+            .generate(
+                &driver_license::GenOptions {
+                    country: Some(country.to_string()),
+                    state: None,
+                },
+                rng,
+            )
+            .map(|res| {
+                let code = licence_for(country, res.code, &parsed.code, &born);
+                valid &= self.driver_license.validate(country, &code);
+                code
+            })
+            .unwrap_or_default();
+
+        let tax_id = if self.tax_id.validate(country, &parsed.code) {
+            parsed.code.clone()
+        } else if let Some(code) = self.derived_tax_id(country, &parsed.code, rng) {
+            code
+        } else {
+            self.tax_id
+                .generate(
+                    &tax_id::GenOptions {
+                        country: Some(country.to_string()),
+                        holder_type: None,
+                    },
+                    rng,
+                )
+                .map(|res| {
+                    valid &= res.valid;
+                    res.code
+                })
+                .unwrap_or_default()
+        };
+
+        let iban = if iban::supported_countries().contains(&country) {
+            match iban::generate_iban(Some(country), rng) {
+                Ok(code) => {
+                    valid &= iban::validate_iban(&code);
+                    code
+                }
+                Err(_) => String::new(),
+            }
+        } else {
+            String::new()
+        };
+
+        let (bank_account, routing) =
+            seeded::bank_account(country, &bank_account::GenOptions::default(), rng)
+                .map(|res| {
+                    valid &= res.valid;
+                    (res.account_number, res.bank_code.unwrap_or_default())
+                })
+                .unwrap_or_default();
+
+        Some(PersonRow {
+            country: country.to_string(),
+            gender: parsed
+                .gender
+                .unwrap_or_else(|| if female { "female" } else { "male" }.to_string()),
+            dob: born.iso(),
+            personal_id: parsed.code,
+            passport,
+            driver_license,
+            tax_id,
+            iban,
+            bank_account,
+            routing,
+            valid,
+        })
+    }
+
+    /// Tax IDs that encode the date of birth without being the personal ID:
+    /// its date digits, completed with the check character the validator
+    /// accepts.
+    fn derived_tax_id<R: Rng>(
+        &self,
+        country: &str,
+        personal_id: &str,
+        rng: &mut R,
+    ) -> Option<String> {
+        let body = match country {
+            // Matricule: the same date and serial, with a second check over
+            // the first one as well.
+            "LU" if personal_id.len() == 13 => personal_id[..12].to_string(),
+            // RFC: the CURP's name letters and date of birth, then a
+            // homoclave.
+            "MX" if personal_id.len() >= 10 => {
+                let homoclave: String = (0..2)
+                    .map(|_| ALPHANUMERIC[rng.gen_range(0..ALPHANUMERIC.len())] as char)
+                    .collect();
+                format!("{}{}", &personal_id[..10], homoclave)
+            }
+            _ => return None,
+        };
+        "0123456789A"
+            .chars()
+            .map(|check| format!("{body}{check}"))
+            .find(|code| self.tax_id.validate(country, code))
+    }
+}
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// The date of birth and gender every document of a person describes.
+struct Born {
+    year: u16,
+    month: u8,
+    day: u8,
+    female: bool,
+}
+
+impl Born {
+    /// The personal ID's `dob`, with the parts it doesn't encode drawn.
+    fn new<R: Rng>(dob: Option<&str>, year: u16, female: bool, rng: &mut R) -> Self {
+        let mut born = Born {
+            year,
+            month: rng.gen_range(1..=12),
+            day: rng.gen_range(1..=28),
+            female,
+        };
+        let parts: Vec<&str> = dob.unwrap_or_default().split('-').collect();
+        if let Some(year) = parts.first().and_then(|p| p.parse().ok()) {
+            born.year = year;
+        }
+        if let Some(month) = parts.get(1).and_then(|p| p.parse().ok()) {
+            born.month = month;
+        }
+        if let Some(day) = parts.get(2).and_then(|p| p.parse().ok()) {
+            born.day = day;
+        }
+        born
+    }
+
+    fn iso(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// `code` with the parts that encode the holder's date of birth or gender
+/// rewritten for `born`; formats that encode neither pass through.
+fn licence_for(country: &str, code: String, personal_id: &str, born: &Born) -> String {
+    match country {
+        // DVLA: decade, month (plus 50 for women), day and year digit.
+        "GB" if code.len() == 16 => {
+            let month = born.month + if born.female { 50 } else { 0 };
+            format!(
+                "{}{}{:02}{:02}{}{}",
+                &code[..5],
+                born.year % 100 / 10,
+                month,
+                born.day,
+                born.year % 10,
+                &code[11..]
+            )
+        }
+        // The licence carries the holder's national ID number.
+        "SE" | "ZA" => personal_id.chars().filter(char::is_ascii_digit).collect(),
+        // The CURP's name letters and date of birth, then two characters.
+        "MX" if code.len() == 12 && personal_id.len() >= 10 => {
+            format!("{}{}", &personal_id[..10], &code[10..])
+        }
+        _ => code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    fn people(country: &str, gender: &str, year: Option<u16>) -> Vec<PersonRow> {
+        PersonGenerator::new().generate(country, gender, year, 50, &mut seeded_rng(3))
+    }
+
+    #[test]
+    fn honours_gender_and_birth_year() {
+        for row in people("EE", "female", Some(1980)) {
+            assert_eq!(row.gender, "female");
+            assert!(row.dob.starts_with("1980-"), "{}", row.dob);
+            assert!(row.valid, "{row:?}");
+        }
+    }
+
+    #[test]
+    fn tax_id_reuses_the_personal_id() {
+        for country in ["PL", "EE", "SE"] {
+            for row in people(country, "any", None) {
+                assert_eq!(row.tax_id, row.personal_id, "{country}");
+            }
+        }
+    }
+
+    #[test]
+    fn documents_encode_the_persons_birth() {
+        for row in people("GB", "female", Some(1987)) {
+            let month: u8 = row.dob[5..7].parse().unwrap();
+            let day = &row.dob[8..];
+            let licence = &row.driver_license;
+            assert_eq!(&licence[5..6], "8", "{row:?}");
+            assert_eq!(licence[6..8].parse::<u8>().unwrap(), month + 50, "{row:?}");
+            assert_eq!(&licence[8..10], day, "{row:?}");
+            assert_eq!(&licence[10..11], "7", "{row:?}");
+            assert!(row.valid, "{row:?}");
+        }
+        for country in ["SE", "ZA"] {
+            for row in people(country, "any", None) {
+                let digits: String = row
+                    .personal_id
+                    .chars()
+                    .filter(char::is_ascii_digit)
+                    .collect();
+                assert_eq!(row.driver_license, digits, "{country}");
+                assert!(row.valid, "{row:?}");
+            }
+        }
+        for row in people("MX", "any", None) {
+            assert_eq!(row.driver_license[..10], row.personal_id[..10], "{row:?}");
+            assert_eq!(row.tax_id[..10], row.personal_id[..10], "{row:?}");
+            assert_eq!(row.tax_id.len(), 13, "{row:?}");
+            assert!(row.valid, "{row:?}");
+        }
+        for row in people("LU", "any", None) {
+            assert_eq!(row.tax_id[..12], row.personal_id[..12], "{row:?}");
+            assert!(row.valid, "{row:?}");
+        }
+    }
+
+    #[test]
+    fn ids_without_a_date_get_a_drawn_one() {
+        for row in people("GB", "male", Some(1990)) {
+            assert_eq!(row.gender, "male");
+            assert!(crate::dates::parse(&row.dob).is_some(), "{}", row.dob);
+            assert!(row.dob.starts_with("1990-"), "{}", row.dob);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_people() {
+        let a = people("DE", "any", None);
+        let b = people("DE", "any", None);
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+        assert!(
+            a.iter()
+                .all(|row| !row.personal_id.is_empty() && !row.iban.is_empty())
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! Format-preserving pseudonymization of real data files: every value in
+//! the marked columns of a CSV is replaced by a valid synthetic one of the
+//! same type and country, with the original's spacing and separators.
+//!
+//! Each replacement is drawn from an RNG seeded with an HMAC-SHA256 of the
+//! value under a secret key, so with the same key a real IBAN maps to the
+//! same fake one in every file, and without the key the mapping can't be
+//! recomputed.
+//!
+//! Values with no replacement are blanked, so the output never holds a
+//! real value from a marked column.
+
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::dataset::{self, Generators};
+use crate::export::{self, SqlValue};
+use crate::generate;
+use crate::validate::{self, Validator};
+
+/// What a marked column holds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rule {
+    /// A [`validate::KINDS`] key, or [`validate::DETECT`] to take each
+    /// value's most specific reading.
+    pub kind: String,
+    /// Country of the column's values; when empty, each value's own, as
+    /// far as it reveals one.
+    pub country: String,
+}
+
+/// How many values of a marked column were replaced, and how many were
+/// blanked because their type or country couldn't be told.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnReport {
+    pub name: String,
+    pub replaced: usize,
+    pub blanked: usize,
+}
+
+/// A pseudonymized CSV file, written with the input's delimiter, byte-order
+/// mark and line endings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pseudonymized {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub csv: String,
+    pub columns: Vec<ColumnReport>,
+}
+
+/// Replaces values under one key, remembering each mapping so repeated
+/// values are looked up instead of detected and generated again.
+pub struct Pseudonymizer {
+    key: Vec<u8>,
+    current_year: u16,
+    validator: Validator,
+    generators: Generators,
+    mapped: HashMap<(String, String, String), Option<String>>,
+}
+
+impl Pseudonymizer {
+    /// `current_year` is the two-digit year card expiry dates are picked
+    /// after, as for generated cards.
+    pub fn new(key: &str, current_year: u16) -> Result<Self, String> {
+        if key.is_empty() {
+            return Err("Enter a key; the same key always gives the same replacements".to_string());
+        }
+        Ok(Self {
+            key: key.as_bytes().to_vec(),
+            current_year,
+            validator: Validator::new(),
+            generators: Generators::new(),
+            mapped: HashMap::new(),
+        })
+    }
+
+    /// The replacement for `value` in a column marked with `rule`, or `None`
+    /// if its type or country can't be told.
+    pub fn value(&mut self, rule: &Rule, value: &str) -> Option<String> {
+        let value = value.trim();
+        let code = compact(value);
+        let lookup = (rule.kind.clone(), rule.country.clone(), code.clone());
+        if let Some(fake) = self.mapped.get(&lookup) {
+            return fake.as_deref().map(|f| reshape(value, f));
+        }
+        let fake = self.generate(rule, value, &code);
+        self.mapped.insert(lookup, fake.clone());
+        fake.map(|f| reshape(value, &f))
+    }
+
+    /// Replaces every non-empty field of the columns named in `rules` in
+    /// the CSV `text`, blanking those it has no replacement for.
+    pub fn csv(&mut self, text: &str, rules: &[(String, Rule)]) -> Result<Pseudonymized, String> {
+        let (headers, mut rows, options) = export::read_csv(text);
+        if headers.is_empty() {
+            return Err("The file is empty".to_string());
+        }
+        if rules.is_empty() {
+            return Err("Mark at least one column to pseudonymize".to_string());
+        }
+        let mut marked = Vec::new();
+        for (name, rule) in rules {
+            let index = headers
+                .iter()
+                .position(|h| h.trim() == name.trim())
+                .ok_or_else(|| format!("No column named '{}'", name))?;
+            if rule.kind != validate::DETECT
+                && !validate::KINDS.iter().any(|(k, _)| *k == rule.kind)
+            {
+                return Err(format!(
+                    "Unknown type '{}' for column '{}'",
+                    rule.kind, name
+                ));
+            }
+            marked.push((index, rule));
+        }
+
+        let mut columns: Vec<ColumnReport> = marked
+            .iter()
+            .map(|(index, _)| ColumnReport {
+                name: headers[*index].clone(),
+                ..ColumnReport::default()
+            })
+            .collect();
+        for row in &mut rows {
+            for ((index, rule), report) in marked.iter().zip(&mut columns) {
+                let Some(field) = row.get_mut(*index) else {
+                    continue;
+                };
+                if field.trim().is_empty() {
+                    continue;
+                }
+                match self.value(rule, field) {
+                    Some(fake) => {
+                        *field = fake;
+                        report.replaced += 1;
+                    }
+                    None => {
+                        field.clear();
+                        report.blanked += 1;
+                    }
+                }
+            }
+        }
+
+        let header_refs: Vec<&str> = headers.iter().map(String::as_str).collect();
+        let csv = export::write_csv(&header_refs, rows.iter().cloned(), &options);
+        Ok(Pseudonymized {
+            headers,
+            rows,
+            csv,
+            columns,
+        })
+    }
+
+    /// A new value of the type and country `value` reads as, drawn from its
+    /// keyed hash.
+    fn generate(&self, rule: &Rule, value: &str, code: &str) -> Option<String> {
+        let country = rule.country.trim().to_uppercase();
+        let readings = if rule.kind == validate::DETECT || country.is_empty() {
+            self.validator.detect(value)
+        } else {
+            Vec::new()
+        };
+        let kind = if rule.kind == validate::DETECT {
+            readings.first()?.kind.as_str()
+        } else {
+            rule.kind.as_str()
+        };
+        // Values that don't validate still carry their country in these
+        // prefixes, and must be replaced all the same.
+        let prefix = code
+            .get(..2)
+            .filter(|p| p.chars().all(|c| c.is_ascii_uppercase()))
+            .filter(|_| matches!(kind, "iban" | "vat" | "creditor_id"));
+        let country = Some(country)
+            .filter(|c| !c.is_empty())
+            .or_else(|| {
+                readings
+                    .iter()
+                    .find(|r| r.kind == kind && !r.country.is_empty())
+                    .map(|r| r.country.clone())
+            })
+            .or_else(|| prefix.map(|p| if p == "EL" { "GR" } else { p }.to_string()))
+            .unwrap_or_default();
+        if country.is_empty() && !matches!(kind, "iban" | "card" | "lei" | "creditor_id") {
+            return None;
+        }
+
+        let column = dataset::Column {
+            country: country.clone(),
+            brand: if kind == "card" {
+                self.brand(code)
+            } else {
+                String::new()
+            },
+            ..dataset::Column::new("", dataset_kind(kind))
+        };
+        let digest = hmac_sha256(
+            &self.key,
+            format!("{}\0{}\0{}", kind, country, code).as_bytes(),
+        );
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&digest[..8]);
+        let mut rng = generate::seeded_rng(u64::from_be_bytes(seed));
+        // A draw that happens to equal the real value is drawn again.
+        for _ in 0..3 {
+            let fake = match self
+                .generators
+                .draw(&column, self.current_year, &mut rng)?
+                .into_iter()
+                .next()?
+            {
+                SqlValue::Text(fake) => fake,
+                _ => return None,
+            };
+            if compact(&fake) != code {
+                return Some(fake);
+            }
+        }
+        None
+    }
+
+    /// The generator's key for the brand of card `number`, `visa` if it
+    /// has none that matches.
+    fn brand(&self, number: &str) -> String {
+        let label = validate::card_brand(number);
+        let wanted = match label {
+            "American Express" => "amex".to_string(),
+            other => other.to_lowercase().replace(' ', ""),
+        };
+        self.generators
+            .brands()
+            .into_iter()
+            .find(|b| {
+                let b = b.to_lowercase().replace(['_', '-', ' '], "");
+                b.starts_with(&wanted) || wanted.starts_with(&b)
+            })
+            .unwrap_or_else(|| "visa".to_string())
+    }
+}
+
+/// The [`validate::KINDS`] key most of a column's first values clearly
+/// read as, to mark the column before the user does; `None` if no type
+/// fits more than half of them.
+pub fn suggest<'a>(
+    validator: &Validator,
+    values: impl IntoIterator<Item = &'a str>,
+) -> Option<&'static str> {
+    let mut sampled = 0;
+    let mut kinds = Vec::new();
+    for value in values.into_iter().filter(|v| !v.trim().is_empty()).take(20) {
+        sampled += 1;
+        if let Some(best) = validator.detect(value).into_iter().next()
+            && best.score >= 60
+        {
+            kinds.push(best.kind);
+        }
+    }
+    validate::KINDS
+        .iter()
+        .map(|(key, _)| *key)
+        .find(|key| kinds.iter().filter(|k| k == key).count() * 2 > sampled)
+}
+
+/// [`dataset::KINDS`] key of a [`validate::KINDS`] one.
+fn dataset_kind(kind: &str) -> &str {
+    match kind {
+        "id" => "personal_id",
+        "bank" => "bank_account",
+        "card" => "credit_card",
+        "company" => "company_id",
+        other => other,
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '-' | '.' | '/')
+}
+
+/// `value` without separators, upper-cased, so `de89 3704 ...` and
+/// `DE893704...` are the same value.
+fn compact(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !is_separator(*c))
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// `fake` laid out like `original`: its separators at the same positions
+/// and lower case if it was, when both have as many other characters.
+/// Otherwise `fake` as generated.
+fn reshape(original: &str, fake: &str) -> String {
+    let mut chars = fake.chars().filter(|c| !is_separator(*c));
+    if original.chars().filter(|c| !is_separator(*c)).count() != chars.clone().count() {
+        return fake.to_string();
+    }
+    let lower =
+        original.chars().any(|c| c.is_lowercase()) && !original.chars().any(|c| c.is_uppercase());
+    original
+        .chars()
+        .map(|c| {
+            if is_separator(c) {
+                return c;
+            }
+            let f = chars.next().unwrap_or(c);
+            if lower { f.to_ascii_lowercase() } else { f }
+        })
+        .collect()
+}
+
+/// HMAC-SHA256 (RFC 2104).
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: &str, country: &str) -> Rule {
+        Rule {
+            kind: kind.to_string(),
+            country: country.to_string(),
+        }
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn hmac_test_vectors() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first (RFC 4231, test case 6).
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn csv_rejects_unknown_columns_and_types() {
+        let mut p = Pseudonymizer::new("secret", 26).unwrap();
+        assert!(
+            p.csv("a\n1\n", &[("b".to_string(), rule("iban", ""))])
+                .is_err()
+        );
+        assert!(
+            p.csv("a\n1\n", &[("a".to_string(), rule("nope", ""))])
+                .is_err()
+        );
+        assert!(p.csv("a\n1\n", &[]).is_err());
+        assert!(Pseudonymizer::new("", 26).is_err());
+    }
+
+    #[test]
+    fn reshape_keeps_separators_and_case() {
+        assert_eq!(reshape("ab-12 cd", "XY34EF"), "xy-34 ef");
+        assert_eq!(reshape("AB.12", "XY34"), "XY.34");
+        // Different lengths come back as generated.
+        assert_eq!(reshape("AB-1", "XY34"), "XY34");
+    }
+
+    #[test]
+    fn same_key_same_replacement_across_runs() {
+        let iban = rule("iban", "");
+        let real = "DE89 3704 0044 0532 0130 00";
+        let a = Pseudonymizer::new("secret", 26)
+            .unwrap()
+            .value(&iban, real)
+            .unwrap();
+        let b = Pseudonymizer::new("secret", 26)
+            .unwrap()
+            .value(&iban, real)
+            .unwrap();
+        let other = Pseudonymizer::new("other", 26)
+            .unwrap()
+            .value(&iban, real)
+            .unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, other);
+        assert_ne!(compact(&a), compact(real));
+        assert!(a.starts_with("DE"));
+        assert!(crate::diagnose::iban(&a).iter().all(|c| c.passed), "{}", a);
+        // Laid out like the original.
+        assert_eq!(a.len(), real.len());
+        assert_eq!(a.matches(' ').count(), 5);
+    }
+
+    #[test]
+    fn spelling_doesnt_change_the_replacement() {
+        let mut p = Pseudonymizer::new("secret", 26).unwrap();
+        let spaced = p
+            .value(&rule("iban", ""), "DE89 3704 0044 0532 0130 00")
+            .unwrap();
+        let compacted = p
+            .value(&rule("iban", ""), "de89370400440532013000")
+            .unwrap();
+        assert_eq!(compact(&spaced), compact(&compacted));
+        assert_eq!(compacted, compacted.to_lowercase());
+    }
+
+    #[test]
+    fn every_type_maps_alike_across_runs() {
+        for (rule, real) in [
+            (rule("id", "PL"), "44051401359"),
+            (rule("bank", "DE"), "0532013000"),
+            (rule("company", "PL"), "123-456-32-18"),
+            (rule("passport", "DE"), "C01X00T47"),
+        ] {
+            let a = Pseudonymizer::new("secret", 26)
+                .unwrap()
+                .value(&rule, real)
+                .unwrap();
+            let b = Pseudonymizer::new("secret", 26)
+                .unwrap()
+                .value(&rule, real)
+                .unwrap();
+            assert_eq!(a, b, "{}", rule.kind);
+            assert_ne!(compact(&a), compact(real), "{}", rule.kind);
+        }
+    }
+
+    #[test]
+    fn the_columns_country_comes_first() {
+        let mut p = Pseudonymizer::new("secret", 26).unwrap();
+        // A Polish IBAN in a column marked as German gets a German one.
+        let fake = p
+            .value(&rule("iban", "DE"), "PL61109010140000071219812874")
+            .unwrap();
+        assert!(fake.starts_with("DE"), "{}", fake);
+        assert!(
+            p.value(&rule("iban", ""), "PL61109010140000071219812874")
+                .unwrap()
+                .starts_with("PL")
+        );
+    }
+
+    #[test]
+    fn values_without_a_replacement_are_blanked() {
+        let csv = "name,iban,pesel\r\n\
+                   Ann,DE89370400440532013000,44051401359\r\n\
+                   Bob,not an iban,zzz\r\n\
+                   Cy,,\r\n";
+        let rules = [
+            ("iban".to_string(), rule("iban", "")),
+            ("pesel".to_string(), rule("id", "")),
+        ];
+        let out = Pseudonymizer::new("secret", 26)
+            .unwrap()
+            .csv(csv, &rules)
+            .unwrap();
+        assert_eq!(
+            out.columns,
+            [
+                ColumnReport {
+                    name: "iban".to_string(),
+                    replaced: 2,
+                    blanked: 0,
+                },
+                ColumnReport {
+                    name: "pesel".to_string(),
+                    replaced: 1,
+                    blanked: 1,
+                },
+            ]
+        );
+        for real in [
+            "DE89370400440532013000",
+            "not an iban",
+            "44051401359",
+            "zzz",
+        ] {
+            assert!(!out.csv.contains(real), "{} left in output", real);
+        }
+        // IBANs need no country, so even a malformed one gets a replacement.
+        assert_eq!(out.rows[1][0], "Bob");
+        assert!(!out.rows[1][1].is_empty());
+        assert_eq!(out.rows[1][2], "");
+        assert_eq!(out.rows[2], ["Cy", "", ""]);
+        assert_eq!(out.rows[0][0], "Ann");
+        assert!(out.csv.ends_with("\r\n"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Row types produced by the generators, one per tab.
+
+use serde::{Deserialize, Serialize};
+
+use crate::anatomy::{self, IbanParts};
+use crate::negative::Defect;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IbanRow {
+    pub raw: String,
+    pub formatted: String,
+    #[serde(flatten)]
+    pub parts: IbanParts,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+impl IbanRow {
+    /// Row for `raw`, split into its [`IbanParts`].
+    pub fn new(raw: String, formatted: String, valid: bool) -> Self {
+        Self {
+            parts: anatomy::parts(&raw),
+            raw,
+            formatted,
+            valid,
+            defect: None,
+        }
+    }
+
+    /// The IBAN as displayed, grouped in blocks of four or compact.
+    pub fn display(&self, spaces: bool) -> &str {
+        if spaces { &self.formatted } else { &self.raw }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdRow {
+    pub code: String,
+    pub gender: String,
+    pub dob: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BankAccountRow {
+    pub account: String,
+    pub routing: String,
+    /// The same account as an IBAN; empty where the country has none or
+    /// the account doesn't fit its BBAN.
+    pub iban: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+impl BankAccountRow {
+    /// Account number followed by the routing code in parentheses, if any.
+    pub fn display(&self) -> String {
+        if self.routing.is_empty() {
+            self.account.clone()
+        } else {
+            format!("{} ({})", self.account, self.routing)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreditCardRow {
+    pub number: String,
+    pub brand: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwiftRow {
+    pub code: String,
+    pub bank: String,
+    pub country: String,
+    pub location: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompanyIdRow {
+    pub code: String,
+    pub name: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DriverLicenseRow {
+    pub code: String,
+    pub name: String,
+    pub country: String,
+    pub state: Option<String>,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PassportRow {
+    pub code: String,
+    pub name: String,
+    pub country: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaxIdRow {
+    pub code: String,
+    pub name: String,
+    pub country: String,
+    pub holder_type: Option<String>,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VatRow {
+    pub code: String,
+    pub country_code: String,
+    pub country_name: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeiRow {
+    pub code: String,
+    pub lou: String,
+    pub country_code: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreditorIdRow {
+    pub code: String,
+    pub country: String,
+    pub valid: bool,
+    /// Set on near-miss rows from [`crate::negative::break_rows`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defect: Option<Defect>,
+}
+
+/// One line of a bulk validation report.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidationRow {
+    pub value: String,
+    pub valid: bool,
+    pub reason: String,
+}
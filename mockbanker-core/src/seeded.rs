@@ -0,0 +1,443 @@
+//! Seeded draws for the idsmith generators whose registries only take a
+//! thread RNG: personal IDs, bank accounts and company IDs.
+//!
+//! Personal IDs and bank accounts go straight to idsmith's per-country
+//! generators, which take any RNG; the few personal ID formats whose
+//! generators don't are written out here. Company ID generators take none,
+//! so each country's format is learned from a batch of idsmith's own samples
+//! and filled in from the seeded RNG until the registry validates it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use idsmith::bank_account::{self, AccountResult};
+use idsmith::company_id::{self, CompanyResult};
+use idsmith::personal_id::{self, date::Gender};
+use rand::Rng;
+
+/// Territories using a parent country's personal ID, as idsmith resolves
+/// them.
+const ID_TERRITORIES: &[(&str, &str)] = &[
+    ("AS", "US"),
+    ("GU", "US"),
+    ("MP", "US"),
+    ("PR", "US"),
+    ("UM", "US"),
+    ("VI", "US"),
+    ("CC", "AU"),
+    ("CX", "AU"),
+    ("HM", "AU"),
+    ("NF", "AU"),
+    ("CK", "NZ"),
+    ("NU", "NZ"),
+    ("PN", "NZ"),
+    ("TK", "NZ"),
+    ("BL", "FR"),
+    ("GS", "GB"),
+    ("IO", "GB"),
+    ("SH", "GB"),
+    ("BV", "NO"),
+    ("SJ", "NO"),
+    ("FO", "DK"),
+    ("GL", "DK"),
+    ("GF", "FR"),
+    ("GP", "FR"),
+    ("MF", "FR"),
+    ("MQ", "FR"),
+    ("NC", "FR"),
+    ("PF", "FR"),
+    ("PM", "FR"),
+    ("RE", "FR"),
+    ("TF", "FR"),
+    ("WF", "FR"),
+    ("YT", "FR"),
+    ("VG", "GB"),
+];
+
+/// Territories using a parent country's banking system.
+const BANK_TERRITORIES: &[(&str, &str)] = &[
+    ("AS", "US"),
+    ("GU", "US"),
+    ("MP", "US"),
+    ("PR", "US"),
+    ("UM", "US"),
+    ("VI", "US"),
+    ("CC", "AU"),
+    ("CX", "AU"),
+    ("HM", "AU"),
+    ("NF", "AU"),
+    ("CK", "NZ"),
+    ("NU", "NZ"),
+    ("PN", "NZ"),
+    ("TK", "NZ"),
+    ("BL", "FR"),
+    ("GS", "GB"),
+    ("IO", "GB"),
+    ("SH", "GB"),
+    ("BV", "NO"),
+    ("SJ", "NO"),
+    ("EH", "MA"),
+];
+
+fn parent<'a>(territories: &[(&str, &'a str)], country: &str) -> Option<&'a str> {
+    territories
+        .iter()
+        .find(|(code, _)| *code == country)
+        .map(|(_, parent)| *parent)
+}
+
+/// A personal ID for `country`, or `None` if idsmith has no format for it.
+pub fn personal_id<R: Rng>(
+    country: &str,
+    opts: &personal_id::GenOptions,
+    rng: &mut R,
+) -> Option<String> {
+    use personal_id::*;
+    let code = match country {
+        "EE" | "LT" => ee::generate(opts, rng),
+        "LV" => lv::generate(opts, rng),
+        "FI" => fi::generate(opts, rng),
+        "SE" => se::generate(opts, rng),
+        "NO" => no::generate(opts, rng),
+        "DK" => dk::generate(opts, rng),
+        "IS" => is_::generate(opts, rng),
+        "DE" => de::generate(opts, rng),
+        "AT" => at::generate(opts, rng),
+        "CH" => ch::generate(opts, rng),
+        "NL" => nl::generate(opts, rng),
+        "BE" => be::generate(opts, rng),
+        "FR" => fr::generate(opts, rng),
+        "ES" => es::generate(opts, rng),
+        "PT" => pt::generate(opts, rng),
+        "IT" => it::generate(opts, rng),
+        "GB" => gb::generate(opts, rng),
+        "IE" => ie::generate(opts, rng),
+        "PL" => pl::generate(opts, rng),
+        "CZ" | "SK" => cz::generate(opts, rng),
+        "RO" => ro::generate(opts, rng),
+        "BG" => bg::generate(opts, rng),
+        "HR" => hr::generate(opts, rng),
+        "SI" => jmbg::generate_si(opts, rng),
+        "RS" => jmbg::generate_rs(opts, rng),
+        "BA" => jmbg::generate_ba(opts, rng),
+        "ME" => jmbg::generate_me(opts, rng),
+        "TR" => tr::generate(opts, rng),
+        "GR" => gr::generate(opts, rng),
+        "US" => us::generate(opts, rng),
+        "CA" => ca::generate(opts, rng),
+        "BR" => br::generate(opts, rng),
+        "AR" => ar::generate(opts, rng),
+        "CL" => cl::generate(opts, rng),
+        "CO" => co::generate(opts, rng),
+        "UY" => uy::generate(opts, rng),
+        "CU" => cuban_id(opts, rng),
+        "DO" => dominican_cedula(rng),
+        "EC" => ec::generate(opts, rng),
+        "PE" => pe::generate(opts, rng),
+        "MX" => mx::generate(opts, rng),
+        "CN" => cn::generate(opts, rng),
+        "IN" => in_::generate(opts, rng),
+        "JP" => jp::generate(opts, rng),
+        "KR" => kr::generate(opts, rng),
+        "TW" => tw::generate(opts, rng),
+        "TH" => th::generate(opts, rng),
+        "SG" => sg::generate(opts, rng),
+        "MY" => my::generate(opts, rng),
+        "ID" => id_::generate(opts, rng),
+        "HK" => hk::generate(opts, rng),
+        "AU" => au::generate(opts, rng),
+        "NZ" => nz::generate(opts, rng),
+        "ZA" => za::generate(opts, rng),
+        "IL" => il::generate(opts, rng),
+        "EG" => eg::generate(opts, rng),
+        "DZ" => dz::generate(opts, rng),
+        "MU" => mauritian_nic(rng),
+        "PK" => pakistani_cnic(opts, rng),
+        "SA" => sa::generate(opts, rng),
+        "KZ" => kz::generate(opts, rng),
+        "UA" => ua::generate(opts, rng),
+        "LU" => lu::generate(opts, rng),
+        _ => return personal_id(parent(ID_TERRITORIES, country)?, opts, rng),
+    };
+    Some(code)
+}
+
+fn digits<R: Rng>(count: usize, rng: &mut R) -> String {
+    (0..count)
+        .map(|_| char::from(b'0' + rng.gen_range(0..10u8)))
+        .collect()
+}
+
+/// Cuban carné de identidad: YYMMDD, a century digit, a serial and a
+/// gender digit (even for men).
+fn cuban_id<R: Rng>(opts: &personal_id::GenOptions, rng: &mut R) -> String {
+    let year = opts.year.unwrap_or_else(|| rng.gen_range(1950..=2005));
+    let month = rng.gen_range(1..=12u8);
+    let day = rng.gen_range(1..=28u8);
+    let century = match year {
+        1800..=1899 => 9,
+        2000..=2099 => rng.gen_range(6..=8u8),
+        _ => rng.gen_range(0..=5u8),
+    };
+    let serial = rng.gen_range(0..=99u8);
+    let gender = match opts.gender {
+        Some(Gender::Male) => rng.gen_range(0..=4u8) * 2,
+        Some(Gender::Female) => rng.gen_range(0..=4u8) * 2 + 1,
+        None => rng.gen_range(0..=9u8),
+    };
+    format!(
+        "{:02}{:02}{:02}{}{:02}{}{}",
+        year % 100,
+        month,
+        day,
+        century,
+        serial,
+        gender,
+        rng.gen_range(0..=9u8)
+    )
+}
+
+/// Dominican cédula: ten digits and a Luhn check digit.
+fn dominican_cedula<R: Rng>(rng: &mut R) -> String {
+    let mut digits: Vec<u8> = (0..10).map(|_| rng.gen_range(0..=9u8)).collect();
+    digits.push(personal_id::checksum::luhn_check(&digits));
+    digits.iter().map(|d| char::from(b'0' + d)).collect()
+}
+
+/// Mauritian NIC: a letter, DDMMYY, six digits and a mod-17 check character.
+fn mauritian_nic<R: Rng>(rng: &mut R) -> String {
+    const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let partial = format!(
+        "{}{:02}{:02}{:02}{}",
+        char::from(b'A' + rng.gen_range(0..26u8)),
+        rng.gen_range(1..=28u8),
+        rng.gen_range(1..=12u8),
+        rng.gen_range(0..=99u8),
+        digits(6, rng)
+    );
+    let sum: u32 = partial
+        .bytes()
+        .enumerate()
+        .map(|(i, c)| {
+            let value = match c {
+                b'0'..=b'9' => (c - b'0') as u32,
+                _ => (c - b'A' + 10) as u32,
+            };
+            (14 - i as u32) * value
+        })
+        .sum();
+    let check = ALPHABET[((17 - sum % 17) % 17) as usize];
+    format!("{}{}", partial, char::from(check))
+}
+
+/// Pakistani CNIC: province, locality, serial and a gender digit (odd for
+/// men).
+fn pakistani_cnic<R: Rng>(opts: &personal_id::GenOptions, rng: &mut R) -> String {
+    let province = rng.gen_range(1..=7u8);
+    let locality = digits(4, rng);
+    let serial = digits(7, rng);
+    let gender = match opts.gender {
+        Some(Gender::Male) => rng.gen_range(0..=4u8) * 2 + 1,
+        Some(Gender::Female) => rng.gen_range(1..=4u8) * 2,
+        None => rng.gen_range(1..=9u8),
+    };
+    format!("{}{}{}{}", province, locality, serial, gender)
+}
+
+/// A bank account for `country`, or `None` if idsmith has no format for it.
+pub fn bank_account<R: Rng>(
+    country: &str,
+    opts: &bank_account::GenOptions,
+    rng: &mut R,
+) -> Option<AccountResult> {
+    use bank_account::*;
+    let account = match country {
+        "US" => us::generate(opts, rng),
+        "CA" => ca::generate(opts, rng),
+        "MX" => mx::generate(opts, rng),
+        "AU" => au::generate(opts, rng),
+        "IN" => in_::generate(opts, rng),
+        "JP" => jp::generate(opts, rng),
+        "CN" => cn::generate(opts, rng),
+        "ZA" => za::generate(opts, rng),
+        "NZ" => nz::generate(opts, rng),
+        "SG" => sg::generate(opts, rng),
+        "HK" => hk::generate(opts, rng),
+        "KR" => kr::generate(opts, rng),
+        "BR" => br::generate(opts, rng),
+        "AR" => ar::generate(opts, rng),
+        "NG" => ng::generate(opts, rng),
+        "GB" => {
+            let mut account = gb::generate(opts, rng);
+            account.iban = idsmith::iban::generate_iban(Some("GB"), rng).ok();
+            account
+        }
+        _ => match iban_based::generate(country, rng) {
+            Some(account) => account,
+            None => {
+                let mut account = bank_account(parent(BANK_TERRITORIES, country)?, opts, rng)?;
+                account.country_code = country.to_string();
+                account
+            }
+        },
+    };
+    Some(account)
+}
+
+/// Samples drawn from idsmith to learn a company ID format.
+const SAMPLES: usize = 4096;
+/// Draws tried before giving up on a validating company ID.
+const ATTEMPTS: usize = 10_000;
+
+/// A country's company IDs as idsmith generates them: the characters seen
+/// at each position, for every length that makes up at least a twentieth
+/// of the samples. Both are settled well within [`SAMPLES`], so the same
+/// seed fills in the same IDs in every run.
+struct Format {
+    sample: CompanyResult,
+    shapes: Vec<Vec<Vec<char>>>,
+    /// Whether idsmith's own IDs pass its validator, so draws can be checked
+    /// against it. A few countries' don't.
+    checked: bool,
+}
+
+impl Format {
+    fn learn(reg: &company_id::Registry, country: &str) -> Option<Self> {
+        let opts = company_id::GenOptions {
+            country: Some(country.to_string()),
+        };
+        let rng = &mut rand::thread_rng();
+        let samples: Vec<CompanyResult> = (0..SAMPLES)
+            .map_while(|_| reg.generate(&opts, rng))
+            .collect();
+        let sample = samples.first()?.clone();
+        let valid = samples
+            .iter()
+            .filter(|s| reg.validate(country, &s.code))
+            .count();
+        let mut by_length: BTreeMap<usize, Vec<Vec<char>>> = BTreeMap::new();
+        for s in &samples {
+            let chars: Vec<char> = s.code.chars().collect();
+            by_length.entry(chars.len()).or_default().push(chars);
+        }
+        let shapes = by_length
+            .into_iter()
+            .filter(|(_, codes)| codes.len() * 20 >= samples.len())
+            .map(|(length, codes)| {
+                (0..length)
+                    .map(|i| {
+                        let seen: BTreeSet<char> = codes.iter().map(|c| c[i]).collect();
+                        seen.into_iter().collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(Self {
+            sample,
+            shapes,
+            checked: valid * 2 > samples.len(),
+        })
+    }
+}
+
+/// Formats learned so far, by country; `None` where idsmith has none.
+static FORMATS: Mutex<BTreeMap<String, Option<Format>>> = Mutex::new(BTreeMap::new());
+
+/// A company ID for `country`, or `None` if idsmith has no format for it.
+pub fn company_id<R: Rng>(
+    reg: &company_id::Registry,
+    country: &str,
+    rng: &mut R,
+) -> Option<CompanyResult> {
+    let country = country.to_uppercase();
+    let mut formats = FORMATS.lock().unwrap_or_else(|e| e.into_inner());
+    let format = formats
+        .entry(country.clone())
+        .or_insert_with(|| Format::learn(reg, &country))
+        .as_ref()?;
+    for _ in 0..ATTEMPTS {
+        let shape = &format.shapes[rng.gen_range(0..format.shapes.len())];
+        let code: String = shape
+            .iter()
+            .map(|seen| seen[rng.gen_range(0..seen.len())])
+            .collect();
+        let valid = reg.validate(&country, &code);
+        if valid || !format.checked {
+            return Some(CompanyResult {
+                code,
+                valid,
+                ..format.sample.clone()
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+
+    #[test]
+    fn personal_ids_follow_the_seed_and_validate() {
+        let reg = personal_id::Registry::new();
+        for (country, _, _) in reg.list_countries() {
+            let opts = personal_id::GenOptions {
+                gender: Some(Gender::Female),
+                year: Some(1984),
+            };
+            let a = personal_id(country, &opts, &mut seeded_rng(7)).unwrap();
+            let b = personal_id(country, &opts, &mut seeded_rng(7)).unwrap();
+            assert_eq!(a, b, "{}", country);
+            assert_eq!(reg.validate(country, &a), Some(true), "{} {}", country, a);
+        }
+        for written_out in ["CU", "PK"] {
+            let code = personal_id(written_out, &Default::default(), &mut seeded_rng(1)).unwrap();
+            assert_eq!(reg.validate(written_out, &code), Some(true), "{}", code);
+        }
+        assert!(personal_id("GU", &Default::default(), &mut seeded_rng(1)).is_some());
+        assert_eq!(
+            personal_id("XX", &Default::default(), &mut seeded_rng(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn bank_accounts_follow_the_seed() {
+        let reg = bank_account::Registry::new();
+        for country in ["US", "GB", "DE", "BR", "PR", "EH"] {
+            let opts = bank_account::GenOptions::default();
+            let a = bank_account(country, &opts, &mut seeded_rng(3)).unwrap();
+            let b = bank_account(country, &opts, &mut seeded_rng(3)).unwrap();
+            assert_eq!(a.raw, b.raw, "{}", country);
+            assert_eq!(a.iban, b.iban, "{}", country);
+            assert_eq!(a.country_code, country);
+            assert_eq!(reg.validate(country, &a.raw), Some(true), "{}", country);
+        }
+        assert!(bank_account("XX", &Default::default(), &mut seeded_rng(1)).is_none());
+    }
+
+    #[test]
+    fn company_ids_follow_the_seed_and_validate() {
+        let reg = company_id::Registry::new();
+        for country in ["DE", "GB", "CH", "BR", "SM", "LI"] {
+            let a: Vec<String> = (0..5)
+                .scan(seeded_rng(11), |rng, _| company_id(&reg, country, rng))
+                .map(|r| r.code)
+                .collect();
+            let b: Vec<String> = (0..5)
+                .scan(seeded_rng(11), |rng, _| company_id(&reg, country, rng))
+                .map(|r| r.code)
+                .collect();
+            assert_eq!(a, b, "{}", country);
+            assert!(a.iter().all(|code| reg.validate(country, code)), "{:?}", a);
+        }
+        // Learned again from fresh samples, the format fills in the same IDs.
+        let first = company_id(&reg, "LI", &mut seeded_rng(5)).unwrap();
+        FORMATS.lock().unwrap().remove("LI");
+        assert_eq!(
+            company_id(&reg, "LI", &mut seeded_rng(5)).unwrap().code,
+            first.code
+        );
+    }
+}
@@ -0,0 +1,929 @@
+//! SEPA payment files (ISO 20022 pain messages) built around generated
+//! IBANs and BICs.
+//!
+//! Every party gets a fresh IBAN from `iban::generate_iban` and a BIC from
+//! the SWIFT registry for the same country (the same bank, where the IBAN
+//! embeds the bank code), so the files pass both schema validation and the
+//! account checks an ingestion pipeline runs on them.
+
+use idsmith::swift;
+use rand::Rng;
+
+use crate::dates;
+use crate::generate::{iban_for_bic, mod97};
+
+/// Countries and territories in the SEPA scheme.
+pub const SEPA_COUNTRIES: &[&str] = &[
+    "AD", "AT", "BE", "BG", "CH", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GB", "GI", "GR",
+    "HR", "HU", "IE", "IS", "IT", "LI", "LT", "LU", "LV", "MC", "MT", "NL", "NO", "PL", "PT", "RO",
+    "SE", "SI", "SK", "SM", "VA",
+];
+
+/// National identifier length used when generating a Creditor Identifier
+/// for each SEPA country, and whether it may contain letters.
+const CREDITOR_ID_FORMATS: &[(&str, usize, bool)] = &[
+    ("AD", 8, false),
+    ("AT", 11, false),
+    ("BE", 10, false),
+    ("BG", 10, false),
+    ("CH", 11, false),
+    ("CY", 9, true),
+    ("CZ", 8, false),
+    ("DE", 11, false),
+    ("DK", 8, false),
+    ("EE", 8, false),
+    ("ES", 9, true),
+    ("FI", 8, false),
+    ("FR", 6, true),
+    ("GB", 6, true),
+    ("GI", 6, true),
+    ("GR", 9, false),
+    ("HR", 11, false),
+    ("HU", 8, false),
+    ("IE", 6, true),
+    ("IS", 10, false),
+    ("IT", 11, false),
+    ("LI", 11, false),
+    ("LT", 9, false),
+    ("LU", 19, false),
+    ("LV", 11, false),
+    ("MC", 6, true),
+    ("MT", 8, false),
+    ("NL", 12, false),
+    ("NO", 9, false),
+    ("PL", 10, false),
+    ("PT", 6, false),
+    ("RO", 8, false),
+    ("SE", 10, false),
+    ("SI", 8, false),
+    ("SK", 10, false),
+    ("SM", 11, false),
+    ("VA", 11, false),
+];
+
+/// Creditor Business Code used when the creditor has none.
+const DEFAULT_BUSINESS_CODE: &str = "ZZZ";
+
+/// Generates a SEPA Creditor Identifier, e.g. `DE98ZZZ09999999999`:
+/// country, two check digits, the `ZZZ` business code and a national
+/// identifier. `None` outside SEPA.
+pub fn creditor_identifier<R: Rng>(country: &str, rng: &mut R) -> Option<String> {
+    const CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let &(_, len, letters) = CREDITOR_ID_FORMATS.iter().find(|(c, _, _)| *c == country)?;
+    let alphabet = if letters { CHARS } else { &CHARS[..10] };
+    let national: String = (0..len)
+        .map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char)
+        .collect();
+    Some(format!(
+        "{}{:02}{}{}",
+        country,
+        creditor_check_digits(country, &national),
+        DEFAULT_BUSINESS_CODE,
+        national
+    ))
+}
+
+/// ISO 7064 mod 97-10 check digits over the national identifier and the
+/// country code; the business code is not covered.
+fn creditor_check_digits(country: &str, national: &str) -> u32 {
+    98 - mod97(&format!("{}{}00", national, country))
+}
+
+/// Checks a SEPA Creditor Identifier, explaining the first problem found.
+/// Spaces are ignored and letters may be lower case.
+pub fn validate_creditor_identifier(value: &str) -> Result<(), &'static str> {
+    let ci: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if !(8..=35).contains(&ci.len()) || !ci.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("must be 8 to 35 letters and digits");
+    }
+    let (country, check, national) = (&ci[..2], &ci[2..4], &ci[7..]);
+    if !SEPA_COUNTRIES.contains(&country) {
+        return Err("country is not in the SEPA scheme");
+    }
+    let Ok(check) = check.parse::<u32>() else {
+        return Err("check digits must be numeric");
+    };
+    if check != creditor_check_digits(country, national) {
+        return Err("check digits do not match");
+    }
+    Ok(())
+}
+
+/// Options for [`credit_transfer`]. Amounts are in cents.
+#[derive(Clone, Debug)]
+pub struct CreditTransferOptions {
+    /// Number of `PmtInf` blocks, one debtor account each.
+    pub payment_infos: u32,
+    /// `CdtTrfTxInf` entries per payment info.
+    pub transactions: u32,
+    pub currency: String,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    /// Requested execution date, `YYYY-MM-DD`.
+    pub execution_date: String,
+    /// Group header creation time, `YYYY-MM-DDThh:mm:ss`. Empty uses the
+    /// execution date at 08:00 so seeded files are reproducible.
+    pub created_at: String,
+    /// `None` picks a random SEPA country per party.
+    pub debtor_country: Option<String>,
+    pub creditor_country: Option<String>,
+}
+
+impl Default for CreditTransferOptions {
+    fn default() -> Self {
+        Self {
+            payment_infos: 1,
+            transactions: 5,
+            currency: "EUR".to_string(),
+            min_amount: 100,
+            max_amount: 100_000,
+            execution_date: "2025-01-01".to_string(),
+            created_at: String::new(),
+            debtor_country: None,
+            creditor_country: None,
+        }
+    }
+}
+
+/// A generated account holder: name, IBAN and the BIC of its bank.
+pub(crate) struct Party {
+    pub(crate) country: String,
+    pub(crate) name: String,
+    pub(crate) iban: String,
+    pub(crate) bic: String,
+}
+
+impl Party {
+    /// `country` of `None` picks a random SEPA country; a country outside
+    /// SEPA, which would leave the party without an IBAN, is an error.
+    pub(crate) fn generate<R: Rng>(
+        swift: &swift::Registry,
+        role: &str,
+        n: u32,
+        country: Option<&str>,
+        rng: &mut R,
+    ) -> Result<Self, String> {
+        let country = match country {
+            Some(c) if SEPA_COUNTRIES.contains(&c) => c.to_string(),
+            Some(c) => return Err(format!("'{}' is not a SEPA country", c)),
+            None => SEPA_COUNTRIES[rng.gen_range(0..SEPA_COUNTRIES.len())].to_string(),
+        };
+        let bic = swift
+            .generate(
+                &swift::GenOptions {
+                    country: Some(country.clone()),
+                },
+                rng,
+            )
+            .code;
+        let iban = iban_for_bic(&country, &bic, rng)
+            .ok_or_else(|| format!("no IBAN format for '{}'", country))?;
+        Ok(Self {
+            name: format!("{} {:03}", role, n),
+            iban,
+            bic,
+            country,
+        })
+    }
+}
+
+/// Parses a decimal amount like `"12.5"` or `"1,000.00"` into cents.
+pub fn parse_amount(input: &str) -> Option<u64> {
+    let input: String = input.trim().chars().filter(|c| *c != ',').collect();
+    let (units, cents) = input.split_once('.').unwrap_or((&input, ""));
+    if cents.len() > 2 || !cents.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let units: u64 = units.parse().ok()?;
+    let cents: u64 = format!("{:0<2}", cents).parse().ok()?;
+    Some(units * 100 + cents)
+}
+
+/// [`parse_amount`] with an optional leading minus sign.
+pub fn parse_signed_amount(input: &str) -> Option<i64> {
+    match input.trim().strip_prefix('-') {
+        Some(rest) => parse_amount(rest).map(|c| -(c as i64)),
+        None => parse_amount(input).map(|c| c as i64),
+    }
+}
+
+/// Formats cents as the ISO 20022 decimal amount, e.g. `1234.50`.
+pub fn format_amount(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/// Escapes the five XML special characters.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A random identifier of up to 35 characters, as ISO 20022 IDs allow.
+pub(crate) fn reference<R: Rng>(prefix: &str, rng: &mut R) -> String {
+    format!("{}-{:016X}", prefix, rng.gen_range(0..u64::MAX))
+}
+
+/// The `CreDtTm` of a file: `created_at` if it is a `YYYY-MM-DDThh:mm:ss`
+/// time, or 08:00 on `date` when it is empty.
+pub(crate) fn created_at(created_at: &str, date: &str) -> Result<String, String> {
+    if created_at.is_empty() {
+        return Ok(format!("{}T08:00:00", date));
+    }
+    let error = || {
+        format!(
+            "creation time '{}' isn't a YYYY-MM-DDThh:mm:ss time",
+            created_at
+        )
+    };
+    let (day, time) = created_at.split_once('T').ok_or_else(error)?;
+    let mut parts = time
+        .split(':')
+        .map(|p| (p.len() == 2).then(|| p.parse::<u32>().ok()).flatten());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Some(h)), Some(Some(m)), Some(Some(s)), None)
+            if dates::parse(day).is_some() && h < 24 && m < 60 && s < 60 =>
+        {
+            Ok(created_at.to_string())
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Builds a SEPA Credit Transfer Initiation (`pain.001.001.09`) file.
+///
+/// Each payment info debits one generated debtor account; each transaction
+/// pays a generated creditor a random amount between `min_amount` and
+/// `max_amount`. Non-EUR files drop the `SEPA` service level and charge
+/// bearer `SLEV`, which the scheme only allows for euro payments. An invalid
+/// `execution_date` or `created_at` and a debtor or creditor country outside
+/// SEPA are errors.
+pub fn credit_transfer<R: Rng>(
+    swift: &swift::Registry,
+    opts: &CreditTransferOptions,
+    rng: &mut R,
+) -> Result<String, String> {
+    // The schema needs at least one of each.
+    let payment_infos = opts.payment_infos.max(1);
+    let per_payment = opts.transactions.max(1);
+    let sepa = opts.currency == "EUR";
+    if dates::parse(&opts.execution_date).is_none() {
+        return Err(format!(
+            "execution date '{}' isn't a YYYY-MM-DD date",
+            opts.execution_date
+        ));
+    }
+    let created = created_at(&opts.created_at, &opts.execution_date)?;
+    let currency = escape(&opts.currency);
+    let (min, max) = (
+        opts.min_amount.min(opts.max_amount),
+        opts.min_amount.max(opts.max_amount),
+    );
+
+    let mut payments = String::new();
+    let mut total_count = 0u64;
+    let mut total_sum = 0u64;
+    for p in 1..=payment_infos {
+        let debtor = Party::generate(swift, "Debtor", p, opts.debtor_country.as_deref(), rng)?;
+        let mut transactions = String::new();
+        let mut sum = 0u64;
+        for t in 1..=per_payment {
+            let n = (p - 1) * per_payment + t;
+            let creditor =
+                Party::generate(swift, "Creditor", n, opts.creditor_country.as_deref(), rng)?;
+            let amount = rng.gen_range(min..=max);
+            sum += amount;
+            transactions.push_str(&format!(
+                "      <CdtTrfTxInf>
+        <PmtId>
+          <EndToEndId>{e2e}</EndToEndId>
+        </PmtId>
+        <Amt>
+          <InstdAmt Ccy=\"{currency}\">{amount}</InstdAmt>
+        </Amt>
+        <CdtrAgt>
+          <FinInstnId>
+            <BICFI>{bic}</BICFI>
+          </FinInstnId>
+        </CdtrAgt>
+        <Cdtr>
+          <Nm>{name}</Nm>
+        </Cdtr>
+        <CdtrAcct>
+          <Id>
+            <IBAN>{iban}</IBAN>
+          </Id>
+        </CdtrAcct>
+        <RmtInf>
+          <Ustrd>Invoice {n:06}</Ustrd>
+        </RmtInf>
+      </CdtTrfTxInf>
+",
+                e2e = reference("E2E", rng),
+                amount = format_amount(amount),
+                bic = creditor.bic,
+                name = creditor.name,
+                iban = creditor.iban,
+            ));
+        }
+        total_count += per_payment as u64;
+        total_sum += sum;
+
+        payments.push_str(&format!(
+            "    <PmtInf>
+      <PmtInfId>{id}</PmtInfId>
+      <PmtMtd>TRF</PmtMtd>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{sum}</CtrlSum>
+{service_level}      <ReqdExctnDt>
+        <Dt>{date}</Dt>
+      </ReqdExctnDt>
+      <Dbtr>
+        <Nm>{name}</Nm>
+      </Dbtr>
+      <DbtrAcct>
+        <Id>
+          <IBAN>{iban}</IBAN>
+        </Id>
+      </DbtrAcct>
+      <DbtrAgt>
+        <FinInstnId>
+          <BICFI>{bic}</BICFI>
+        </FinInstnId>
+      </DbtrAgt>
+      <ChrgBr>{charges}</ChrgBr>
+{transactions}    </PmtInf>
+",
+            id = reference("PMT", rng),
+            count = per_payment,
+            sum = format_amount(sum),
+            service_level = if sepa {
+                "      <PmtTpInf>\n        <SvcLvl>\n          <Cd>SEPA</Cd>\n        </SvcLvl>\n      </PmtTpInf>\n"
+            } else {
+                ""
+            },
+            date = escape(&opts.execution_date),
+            name = debtor.name,
+            iban = debtor.iban,
+            bic = debtor.bic,
+            charges = if sepa { "SLEV" } else { "SHAR" },
+        ));
+    }
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.09\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">
+  <CstmrCdtTrfInitn>
+    <GrpHdr>
+      <MsgId>{id}</MsgId>
+      <CreDtTm>{created}</CreDtTm>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{sum}</CtrlSum>
+      <InitgPty>
+        <Nm>MockBanker</Nm>
+      </InitgPty>
+    </GrpHdr>
+{payments}  </CstmrCdtTrfInitn>
+</Document>
+",
+        id = reference("MSG", rng),
+        created = escape(&created),
+        count = total_count,
+        sum = format_amount(total_sum),
+    ))
+}
+
+/// Direct debit sequence types, in the order a mandate goes through them.
+pub const SEQUENCE_TYPES: &[(&str, &str)] = &[
+    ("FRST", "First"),
+    ("RCUR", "Recurring"),
+    ("FNAL", "Final"),
+    ("OOFF", "One-off"),
+];
+
+/// Direct debit schemes: consumer debtors, or business debtors only.
+pub const SCHEMES: &[&str] = &["CORE", "B2B"];
+
+/// Options for [`direct_debit`]. Amounts are in cents.
+#[derive(Clone, Debug)]
+pub struct DirectDebitOptions {
+    /// Number of `PmtInf` blocks, one creditor and sequence type each.
+    pub payment_infos: u32,
+    /// `DrctDbtTxInf` entries per payment info.
+    pub transactions: u32,
+    pub currency: String,
+    pub min_amount: u64,
+    pub max_amount: u64,
+    /// Requested collection date, `YYYY-MM-DD`. Mandates are signed up to
+    /// two years before it.
+    pub collection_date: String,
+    /// Same as [`CreditTransferOptions::created_at`].
+    pub created_at: String,
+    /// One of [`SCHEMES`].
+    pub scheme: String,
+    /// One of [`SEQUENCE_TYPES`]; `None` draws one per payment info.
+    pub sequence_type: Option<String>,
+    /// `None` picks a random SEPA country per party.
+    pub creditor_country: Option<String>,
+    pub debtor_country: Option<String>,
+}
+
+impl Default for DirectDebitOptions {
+    fn default() -> Self {
+        Self {
+            payment_infos: 1,
+            transactions: 5,
+            currency: "EUR".to_string(),
+            min_amount: 100,
+            max_amount: 100_000,
+            collection_date: "2025-01-01".to_string(),
+            created_at: String::new(),
+            scheme: "CORE".to_string(),
+            sequence_type: None,
+            creditor_country: None,
+            debtor_country: None,
+        }
+    }
+}
+
+/// Builds a SEPA Direct Debit Initiation (`pain.008.001.08`) file.
+///
+/// Each payment info collects for one generated creditor, identified by a
+/// Creditor Identifier from its country, under one sequence type; each
+/// transaction debits a generated debtor under its own mandate. The scheme
+/// only collects euros, so another currency is an error, as are an unknown
+/// scheme or sequence type, an invalid `collection_date` or `created_at` and
+/// a creditor or debtor country outside SEPA.
+pub fn direct_debit<R: Rng>(
+    swift: &swift::Registry,
+    opts: &DirectDebitOptions,
+    rng: &mut R,
+) -> Result<String, String> {
+    // The schema needs at least one of each.
+    let payment_infos = opts.payment_infos.max(1);
+    let per_payment = opts.transactions.max(1);
+    if opts.currency != "EUR" {
+        return Err(format!(
+            "SEPA direct debits are collected in EUR, not '{}'",
+            opts.currency
+        ));
+    }
+    if !SCHEMES.contains(&opts.scheme.as_str()) {
+        return Err(format!("unknown scheme '{}'", opts.scheme));
+    }
+    if let Some(s) = &opts.sequence_type
+        && !SEQUENCE_TYPES.iter().any(|(code, _)| code == s)
+    {
+        return Err(format!("unknown sequence type '{}'", s));
+    }
+    let collection = dates::parse(&opts.collection_date).ok_or_else(|| {
+        format!(
+            "collection date '{}' isn't a YYYY-MM-DD date",
+            opts.collection_date
+        )
+    })?;
+    let currency = escape(&opts.currency);
+    let (min, max) = (
+        opts.min_amount.min(opts.max_amount),
+        opts.min_amount.max(opts.max_amount),
+    );
+
+    let mut payments = String::new();
+    let mut total_count = 0u64;
+    let mut total_sum = 0u64;
+    for p in 1..=payment_infos {
+        let creditor =
+            Party::generate(swift, "Creditor", p, opts.creditor_country.as_deref(), rng)?;
+        let creditor_id = creditor_identifier(&creditor.country, rng).unwrap_or_default();
+        let sequence = match &opts.sequence_type {
+            Some(s) => s.clone(),
+            None => SEQUENCE_TYPES[rng.gen_range(0..SEQUENCE_TYPES.len())]
+                .0
+                .to_string(),
+        };
+
+        let mut transactions = String::new();
+        let mut sum = 0u64;
+        for t in 1..=per_payment {
+            let n = (p - 1) * per_payment + t;
+            let debtor = Party::generate(swift, "Debtor", n, opts.debtor_country.as_deref(), rng)?;
+            let amount = rng.gen_range(min..=max);
+            sum += amount;
+            let signed = dates::format(collection - rng.gen_range(1..=730));
+            transactions.push_str(&format!(
+                "      <DrctDbtTxInf>
+        <PmtId>
+          <EndToEndId>{e2e}</EndToEndId>
+        </PmtId>
+        <InstdAmt Ccy=\"{currency}\">{amount}</InstdAmt>
+        <DrctDbtTx>
+          <MndtRltdInf>
+            <MndtId>{mandate}</MndtId>
+            <DtOfSgntr>{signed}</DtOfSgntr>
+          </MndtRltdInf>
+        </DrctDbtTx>
+        <DbtrAgt>
+          <FinInstnId>
+            <BICFI>{bic}</BICFI>
+          </FinInstnId>
+        </DbtrAgt>
+        <Dbtr>
+          <Nm>{name}</Nm>
+        </Dbtr>
+        <DbtrAcct>
+          <Id>
+            <IBAN>{iban}</IBAN>
+          </Id>
+        </DbtrAcct>
+        <RmtInf>
+          <Ustrd>Invoice {n:06}</Ustrd>
+        </RmtInf>
+      </DrctDbtTxInf>
+",
+                e2e = reference("E2E", rng),
+                amount = format_amount(amount),
+                mandate = reference("MNDT", rng),
+                bic = debtor.bic,
+                name = debtor.name,
+                iban = debtor.iban,
+            ));
+        }
+        total_count += per_payment as u64;
+        total_sum += sum;
+
+        payments.push_str(&format!(
+            "    <PmtInf>
+      <PmtInfId>{id}</PmtInfId>
+      <PmtMtd>DD</PmtMtd>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{sum}</CtrlSum>
+      <PmtTpInf>
+        <SvcLvl>
+          <Cd>SEPA</Cd>
+        </SvcLvl>
+        <LclInstrm>
+          <Cd>{scheme}</Cd>
+        </LclInstrm>
+        <SeqTp>{sequence}</SeqTp>
+      </PmtTpInf>
+      <ReqdColltnDt>{date}</ReqdColltnDt>
+      <Cdtr>
+        <Nm>{name}</Nm>
+      </Cdtr>
+      <CdtrAcct>
+        <Id>
+          <IBAN>{iban}</IBAN>
+        </Id>
+      </CdtrAcct>
+      <CdtrAgt>
+        <FinInstnId>
+          <BICFI>{bic}</BICFI>
+        </FinInstnId>
+      </CdtrAgt>
+      <ChrgBr>SLEV</ChrgBr>
+      <CdtrSchmeId>
+        <Id>
+          <PrvtId>
+            <Othr>
+              <Id>{creditor_id}</Id>
+              <SchmeNm>
+                <Prtry>SEPA</Prtry>
+              </SchmeNm>
+            </Othr>
+          </PrvtId>
+        </Id>
+      </CdtrSchmeId>
+{transactions}    </PmtInf>
+",
+            id = reference("PMT", rng),
+            count = per_payment,
+            sum = format_amount(sum),
+            scheme = opts.scheme,
+            date = escape(&opts.collection_date),
+            name = creditor.name,
+            iban = creditor.iban,
+            bic = creditor.bic,
+        ));
+    }
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.008.001.08\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">
+  <CstmrDrctDbtInitn>
+    <GrpHdr>
+      <MsgId>{id}</MsgId>
+      <CreDtTm>{created}</CreDtTm>
+      <NbOfTxs>{count}</NbOfTxs>
+      <CtrlSum>{sum}</CtrlSum>
+      <InitgPty>
+        <Nm>MockBanker</Nm>
+      </InitgPty>
+    </GrpHdr>
+{payments}  </CstmrDrctDbtInitn>
+</Document>
+",
+        id = reference("MSG", rng),
+        created = escape(&created_at(&opts.created_at, &opts.collection_date)?),
+        count = total_count,
+        sum = format_amount(total_sum),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+    use idsmith::iban;
+
+    /// The text of every `<tag ...>text</tag>` in `xml`, in order.
+    fn values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+        let (open, close) = (format!("<{}", tag), format!("</{}>", tag));
+        let mut found = Vec::new();
+        let mut rest = xml;
+        while let Some(start) = rest.find(&open) {
+            rest = &rest[start + open.len()..];
+            if !rest.starts_with(['>', ' ']) {
+                continue;
+            }
+            let body = &rest[rest.find('>').unwrap() + 1..];
+            let end = body.find(&close).unwrap();
+            found.push(&body[..end]);
+            rest = &body[end..];
+        }
+        found
+    }
+
+    fn cents(amounts: &[&str]) -> u64 {
+        amounts.iter().map(|a| parse_amount(a).unwrap()).sum()
+    }
+
+    fn transfer(opts: &CreditTransferOptions) -> Result<String, String> {
+        credit_transfer(&swift::Registry::new(), opts, &mut seeded_rng(7))
+    }
+
+    #[test]
+    fn credit_transfer_counts_and_sums_add_up() {
+        let opts = CreditTransferOptions {
+            payment_infos: 3,
+            transactions: 4,
+            ..Default::default()
+        };
+        let xml = transfer(&opts).unwrap();
+        assert_eq!(values(&xml, "PmtInf").len(), 3);
+        assert_eq!(values(&xml, "CdtTrfTxInf").len(), 12);
+        assert_eq!(values(&xml, "NbOfTxs"), ["12", "4", "4", "4"]);
+
+        let sums = values(&xml, "CtrlSum");
+        let amounts = values(&xml, "InstdAmt");
+        assert_eq!(parse_amount(sums[0]), Some(cents(&amounts)));
+        for (payment, sum) in values(&xml, "PmtInf").iter().zip(&sums[1..]) {
+            assert_eq!(parse_amount(sum), Some(cents(&values(payment, "InstdAmt"))));
+        }
+        for amount in amounts {
+            let amount = parse_amount(amount).unwrap();
+            assert!((opts.min_amount..=opts.max_amount).contains(&amount));
+        }
+    }
+
+    #[test]
+    fn credit_transfer_has_the_pain_001_layout() {
+        let xml = transfer(&CreditTransferOptions::default()).unwrap();
+        assert!(xml.contains("urn:iso:std:iso:20022:tech:xsd:pain.001.001.09"));
+        let order = [
+            "<GrpHdr>",
+            "<MsgId>",
+            "<CreDtTm>",
+            "<NbOfTxs>",
+            "<CtrlSum>",
+            "<InitgPty>",
+            "<PmtInf>",
+            "<PmtInfId>",
+            "<PmtMtd>TRF",
+            "<PmtTpInf>",
+            "<ReqdExctnDt>",
+            "<Dbtr>",
+            "<DbtrAcct>",
+            "<DbtrAgt>",
+            "<ChrgBr>SLEV",
+            "<CdtTrfTxInf>",
+            "<PmtId>",
+            "<Amt>",
+            "<CdtrAgt>",
+            "<Cdtr>",
+            "<CdtrAcct>",
+            "<RmtInf>",
+        ];
+        let positions: Vec<usize> = order.iter().map(|tag| xml.find(tag).unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]), "{positions:?}");
+        assert_eq!(values(&xml, "CreDtTm"), ["2025-01-01T08:00:00"]);
+        assert_eq!(values(&xml, "Dt"), ["2025-01-01"]);
+        for account in values(&xml, "IBAN") {
+            assert!(iban::validate_iban(account), "{account}");
+        }
+    }
+
+    #[test]
+    fn credit_transfer_outside_euro_drops_the_sepa_service_level() {
+        let xml = transfer(&CreditTransferOptions {
+            currency: "USD".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!xml.contains("<PmtTpInf>"));
+        assert_eq!(values(&xml, "ChrgBr"), ["SHAR"]);
+        assert!(xml.contains("<InstdAmt Ccy=\"USD\">"));
+    }
+
+    #[test]
+    fn credit_transfer_keeps_countries_and_seed() {
+        let opts = CreditTransferOptions {
+            debtor_country: Some("DE".to_string()),
+            creditor_country: Some("FR".to_string()),
+            ..Default::default()
+        };
+        let xml = transfer(&opts).unwrap();
+        let ibans = values(&xml, "IBAN");
+        assert!(ibans[0].starts_with("DE"));
+        assert!(ibans[1..].iter().all(|i| i.starts_with("FR")));
+        assert_eq!(xml, transfer(&opts).unwrap());
+    }
+
+    #[test]
+    fn credit_transfer_rejects_bad_input() {
+        let bad = [
+            CreditTransferOptions {
+                execution_date: "2025-02-30".to_string(),
+                ..Default::default()
+            },
+            CreditTransferOptions {
+                created_at: "2025-01-01 08:00".to_string(),
+                ..Default::default()
+            },
+            CreditTransferOptions {
+                created_at: "2025-01-01T24:00:00".to_string(),
+                ..Default::default()
+            },
+            CreditTransferOptions {
+                debtor_country: Some("US".to_string()),
+                ..Default::default()
+            },
+        ];
+        for opts in bad {
+            assert!(transfer(&opts).is_err(), "{opts:?}");
+        }
+        let xml = transfer(&CreditTransferOptions {
+            created_at: "2024-12-31T23:59:59".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(values(&xml, "CreDtTm"), ["2024-12-31T23:59:59"]);
+    }
+
+    #[test]
+    fn amounts_parse_and_format() {
+        assert_eq!(parse_amount("12.5"), Some(1250));
+        assert_eq!(parse_amount("1,000.00"), Some(100_000));
+        assert_eq!(parse_amount("1.234"), None);
+        assert_eq!(parse_signed_amount("-3.07"), Some(-307));
+        assert_eq!(format_amount(123_450), "1234.50");
+    }
+
+    fn debit(opts: &DirectDebitOptions) -> Result<String, String> {
+        direct_debit(&swift::Registry::new(), opts, &mut seeded_rng(8))
+    }
+
+    #[test]
+    fn creditor_identifiers_validate_for_every_sepa_country() {
+        let mut rng = seeded_rng(1);
+        for country in SEPA_COUNTRIES {
+            let ci = creditor_identifier(country, &mut rng).unwrap();
+            assert!(ci.starts_with(country) && ci[4..7] == *"ZZZ", "{ci}");
+            assert_eq!(validate_creditor_identifier(&ci), Ok(()), "{ci}");
+        }
+        assert_eq!(creditor_identifier("US", &mut rng), None);
+    }
+
+    #[test]
+    fn creditor_identifier_checks() {
+        assert_eq!(validate_creditor_identifier("DE98ZZZ09999999999"), Ok(()));
+        assert_eq!(
+            validate_creditor_identifier("de98 zzz0 9999 9999 99"),
+            Ok(())
+        );
+        // The business code isn't covered by the check digits.
+        assert_eq!(validate_creditor_identifier("DE98ABC09999999999"), Ok(()));
+        assert_eq!(
+            validate_creditor_identifier("DE97ZZZ09999999999"),
+            Err("check digits do not match")
+        );
+        assert_eq!(
+            validate_creditor_identifier("US98ZZZ09999999999"),
+            Err("country is not in the SEPA scheme")
+        );
+        assert_eq!(
+            validate_creditor_identifier("DE98"),
+            Err("must be 8 to 35 letters and digits")
+        );
+    }
+
+    #[test]
+    fn direct_debit_counts_sums_and_mandates() {
+        let opts = DirectDebitOptions {
+            payment_infos: 2,
+            transactions: 3,
+            collection_date: "2025-06-02".to_string(),
+            ..Default::default()
+        };
+        let xml = debit(&opts).unwrap();
+        assert!(xml.contains("urn:iso:std:iso:20022:tech:xsd:pain.008.001.08"));
+        assert_eq!(values(&xml, "NbOfTxs"), ["6", "3", "3"]);
+        let sums = values(&xml, "CtrlSum");
+        assert_eq!(
+            parse_amount(sums[0]),
+            Some(cents(&values(&xml, "InstdAmt")))
+        );
+        for (payment, sum) in values(&xml, "PmtInf").iter().zip(&sums[1..]) {
+            assert_eq!(parse_amount(sum), Some(cents(&values(payment, "InstdAmt"))));
+            let other = values(payment, "Othr");
+            assert_eq!(
+                validate_creditor_identifier(values(other[0], "Id")[0]),
+                Ok(())
+            );
+        }
+        let collection = dates::parse("2025-06-02").unwrap();
+        for signed in values(&xml, "DtOfSgntr") {
+            let signed = dates::parse(signed).unwrap();
+            assert!((collection - 730..collection).contains(&signed));
+        }
+        assert_eq!(values(&xml, "MndtId").len(), 6);
+        assert_eq!(
+            values(&xml, "Cd").iter().filter(|c| **c == "CORE").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn direct_debit_schemes_and_sequence_types() {
+        let xml = debit(&DirectDebitOptions {
+            payment_infos: 2,
+            scheme: "B2B".to_string(),
+            sequence_type: Some("RCUR".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(values(&xml, "SeqTp"), ["RCUR", "RCUR"]);
+        assert_eq!(
+            values(&xml, "Cd").iter().filter(|c| **c == "B2B").count(),
+            2
+        );
+
+        let xml = debit(&DirectDebitOptions {
+            payment_infos: 20,
+            transactions: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        for sequence in values(&xml, "SeqTp") {
+            assert!(SEQUENCE_TYPES.iter().any(|(code, _)| *code == sequence));
+        }
+    }
+
+    #[test]
+    fn direct_debit_rejects_bad_input() {
+        let bad = [
+            DirectDebitOptions {
+                scheme: "COR1".to_string(),
+                ..Default::default()
+            },
+            DirectDebitOptions {
+                sequence_type: Some("ONCE".to_string()),
+                ..Default::default()
+            },
+            DirectDebitOptions {
+                currency: "USD".to_string(),
+                ..Default::default()
+            },
+            DirectDebitOptions {
+                collection_date: "2025-13-01".to_string(),
+                ..Default::default()
+            },
+            DirectDebitOptions {
+                created_at: "yesterday".to_string(),
+                ..Default::default()
+            },
+            DirectDebitOptions {
+                creditor_country: Some("JP".to_string()),
+                ..Default::default()
+            },
+        ];
+        for opts in bad {
+            assert!(debit(&opts).is_err(), "{opts:?}");
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Generated rows as test fixtures ready to paste into source code: a Rust
+//! `const` slice of structs, a pytest `parametrize` table, JUnit
+//! `@ValueSource` and `@MethodSource` data, or a TypeScript `as const` array.
+//!
+//! Each row carries every export column, e.g. gender and date of birth
+//! for personal IDs, and the defect of near-miss rows.
+
+use crate::export::{self, Record, SqlType, SqlValue};
+
+/// Language, and for Python and Java test framework, of a snippet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Pytest,
+    Junit,
+    TypeScript,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::Rust,
+        Language::Pytest,
+        Language::Junit,
+        Language::TypeScript,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Pytest => "pytest",
+            Language::Junit => "junit",
+            Language::TypeScript => "typescript",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Pytest => "pytest",
+            Language::Junit => "JUnit",
+            Language::TypeScript => "TypeScript",
+        }
+    }
+
+    /// Source file extension, e.g. `rs`.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Language::Rust => "rs",
+            Language::Pytest => "py",
+            Language::Junit => "java",
+            Language::TypeScript => "ts",
+        }
+    }
+
+    pub fn parse(key: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|l| l.key().eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                format!(
+                    "unknown snippet language '{}', use rust, pytest, junit or typescript",
+                    key
+                )
+            })
+    }
+
+    /// `s` as a double-quoted string literal.
+    fn string(self, s: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in s.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                c if c.is_control() && self == Language::Rust => {
+                    quoted.push_str(&format!("\\u{{{:x}}}", c as u32))
+                }
+                c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// `value` as a literal; Rust wraps values of `nullable` columns in `Some`.
+    fn literal(self, value: &SqlValue, nullable: bool) -> String {
+        let literal = match (value, self) {
+            (SqlValue::Null, Language::Rust | Language::Pytest) => "None".to_string(),
+            (SqlValue::Null, _) => "null".to_string(),
+            (SqlValue::Bool(b), Language::Pytest) => if *b { "True" } else { "False" }.to_string(),
+            (SqlValue::Bool(b), _) => b.to_string(),
+            (SqlValue::Text(s) | SqlValue::Date(s), _) => self.string(s),
+        };
+        if nullable && self == Language::Rust && *value != SqlValue::Null {
+            format!("Some({})", literal)
+        } else {
+            literal
+        }
+    }
+}
+
+/// One exported column: its name and whether any row has no value.
+struct Column {
+    name: &'static str,
+    boolean: bool,
+    nullable: bool,
+}
+
+/// `rows` as a fixture in `language`; a seed is recorded as a leading
+/// comment so the snippet says how to regenerate it.
+pub fn to_snippet<T: Record>(rows: &[T], language: Language, seed: Option<u64>) -> String {
+    let defects = export::has_defects(rows);
+    let values: Vec<Vec<SqlValue>> = rows.iter().map(|r| export::sql_row(r, defects)).collect();
+    let columns: Vec<Column> = export::sql_columns::<T>(defects)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, ty))| Column {
+            name,
+            boolean: ty == SqlType::Boolean,
+            nullable: values.iter().any(|row| row[i] == SqlValue::Null),
+        })
+        .collect();
+
+    let comment = if language == Language::Pytest {
+        "#"
+    } else {
+        "//"
+    };
+    let mut code = seed
+        .map(|s| format!("{} MockBanker seed: {}\n", comment, s))
+        .unwrap_or_default();
+    let constant = T::TABLE.to_uppercase();
+    let tuple = |row: &[SqlValue]| -> Vec<String> {
+        row.iter()
+            .zip(&columns)
+            .map(|(value, column)| language.literal(value, column.nullable))
+            .collect()
+    };
+    match language {
+        Language::Rust => {
+            code.push_str(&format!(
+                "#[derive(Clone, Copy, Debug)]\npub struct {} {{\n",
+                T::NAME
+            ));
+            for column in &columns {
+                let ty = match (column.boolean, column.nullable) {
+                    (true, false) => "bool",
+                    (true, true) => "Option<bool>",
+                    (false, false) => "&'static str",
+                    (false, true) => "Option<&'static str>",
+                };
+                code.push_str(&format!("    pub {}: {},\n", column.name, ty));
+            }
+            code.push_str(&format!(
+                "}}\n\npub const {}: &[{}] = &[\n",
+                constant,
+                T::NAME
+            ));
+            for row in &values {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .zip(tuple(row))
+                    .map(|(column, value)| format!("{}: {}", column.name, value))
+                    .collect();
+                code.push_str(&format!("    {} {{ {} }},\n", T::NAME, fields.join(", ")));
+            }
+            code.push_str("];\n");
+        }
+        Language::Pytest => {
+            let names: Vec<&str> = columns.iter().map(|c| c.name).collect();
+            code.push_str(&format!("import pytest\n\n{} = [\n", constant));
+            for row in &values {
+                code.push_str(&format!("    ({}),\n", tuple(row).join(", ")));
+            }
+            code.push_str(&format!(
+                "]\n\n\n@pytest.mark.parametrize(({}), {})\ndef test_{}({}):\n    ...\n",
+                names
+                    .iter()
+                    .map(|n| language.string(n))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                constant,
+                T::TABLE,
+                names.join(", ")
+            ));
+        }
+        Language::Junit => {
+            let method = camel_case(T::NAME);
+            let factory = camel_case(T::TABLE);
+            let components: Vec<String> = columns
+                .iter()
+                .map(|c| {
+                    let ty = match (c.boolean, c.nullable) {
+                        (true, false) => "boolean",
+                        (true, true) => "Boolean",
+                        (false, _) => "String",
+                    };
+                    format!("{} {}", ty, camel_case(c.name))
+                })
+                .collect();
+            code.push_str(&format!(
+                "record {}({}) {{}}\n\nstatic Stream<{}> {}() {{\n    return Stream.of(",
+                T::NAME,
+                components.join(", "),
+                T::NAME,
+                factory
+            ));
+            let records: Vec<String> = values
+                .iter()
+                .map(|row| format!("\n        new {}({})", T::NAME, tuple(row).join(", ")))
+                .collect();
+            code.push_str(&records.join(","));
+            code.push_str(");\n}\n\n@ParameterizedTest\n@ValueSource(strings = {\n");
+            for row in &values {
+                code.push_str(&format!("    {},\n", language.literal(&row[0], false)));
+            }
+            code.push_str(&format!(
+                "}})\nvoid {0}(String {1}) {{\n}}\n\n\
+                 @ParameterizedTest\n@MethodSource(\"{2}\")\nvoid {0}Row({3} row) {{\n}}\n",
+                method,
+                camel_case(columns[0].name),
+                factory,
+                T::NAME
+            ));
+        }
+        Language::TypeScript => {
+            code.push_str(&format!("export const {} = [\n", constant));
+            for row in &values {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .zip(tuple(row))
+                    .map(|(column, value)| format!("{}: {}", camel_case(column.name), value))
+                    .collect();
+                code.push_str(&format!("  {{ {} }},\n", fields.join(", ")));
+            }
+            code.push_str(&format!(
+                "] as const;\n\nexport type {} = (typeof {})[number];\n",
+                T::NAME,
+                constant
+            ));
+        }
+    }
+    code
+}
+
+/// `check_digits` or `PersonalId` as `checkDigits` or `personalId`.
+fn camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.push(c.to_ascii_uppercase());
+            upper = false;
+        } else if i == 0 {
+            camel.push(c.to_ascii_lowercase());
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdRow;
+    use crate::negative::Defect;
+
+    fn rows() -> Vec<IdRow> {
+        vec![
+            IdRow {
+                code: "85010112345".to_string(),
+                gender: "female".to_string(),
+                dob: "1985-01-01".to_string(),
+                valid: true,
+                defect: None,
+            },
+            IdRow {
+                code: "say \"hi\"\\\n".to_string(),
+                gender: "male".to_string(),
+                dob: String::new(),
+                valid: false,
+                defect: Some(Defect::CheckDigit),
+            },
+        ]
+    }
+
+    #[test]
+    fn rust_fixture() {
+        assert_eq!(
+            to_snippet(&rows(), Language::Rust, Some(7)),
+            r#"// MockBanker seed: 7
+#[derive(Clone, Copy, Debug)]
+pub struct PersonalId {
+    pub code: &'static str,
+    pub gender: &'static str,
+    pub dob: Option<&'static str>,
+    pub valid: bool,
+    pub defect: Option<&'static str>,
+}
+
+pub const PERSONAL_IDS: &[PersonalId] = &[
+    PersonalId { code: "85010112345", gender: "female", dob: Some("1985-01-01"), valid: true, defect: None },
+    PersonalId { code: "say \"hi\"\\\n", gender: "male", dob: None, valid: false, defect: Some("check_digit") },
+];
+"#
+        );
+    }
+
+    #[test]
+    fn pytest_fixture() {
+        assert_eq!(
+            to_snippet(&rows(), Language::Pytest, None),
+            r#"import pytest
+
+PERSONAL_IDS = [
+    ("85010112345", "female", "1985-01-01", True, None),
+    ("say \"hi\"\\\n", "male", None, False, "check_digit"),
+]
+
+
+@pytest.mark.parametrize(("code", "gender", "dob", "valid", "defect"), PERSONAL_IDS)
+def test_personal_ids(code, gender, dob, valid, defect):
+    ...
+"#
+        );
+    }
+
+    #[test]
+    fn junit_fixture() {
+        let code = to_snippet(&rows(), Language::Junit, Some(7));
+        assert!(code.starts_with("// MockBanker seed: 7\n"));
+        for line in [
+            "record PersonalId(String code, String gender, String dob, boolean valid, String defect) {}",
+            "static Stream<PersonalId> personalIds() {",
+            "        new PersonalId(\"say \\\"hi\\\"\\\\\\n\", \"male\", null, false, \"check_digit\"));",
+            "    \"85010112345\",",
+            "void personalId(String code) {",
+            "@MethodSource(\"personalIds\")",
+            "void personalIdRow(PersonalId row) {",
+        ] {
+            assert!(code.lines().any(|l| l == line), "{line}\n{code}");
+        }
+    }
+
+    #[test]
+    fn typescript_fixture() {
+        let code = to_snippet(&rows()[..1], Language::TypeScript, None);
+        assert_eq!(
+            code,
+            "export const PERSONAL_IDS = [\n  { code: \"85010112345\", gender: \"female\", \
+             dob: \"1985-01-01\", valid: true },\n] as const;\n\n\
+             export type PersonalId = (typeof PERSONAL_IDS)[number];\n"
+        );
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(Language::Rust.string("a\u{1}b"), "\"a\\u{1}b\"");
+        assert_eq!(Language::TypeScript.string("a\u{1}b"), "\"a\\u0001b\"");
+        assert_eq!(Language::Pytest.string("tab\there"), "\"tab\\there\"");
+    }
+
+    #[test]
+    fn names_and_languages() {
+        assert_eq!(camel_case("check_digits"), "checkDigits");
+        assert_eq!(camel_case("PersonalId"), "personalId");
+        assert_eq!(Language::parse("JUnit"), Ok(Language::Junit));
+        assert!(Language::parse("cobol").is_err());
+    }
+}
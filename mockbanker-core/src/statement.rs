@@ -0,0 +1,669 @@
+//! Account statements for a generated IBAN/BIC pair, as ISO 20022
+//! `camt.053` XML or SWIFT MT940 text.
+//!
+//! One statement is produced per business day. Each carries the previous
+//! day's closing balance as its opening balance, so the running balance
+//! reconciles across the whole period in both formats.
+
+use idsmith::swift;
+use rand::Rng;
+
+use crate::dates;
+use crate::sepa::{Party, created_at, escape, format_amount, reference};
+
+/// Options for [`generate`]. Amounts are in cents.
+#[derive(Clone, Debug)]
+pub struct StatementOptions {
+    /// Account country; `None` picks a random SEPA country.
+    pub country: Option<String>,
+    pub currency: String,
+    /// First calendar day covered, `YYYY-MM-DD`.
+    pub start_date: String,
+    /// Calendar days covered; weekends get no statement, so at least one
+    /// must be a weekday.
+    pub days: u32,
+    /// Upper bound of bookings per business day.
+    pub max_transactions: u32,
+    /// Balance before the first booking; may be negative.
+    pub opening_balance: i64,
+    /// Group header creation time, `YYYY-MM-DDThh:mm:ss`. Empty uses the
+    /// end of the last day covered.
+    pub created_at: String,
+}
+
+impl Default for StatementOptions {
+    fn default() -> Self {
+        Self {
+            country: None,
+            currency: "EUR".to_string(),
+            start_date: "2025-01-01".to_string(),
+            days: 7,
+            max_transactions: 5,
+            opening_balance: 250_000,
+            created_at: String::new(),
+        }
+    }
+}
+
+/// Kinds of booking drawn for each entry: remittance text, credit or
+/// debit, amount range in cents, ISO bank transaction family and
+/// sub-family, and MT940 transaction type.
+const BOOKINGS: &[(&str, bool, u64, u64, &str, &str, &str)] = &[
+    ("Salary", true, 180_000, 450_000, "RCDT", "ESCT", "NTRF"),
+    ("Invoice", true, 5_000, 250_000, "RCDT", "ESCT", "NTRF"),
+    ("Refund", true, 500, 15_000, "RCDT", "ESCT", "NTRF"),
+    ("Rent", false, 60_000, 180_000, "ICDT", "ESCT", "NTRF"),
+    ("Utilities", false, 4_000, 25_000, "IDDT", "ESDD", "NDDT"),
+    ("Insurance", false, 2_000, 30_000, "IDDT", "ESDD", "NDDT"),
+    ("Subscription", false, 500, 3_000, "IDDT", "ESDD", "NDDT"),
+    ("Card payment", false, 300, 12_000, "CCRD", "POSD", "NMSC"),
+];
+
+/// Fictitious counterparties.
+const COUNTERPARTIES: &[&str] = &[
+    "Northwind Traders",
+    "Contoso Ltd",
+    "Fabrikam Inc",
+    "Tailspin Toys",
+    "Wide World Importers",
+    "Adventure Works",
+    "Proseware Inc",
+    "Litware Inc",
+    "Coho Winery",
+    "Alpine Ski House",
+];
+
+/// One booked transaction.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    /// Signed amount in cents; negative for debits.
+    pub amount: i64,
+    pub counterparty: String,
+    pub counterparty_iban: String,
+    pub counterparty_bic: String,
+    pub remittance: String,
+    pub end_to_end_id: String,
+    pub bank_reference: String,
+    family: &'static str,
+    sub_family: &'static str,
+    swift_code: &'static str,
+}
+
+/// The bookings of one business day.
+#[derive(Clone, Debug)]
+pub struct StatementDay {
+    /// Days since 1970-01-01.
+    pub date: i64,
+    pub opening: i64,
+    pub entries: Vec<Entry>,
+}
+
+impl StatementDay {
+    pub fn closing(&self) -> i64 {
+        self.opening + self.entries.iter().map(|e| e.amount).sum::<i64>()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Statement {
+    pub iban: String,
+    pub bic: String,
+    pub owner: String,
+    pub currency: String,
+    pub days: Vec<StatementDay>,
+    id: String,
+    created_at: String,
+}
+
+/// Generates statements for one account. Fails if `start_date` isn't a
+/// valid `YYYY-MM-DD` date, the period has no business day to state,
+/// `created_at` is malformed or `country` is outside SEPA.
+pub fn generate<R: Rng>(
+    swift: &swift::Registry,
+    opts: &StatementOptions,
+    rng: &mut R,
+) -> Result<Statement, String> {
+    let start = dates::parse(&opts.start_date)
+        .ok_or_else(|| format!("start date '{}' isn't a YYYY-MM-DD date", opts.start_date))?;
+    let end = start + opts.days as i64;
+    if !(start..end).any(|date| dates::weekday(date) <= 5) {
+        return Err(format!(
+            "{} day(s) from {} cover no business day to state",
+            opts.days, opts.start_date
+        ));
+    }
+    let last = dates::format(end - 1);
+    let created_at = if opts.created_at.is_empty() {
+        format!("{}T23:59:59", last)
+    } else {
+        created_at(&opts.created_at, &last)?
+    };
+    let account = Party::generate(swift, "Account Holder", 1, opts.country.as_deref(), rng)?;
+
+    let mut days = Vec::new();
+    let mut balance = opts.opening_balance;
+    for date in start..end {
+        if dates::weekday(date) > 5 {
+            continue;
+        }
+        let mut entries = Vec::new();
+        for _ in 0..rng.gen_range(0..=opts.max_transactions) {
+            let &(label, credit, min, max, family, sub_family, swift_code) =
+                &BOOKINGS[rng.gen_range(0..BOOKINGS.len())];
+            let counterparty = Party::generate(swift, "Counterparty", 1, None, rng)?;
+            let amount = rng.gen_range(min..=max) as i64;
+            entries.push(Entry {
+                amount: if credit { amount } else { -amount },
+                counterparty: COUNTERPARTIES[rng.gen_range(0..COUNTERPARTIES.len())].to_string(),
+                counterparty_iban: counterparty.iban,
+                counterparty_bic: counterparty.bic,
+                remittance: format!("{} {:06}", label, rng.gen_range(0..1_000_000)),
+                end_to_end_id: reference("E2E", rng),
+                bank_reference: format!("{:016X}", rng.gen_range(0..u64::MAX)),
+                family,
+                sub_family,
+                swift_code,
+            });
+        }
+        let day = StatementDay {
+            date,
+            opening: balance,
+            entries,
+        };
+        balance = day.closing();
+        days.push(day);
+    }
+
+    Ok(Statement {
+        iban: account.iban,
+        bic: account.bic,
+        owner: account.name,
+        currency: opts.currency.clone(),
+        days,
+        id: reference("STMT", rng),
+        created_at,
+    })
+}
+
+/// `(amount, indicator)` for a signed balance, as camt.053 writes it.
+fn camt_amount(cents: i64) -> (String, &'static str) {
+    (
+        format_amount(cents.unsigned_abs()),
+        if cents < 0 { "DBIT" } else { "CRDT" },
+    )
+}
+
+/// MT940 amount: comma as decimal separator, e.g. `1234,50`.
+fn mt940_amount(cents: i64) -> String {
+    format_amount(cents.unsigned_abs()).replace('.', ",")
+}
+
+/// MT940 date, `YYMMDD`.
+fn mt940_date(days: i64) -> String {
+    let (year, month, day) = dates::civil_from_days(days);
+    format!("{:02}{:02}{:02}", year % 100, month, day)
+}
+
+impl Statement {
+    /// Total number of bookings across all days.
+    pub fn entry_count(&self) -> usize {
+        self.days.iter().map(|d| d.entries.len()).sum()
+    }
+
+    pub fn closing(&self) -> Option<i64> {
+        self.days.last().map(StatementDay::closing)
+    }
+
+    /// Bank-to-Customer Statement (`camt.053.001.08`), one `Stmt` per day.
+    pub fn camt053(&self) -> String {
+        let currency = escape(&self.currency);
+        let balance = |code: &str, cents: i64, date: &str| {
+            let (amount, indicator) = camt_amount(cents);
+            format!(
+                "      <Bal>
+        <Tp>
+          <CdOrPrtry>
+            <Cd>{code}</Cd>
+          </CdOrPrtry>
+        </Tp>
+        <Amt Ccy=\"{currency}\">{amount}</Amt>
+        <CdtDbtInd>{indicator}</CdtDbtInd>
+        <Dt>
+          <Dt>{date}</Dt>
+        </Dt>
+      </Bal>
+"
+            )
+        };
+
+        let mut statements = String::new();
+        for (n, day) in self.days.iter().enumerate() {
+            let date = dates::format(day.date);
+            let credits: Vec<i64> = day
+                .entries
+                .iter()
+                .map(|e| e.amount)
+                .filter(|a| *a >= 0)
+                .collect();
+            let debits: Vec<i64> = day
+                .entries
+                .iter()
+                .map(|e| e.amount)
+                .filter(|a| *a < 0)
+                .collect();
+            let credit_sum: i64 = credits.iter().sum();
+            let debit_sum: i64 = -debits.iter().sum::<i64>();
+            let (net, net_indicator) = camt_amount(credit_sum - debit_sum);
+
+            let mut entries = String::new();
+            for entry in &day.entries {
+                let (amount, indicator) = camt_amount(entry.amount);
+                // The counterparty is the debtor of a credit and the creditor
+                // of a debit.
+                let (party, agent) = if entry.amount >= 0 {
+                    ("Dbtr", "DbtrAgt")
+                } else {
+                    ("Cdtr", "CdtrAgt")
+                };
+                entries.push_str(&format!(
+                    "      <Ntry>
+        <Amt Ccy=\"{currency}\">{amount}</Amt>
+        <CdtDbtInd>{indicator}</CdtDbtInd>
+        <Sts>
+          <Cd>BOOK</Cd>
+        </Sts>
+        <BookgDt>
+          <Dt>{date}</Dt>
+        </BookgDt>
+        <ValDt>
+          <Dt>{date}</Dt>
+        </ValDt>
+        <AcctSvcrRef>{bank_ref}</AcctSvcrRef>
+        <BkTxCd>
+          <Domn>
+            <Cd>PMNT</Cd>
+            <Fmly>
+              <Cd>{family}</Cd>
+              <SubFmlyCd>{sub_family}</SubFmlyCd>
+            </Fmly>
+          </Domn>
+        </BkTxCd>
+        <NtryDtls>
+          <TxDtls>
+            <Refs>
+              <EndToEndId>{e2e}</EndToEndId>
+            </Refs>
+            <Amt Ccy=\"{currency}\">{amount}</Amt>
+            <CdtDbtInd>{indicator}</CdtDbtInd>
+            <RltdPties>
+              <{party}>
+                <Pty>
+                  <Nm>{name}</Nm>
+                </Pty>
+              </{party}>
+              <{party}Acct>
+                <Id>
+                  <IBAN>{iban}</IBAN>
+                </Id>
+              </{party}Acct>
+            </RltdPties>
+            <RltdAgts>
+              <{agent}>
+                <FinInstnId>
+                  <BICFI>{bic}</BICFI>
+                </FinInstnId>
+              </{agent}>
+            </RltdAgts>
+            <RmtInf>
+              <Ustrd>{remittance}</Ustrd>
+            </RmtInf>
+          </TxDtls>
+        </NtryDtls>
+      </Ntry>
+",
+                    bank_ref = entry.bank_reference,
+                    family = entry.family,
+                    sub_family = entry.sub_family,
+                    e2e = entry.end_to_end_id,
+                    name = escape(&entry.counterparty),
+                    iban = entry.counterparty_iban,
+                    bic = entry.counterparty_bic,
+                    remittance = escape(&entry.remittance),
+                ));
+            }
+
+            statements.push_str(&format!(
+                "    <Stmt>
+      <Id>{id}-{seq:03}</Id>
+      <ElctrncSeqNb>{seq}</ElctrncSeqNb>
+      <CreDtTm>{date}T23:59:59</CreDtTm>
+      <FrToDt>
+        <FrDtTm>{date}T00:00:00</FrDtTm>
+        <ToDtTm>{date}T23:59:59</ToDtTm>
+      </FrToDt>
+      <Acct>
+        <Id>
+          <IBAN>{iban}</IBAN>
+        </Id>
+        <Ccy>{currency}</Ccy>
+        <Ownr>
+          <Nm>{owner}</Nm>
+        </Ownr>
+        <Svcr>
+          <FinInstnId>
+            <BICFI>{bic}</BICFI>
+          </FinInstnId>
+        </Svcr>
+      </Acct>
+{opening}{closing}      <TxsSummry>
+        <TtlNtries>
+          <NbOfNtries>{count}</NbOfNtries>
+          <Sum>{sum}</Sum>
+          <TtlNetNtry>
+            <Amt>{net}</Amt>
+            <CdtDbtInd>{net_indicator}</CdtDbtInd>
+          </TtlNetNtry>
+        </TtlNtries>
+        <TtlCdtNtries>
+          <NbOfNtries>{credit_count}</NbOfNtries>
+          <Sum>{credit_sum}</Sum>
+        </TtlCdtNtries>
+        <TtlDbtNtries>
+          <NbOfNtries>{debit_count}</NbOfNtries>
+          <Sum>{debit_sum}</Sum>
+        </TtlDbtNtries>
+      </TxsSummry>
+{entries}    </Stmt>
+",
+                id = self.id,
+                seq = n + 1,
+                iban = self.iban,
+                owner = escape(&self.owner),
+                bic = self.bic,
+                opening = balance("OPBD", day.opening, &date),
+                closing = balance("CLBD", day.closing(), &date),
+                count = day.entries.len(),
+                sum = format_amount((credit_sum + debit_sum) as u64),
+                credit_count = credits.len(),
+                credit_sum = format_amount(credit_sum as u64),
+                debit_count = debits.len(),
+                debit_sum = format_amount(debit_sum as u64),
+            ));
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.053.001.08\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">
+  <BkToCstmrStmt>
+    <GrpHdr>
+      <MsgId>{id}</MsgId>
+      <CreDtTm>{created}</CreDtTm>
+    </GrpHdr>
+{statements}  </BkToCstmrStmt>
+</Document>
+",
+            id = self.id,
+            created = escape(&self.created_at),
+        )
+    }
+
+    /// SWIFT MT940 Customer Statement, one message per day, each opening
+    /// (`:60F:`) with the previous closing balance (`:62F:`).
+    pub fn mt940(&self) -> String {
+        let currency: String = self.currency.chars().take(3).collect();
+        let balance = |tag: &str, cents: i64, date: i64| {
+            format!(
+                ":{}:{}{}{}{}\r\n",
+                tag,
+                if cents < 0 { "D" } else { "C" },
+                mt940_date(date),
+                currency,
+                mt940_amount(cents)
+            )
+        };
+
+        let mut out = String::new();
+        for (n, day) in self.days.iter().enumerate() {
+            // :20: allows 16 characters.
+            out.push_str(&format!(
+                ":20:{:.12}{:04}\r\n",
+                self.id.replace('-', ""),
+                n + 1
+            ));
+            out.push_str(&format!(":25:{}\r\n", self.iban));
+            out.push_str(&format!(":28C:{:05}/001\r\n", n + 1));
+            out.push_str(&balance("60F", day.opening, day.date));
+            for entry in &day.entries {
+                // Value date plus booking date as MMDD; both the same day.
+                let (_, month, day_of_month) = dates::civil_from_days(day.date);
+                out.push_str(&format!(
+                    ":61:{}{:02}{:02}{}{}{}NONREF//{}\r\n",
+                    mt940_date(day.date),
+                    month,
+                    day_of_month,
+                    if entry.amount < 0 { "D" } else { "C" },
+                    mt940_amount(entry.amount),
+                    entry.swift_code,
+                    entry.bank_reference
+                ));
+                let info = format!(
+                    "/EREF/{}/IBAN/{}/BIC/{}/NAME/{}/REMI/{}",
+                    entry.end_to_end_id,
+                    entry.counterparty_iban,
+                    entry.counterparty_bic,
+                    entry.counterparty,
+                    entry.remittance
+                );
+                // Up to 6 lines of 65 characters.
+                let chars: Vec<char> = info.chars().take(6 * 65).collect();
+                for (i, line) in chars.chunks(65).enumerate() {
+                    let line: String = line.iter().collect();
+                    if i == 0 {
+                        out.push_str(&format!(":86:{}\r\n", line));
+                    } else {
+                        out.push_str(&format!("{}\r\n", line));
+                    }
+                }
+            }
+            out.push_str(&balance("62F", day.closing(), day.date));
+            out.push_str("-\r\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::seeded_rng;
+    use crate::sepa::parse_amount;
+    use idsmith::iban;
+
+    fn statement(opts: &StatementOptions) -> Result<Statement, String> {
+        generate(&swift::Registry::new(), opts, &mut seeded_rng(9))
+    }
+
+    /// The text of every `<tag>text</tag>` in `xml`, in order.
+    fn values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+        let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+        xml.split(&open)
+            .skip(1)
+            .map(|rest| &rest[..rest.find(&close).unwrap()])
+            .collect()
+    }
+
+    #[test]
+    fn states_every_weekday_and_carries_balances() {
+        // 2025-01-01 is a Wednesday; the weekend of the 4th is skipped.
+        let opts = StatementOptions {
+            opening_balance: -1_000,
+            ..Default::default()
+        };
+        let stmt = statement(&opts).unwrap();
+        let dates: Vec<String> = stmt.days.iter().map(|d| dates::format(d.date)).collect();
+        assert_eq!(
+            dates,
+            [
+                "2025-01-01",
+                "2025-01-02",
+                "2025-01-03",
+                "2025-01-06",
+                "2025-01-07"
+            ]
+        );
+        assert_eq!(stmt.days[0].opening, -1_000);
+        for pair in stmt.days.windows(2) {
+            assert_eq!(pair[1].opening, pair[0].closing());
+        }
+        let booked: i64 = stmt
+            .days
+            .iter()
+            .flat_map(|d| &d.entries)
+            .map(|e| e.amount)
+            .sum();
+        assert_eq!(stmt.closing(), Some(-1_000 + booked));
+        assert!(iban::validate_iban(&stmt.iban));
+    }
+
+    #[test]
+    fn rejects_periods_without_a_business_day() {
+        for (start, days) in [("2025-01-01", 0), ("2025-01-04", 2), ("2025-02-30", 7)] {
+            let opts = StatementOptions {
+                start_date: start.to_string(),
+                days,
+                ..Default::default()
+            };
+            assert!(statement(&opts).is_err(), "{start} {days}");
+        }
+        let opts = StatementOptions {
+            start_date: "2025-01-04".to_string(),
+            days: 3,
+            ..Default::default()
+        };
+        assert_eq!(statement(&opts).unwrap().days.len(), 1);
+        let opts = StatementOptions {
+            created_at: "2025-01-08".to_string(),
+            ..Default::default()
+        };
+        assert!(statement(&opts).is_err());
+    }
+
+    #[test]
+    fn camt053_balances_and_element_order() {
+        let stmt = statement(&StatementOptions {
+            opening_balance: -50_000,
+            max_transactions: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        let xml = stmt.camt053();
+        assert_eq!(values(&xml, "CreDtTm")[0], "2025-01-07T23:59:59");
+        let statements = values(&xml, "Stmt");
+        assert_eq!(statements.len(), stmt.days.len());
+        for (day, body) in stmt.days.iter().zip(&statements) {
+            let order = [
+                "<Id>",
+                "<ElctrncSeqNb>",
+                "<CreDtTm>",
+                "<FrToDt>",
+                "<Acct>",
+                "<Cd>OPBD",
+                "<Cd>CLBD",
+                "<TxsSummry>",
+            ];
+            let positions: Vec<usize> = order.iter().map(|tag| body.find(tag).unwrap()).collect();
+            assert!(positions.windows(2).all(|w| w[0] < w[1]), "{positions:?}");
+            if let Some(first) = body.find("<Ntry>") {
+                assert!(first > body.find("</TxsSummry>").unwrap());
+            }
+
+            let balances = values(body, "Bal");
+            for (bal, cents) in balances.iter().zip([day.opening, day.closing()]) {
+                let amount = format!(
+                    "<Amt Ccy=\"EUR\">{}</Amt>",
+                    format_amount(cents.unsigned_abs())
+                );
+                assert!(bal.contains(&amount), "{bal}");
+                let indicator = if cents < 0 { "DBIT" } else { "CRDT" };
+                assert_eq!(values(bal, "CdtDbtInd"), [indicator]);
+            }
+
+            let entries = values(body, "Ntry");
+            assert_eq!(entries.len(), day.entries.len());
+            let summary = values(body, "TtlNtries")[0];
+            assert_eq!(
+                values(summary, "NbOfNtries"),
+                [day.entries.len().to_string()]
+            );
+            let total: u64 = day.entries.iter().map(|e| e.amount.unsigned_abs()).sum();
+            assert_eq!(parse_amount(values(summary, "Sum")[0]), Some(total));
+        }
+    }
+
+    #[test]
+    fn mt940_layout() {
+        let stmt = statement(&StatementOptions {
+            max_transactions: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        let text = stmt.mt940();
+        assert!(text.ends_with("-\r\n"));
+        let messages: Vec<&str> = text.split("-\r\n").filter(|m| !m.is_empty()).collect();
+        assert_eq!(messages.len(), stmt.days.len());
+        for (n, (day, message)) in stmt.days.iter().zip(messages).enumerate() {
+            let lines: Vec<&str> = message.split("\r\n").filter(|l| !l.is_empty()).collect();
+            assert!(lines[0].starts_with(":20:") && lines[0].len() <= 4 + 16);
+            assert_eq!(lines[1], format!(":25:{}", stmt.iban));
+            assert_eq!(lines[2], format!(":28C:{:05}/001", n + 1));
+            let date = mt940_date(day.date);
+            assert!(lines[3].starts_with(&format!(":60F:C{}EUR", date)));
+            assert!(
+                lines
+                    .last()
+                    .unwrap()
+                    .starts_with(&format!(":62F:C{}EUR", date))
+            );
+
+            let body = &lines[4..lines.len() - 1];
+            let bookings: Vec<usize> = (0..body.len())
+                .filter(|&i| body[i].starts_with(":61:"))
+                .collect();
+            assert_eq!(bookings.len(), day.entries.len());
+            for (&i, entry) in bookings.iter().zip(&day.entries) {
+                // :61: date, entry date, mark, amount, type, references.
+                let line = &body[i][4..];
+                assert!(line.starts_with(&format!("{}{}", date, &date[2..])));
+                let mark = if entry.amount < 0 { 'D' } else { 'C' };
+                assert_eq!(line[10..].chars().next(), Some(mark));
+                let amount = mt940_amount(entry.amount);
+                assert!(line[11..].starts_with(&format!("{}{}NONREF//", amount, entry.swift_code)));
+                assert!(line.ends_with(&entry.bank_reference));
+                // :86: follows, at most 6 lines of 65 characters.
+                assert!(body[i + 1].starts_with(":86:/EREF/"));
+                let info_end = bookings
+                    .iter()
+                    .find(|&&j| j > i)
+                    .copied()
+                    .unwrap_or(body.len());
+                let info = &body[i + 1..info_end];
+                assert!(info.len() <= 6);
+                assert!(info[0].len() <= 4 + 65);
+                assert!(
+                    info[1..]
+                        .iter()
+                        .all(|l| l.chars().count() <= 65 && !l.starts_with(':'))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_statement() {
+        let opts = StatementOptions::default();
+        assert_eq!(
+            statement(&opts).unwrap().mt940(),
+            statement(&opts).unwrap().mt940()
+        );
+    }
+}
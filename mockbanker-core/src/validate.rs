@@ -0,0 +1,676 @@
+//! Single-value validation across every supported identifier type, plus
+//! detection of which types and countries a value could be.
+
+use idsmith::{
+    bank_account, company_id, credit_card, driver_license, iban, lei, passport, personal_id, swift,
+    tax_id, vat,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::dates;
+use crate::diagnose::{self, Check, Diagnosis};
+use crate::rows::ValidationRow;
+use crate::sepa;
+
+/// Identifier types understood by [`Validator::validate`], as `(key, label)`.
+pub const KINDS: &[(&str, &str)] = &[
+    ("iban", "IBAN"),
+    ("id", "Personal ID"),
+    ("bank", "Bank Account"),
+    ("card", "Credit Card"),
+    ("swift", "SWIFT/BIC"),
+    ("company", "Company ID"),
+    ("driver_license", "Driver's License"),
+    ("passport", "Passport"),
+    ("tax_id", "Tax ID"),
+    ("vat", "VAT"),
+    ("lei", "LEI"),
+    ("creditor_id", "SEPA Creditor ID"),
+];
+
+/// Pseudo-kind for [`Validator::validate`] that reports the best
+/// [`Validator::detect`] match instead of checking one type.
+pub const DETECT: &str = "detect";
+
+/// National kinds matching in more countries than this are reported as one
+/// grouped interpretation.
+const MAX_COUNTRIES: usize = 5;
+
+/// One plausible reading of a value, from [`Validator::detect`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interpretation {
+    /// Key from [`KINDS`].
+    pub kind: String,
+    /// Empty when the type isn't national or several countries match.
+    pub country: String,
+    pub description: String,
+    /// How strongly a match implies this reading: check digits over a fixed
+    /// layout score high, format-only patterns low.
+    pub score: u8,
+}
+
+/// Whether validating `kind` needs a country to pick the national format.
+pub fn needs_country(kind: &str) -> bool {
+    matches!(
+        kind,
+        "id" | "bank" | "company" | "driver_license" | "passport" | "tax_id"
+    )
+}
+
+/// Values to check from a pasted list or an uploaded CSV/TXT file, one per
+/// line. Only the first comma-, semicolon- or tab-separated field of a line
+/// is kept, unquoted, so a CSV column can be checked as exported; blank
+/// lines are skipped.
+pub fn values(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            let field = match line.strip_prefix('"') {
+                Some(quoted) => quoted.split('"').next().unwrap_or(""),
+                None => line.split([',', ';', '\t']).next().unwrap_or(""),
+            };
+            let field = field.trim();
+            (!field.is_empty()).then(|| field.to_string())
+        })
+        .collect()
+}
+
+/// Card brand from the issuer prefix, for detection labels.
+pub(crate) fn card_brand(number: &str) -> &'static str {
+    let prefix = |n: usize| number.get(..n).and_then(|p| p.parse::<u32>().ok());
+    match (prefix(1), prefix(2), prefix(4)) {
+        (Some(4), _, _) => "Visa",
+        (_, Some(34 | 37), _) => "American Express",
+        (_, Some(51..=55), _) | (_, _, Some(2221..=2720)) => "Mastercard",
+        (_, Some(65), _) | (_, _, Some(6011)) => "Discover",
+        (_, Some(35), _) => "JCB",
+        (_, Some(36 | 38), _) => "Diners Club",
+        _ => "card",
+    }
+}
+
+/// Owns one registry per identifier type so repeated checks don't rebuild them.
+pub struct Validator {
+    personal_id: personal_id::Registry,
+    bank_account: bank_account::Registry,
+    credit_card: credit_card::Registry,
+    swift: swift::Registry,
+    company_id: company_id::Registry,
+    driver_license: driver_license::Registry,
+    passport: passport::Registry,
+    tax_id: tax_id::Registry,
+    vat: vat::Registry,
+    lei: lei::Registry,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self {
+            personal_id: personal_id::Registry::new(),
+            bank_account: bank_account::Registry::new(),
+            credit_card: credit_card::Registry::new(),
+            swift: swift::Registry::new(),
+            company_id: company_id::Registry::new(),
+            driver_license: driver_license::Registry::new(),
+            passport: passport::Registry::new(),
+            tax_id: tax_id::Registry::new(),
+            vat: vat::Registry::new(),
+            lei: lei::Registry::new(),
+        }
+    }
+
+    /// `(code, name)` pairs for the kinds where [`needs_country`] is true.
+    pub fn countries(&self, kind: &str) -> Vec<(String, String)> {
+        match kind {
+            "id" => self
+                .personal_id
+                .list_countries()
+                .iter()
+                .map(|(c, n, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            "bank" => self
+                .bank_account
+                .list_countries()
+                .iter()
+                .map(|(c, n, _, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            "company" => self
+                .company_id
+                .list_countries()
+                .iter()
+                .map(|(c, n, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            "driver_license" => self
+                .driver_license
+                .list_countries()
+                .iter()
+                .map(|(c, n, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            "passport" => self
+                .passport
+                .list_countries()
+                .iter()
+                .map(|(c, n, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            "tax_id" => self
+                .tax_id
+                .list_countries()
+                .iter()
+                .map(|(c, n, _)| (c.to_string(), n.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks `value` as `kind`, returning validity and a human-readable
+    /// message, or `None` for an unknown kind. `country` is ignored for kinds
+    /// that don't need one.
+    pub fn validate(&self, kind: &str, country: &str, value: &str) -> Option<(bool, String)> {
+        let result = match kind {
+            "iban" | "card" | "lei" | "creditor_id" => {
+                let diagnosis = self.diagnose(kind, country, value)?;
+                (diagnosis.valid, diagnosis.summary)
+            }
+            // idsmith's validators slice values by byte, which panics inside
+            // a multi-byte character; no identifier has one.
+            _ if !value.is_ascii() && KINDS.iter().any(|(k, _)| *k == kind) => {
+                (false, "Contains characters no identifier uses".to_string())
+            }
+            // Some parsers index into the value unchecked, so only valid
+            // values are parsed.
+            "id" => match self.personal_id.validate(country, value) {
+                Some(true) => {
+                    let parsed = self.personal_id.parse(country, value);
+                    let (gender, dob) = parsed.map(|p| (p.gender, p.dob)).unwrap_or_default();
+                    // Most formats only check their digits, not the date.
+                    if let Some(dob) = dob.as_deref().filter(|d| d.len() == 10)
+                        && dates::parse(dob).is_none()
+                    {
+                        return Some((false, format!("Date of birth {} doesn't exist", dob)));
+                    }
+                    (
+                        true,
+                        format!(
+                            "Valid ID ({} / {})",
+                            gender.unwrap_or_default(),
+                            dob.unwrap_or_default()
+                        ),
+                    )
+                }
+                Some(false) => (false, "Invalid ID for selected country".to_string()),
+                None => (false, "Could not parse ID".to_string()),
+            },
+            "bank" => match self.bank_account.validate(country, value) {
+                Some(true) => (true, "Valid Bank Account for selected country".to_string()),
+                Some(false) => (false, "Invalid Bank Account checksum or format".to_string()),
+                None => (
+                    false,
+                    "Unsupported country for Bank Account validation".to_string(),
+                ),
+            },
+            "swift" => {
+                let is_valid = self.swift.validate(value);
+                if is_valid && !idsmith::countries::is_supported(&value[4..6]) {
+                    return Some((false, format!("'{}' is not a country code", &value[4..6])));
+                }
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid SWIFT/BIC format".to_string()
+                    } else {
+                        "Invalid SWIFT/BIC format".to_string()
+                    },
+                )
+            }
+            "company" => {
+                let is_valid = self.company_id.validate(country, value);
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid Company ID for selected country".to_string()
+                    } else {
+                        "Invalid Company ID checksum or format".to_string()
+                    },
+                )
+            }
+            "driver_license" => {
+                let is_valid = self.driver_license.validate(country, value);
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid Driver's License for selected country".to_string()
+                    } else {
+                        "Invalid Driver's License format".to_string()
+                    },
+                )
+            }
+            "passport" => {
+                let is_valid = self.passport.validate(country, value);
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid Passport for selected country".to_string()
+                    } else {
+                        "Invalid Passport format".to_string()
+                    },
+                )
+            }
+            "tax_id" => {
+                let is_valid = self.tax_id.validate(country, value);
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid Tax ID for selected country".to_string()
+                    } else {
+                        "Invalid Tax ID format".to_string()
+                    },
+                )
+            }
+            "vat" => {
+                let is_valid = self.vat.validate(value);
+                (
+                    is_valid,
+                    if is_valid {
+                        "Valid VAT number".to_string()
+                    } else {
+                        "Invalid VAT number format".to_string()
+                    },
+                )
+            }
+            DETECT => {
+                let found = self.detect(value);
+                match found.as_slice() {
+                    [] => (false, "No supported identifier type matches".to_string()),
+                    [best] => (true, best.description.clone()),
+                    [best, rest @ ..] => (
+                        true,
+                        format!("{} (+{} other readings)", best.description, rest.len()),
+                    ),
+                }
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Rule-by-rule checks of `value` as `kind`. IBANs, card numbers, LEIs
+    /// and Creditor Identifiers get one check per rule; other kinds a single
+    /// check carrying the [`Validator::validate`] message.
+    pub fn diagnose(&self, kind: &str, country: &str, value: &str) -> Option<Diagnosis> {
+        let (name, valid_message, mut checks, accepted) = match kind {
+            "iban" => {
+                let checks = diagnose::iban(value);
+                // idsmith's check panics on anything but letters and digits,
+                // which the character check already reports.
+                let accepted = checks[0].passed && iban::validate_iban(&value.to_ascii_uppercase());
+                ("IBAN", "Valid IBAN", checks, accepted)
+            }
+            "card" => (
+                "Credit Card",
+                "Valid Credit Card (Luhn check passed)",
+                diagnose::card(value),
+                self.credit_card.validate(value),
+            ),
+            "lei" => (
+                "LEI code",
+                "Valid LEI code",
+                diagnose::lei(value),
+                self.lei.validate(value),
+            ),
+            "creditor_id" => (
+                "Creditor Identifier",
+                "Valid SEPA Creditor Identifier",
+                diagnose::creditor_id(value),
+                sepa::validate_creditor_identifier(value).is_ok(),
+            ),
+            _ => {
+                let (valid, message) = self.validate(kind, country, value)?;
+                return Some(Diagnosis {
+                    valid,
+                    summary: message.clone(),
+                    checks: vec![Check::new("Format and checksum", valid, message)],
+                });
+            }
+        };
+        // The registries also know national layouts (digits-only BBANs and
+        // the like) that the rules above don't spell out.
+        if !accepted && checks.iter().all(|c| c.passed) {
+            checks.push(Check::new(
+                "National format",
+                false,
+                "does not match the country's layout".to_string(),
+            ));
+        }
+        Some(Diagnosis::from_checks(name, valid_message, checks))
+    }
+
+    /// Every type and country `value` validates as, most specific first.
+    /// Types needing a country are tried against all of theirs.
+    pub fn detect(&self, value: &str) -> Vec<Interpretation> {
+        let value = value.trim();
+        let compact: String = value
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect::<String>()
+            .to_ascii_uppercase();
+        let mut found = Vec::new();
+        let mut push = |kind: &str, country: &str, description: String, score: u8| {
+            found.push(Interpretation {
+                kind: kind.to_string(),
+                country: country.to_string(),
+                description,
+                score,
+            })
+        };
+        if compact.is_empty() || !compact.is_ascii() {
+            return Vec::new();
+        }
+        let prefix = compact.get(..2).unwrap_or("");
+        let letters = prefix.len() == 2 && prefix.chars().all(|c| c.is_ascii_uppercase());
+        // idsmith's IBAN check panics on anything but letters and digits.
+        let alphanumeric = compact.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if letters && alphanumeric && compact.len() > 4 && iban::validate_iban(&compact) {
+            push("iban", prefix, format!("Valid {} IBAN", prefix), 100);
+        }
+        if compact.len() == 20 && self.lei.validate(&compact) {
+            push("lei", "", "Valid LEI".to_string(), 95);
+        }
+        if letters && sepa::validate_creditor_identifier(&compact).is_ok() {
+            push(
+                "creditor_id",
+                prefix,
+                format!("Valid {} SEPA Creditor ID", prefix),
+                90,
+            );
+        }
+        if letters && compact.len() > 2 && self.vat.validate(&compact) {
+            // Greece files VAT under EL, not its ISO code.
+            let country = match prefix {
+                "EL" => "GR",
+                other => other,
+            };
+            push("vat", country, format!("Valid {} VAT number", country), 80);
+        }
+        if (12..=19).contains(&compact.len())
+            && compact.chars().all(|c| c.is_ascii_digit())
+            && self.credit_card.validate(&compact)
+        {
+            push(
+                "card",
+                "",
+                format!("Valid {} number (Luhn)", card_brand(&compact)),
+                60,
+            );
+        }
+        if matches!(compact.len(), 8 | 11) && self.swift.validate(&compact) {
+            let country = compact.get(4..6).unwrap_or("");
+            push(
+                "swift",
+                country,
+                format!("Valid SWIFT/BIC format, {} bank", country),
+                40,
+            );
+        }
+
+        let mut ids = Vec::new();
+        for (country, _, name) in self.personal_id.list_countries() {
+            // Some parsers index into the value unchecked, so only valid
+            // values are parsed.
+            if self.personal_id.validate(country, value) == Some(true)
+                && let Some(parsed) = self.personal_id.parse(country, value)
+                && parsed.valid
+            {
+                let score = self.id_score(country, value, parsed.dob.as_deref());
+                let mut description = format!("Valid {} {}", country, name);
+                for detail in [parsed.gender, parsed.dob].into_iter().flatten() {
+                    description.push_str(&format!(", {}", detail.to_lowercase()));
+                }
+                ids.push((country, description, score));
+            }
+        }
+
+        let national = [
+            (
+                "tax_id",
+                "tax ID",
+                50,
+                self.tax_id
+                    .list_countries()
+                    .into_iter()
+                    .filter(|(c, _, _)| self.tax_id.validate(c, value))
+                    .map(|(c, _, name)| (c, name))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "company",
+                "company ID",
+                50,
+                self.company_id
+                    .list_countries()
+                    .into_iter()
+                    .filter(|(c, _, _)| self.company_id.validate(c, value))
+                    .map(|(c, _, name)| (c, name))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "passport",
+                "passport number format",
+                20,
+                self.passport
+                    .list_countries()
+                    .into_iter()
+                    .filter(|(c, _, _)| self.passport.validate(c, value))
+                    .map(|(c, _, name)| (c, name))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "driver_license",
+                "driver's license format",
+                20,
+                self.driver_license
+                    .list_countries()
+                    .into_iter()
+                    .filter(|(c, _, _)| self.driver_license.validate(c, value))
+                    .map(|(c, _, name)| (c, name))
+                    .collect::<Vec<_>>(),
+            ),
+        ];
+        let mut kinds = vec![("id", "personal ID", ids)];
+        for (kind, label, score, matches) in national {
+            let matches = matches
+                .into_iter()
+                .map(|(c, name)| (c, format!("Valid {} {} ({})", c, label, name), score))
+                .collect();
+            kinds.push((kind, label, matches));
+        }
+        for (kind, label, mut matches) in kinds {
+            matches.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+            if matches.len() > MAX_COUNTRIES {
+                // A pattern shared by this many countries says little.
+                let codes: Vec<&str> = matches.iter().map(|(c, _, _)| *c).collect();
+                push(
+                    kind,
+                    "",
+                    format!(
+                        "Matches the {} of {} countries ({}, ...)",
+                        label,
+                        codes.len(),
+                        codes[..MAX_COUNTRIES].join(", ")
+                    ),
+                    matches[0].2 / 2,
+                );
+            } else {
+                for (country, description, score) in matches {
+                    push(kind, country, description, score);
+                }
+            }
+        }
+
+        found.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.country.cmp(&b.country))
+        });
+        found
+    }
+
+    /// How specifically a personal ID match identifies `country`, from 55
+    /// to 79: the share of single-digit changes its format rejects (check
+    /// digits and fixed fields), the value's length, and whether the date of
+    /// birth it encodes is a real date. A PESEL outranks a format that takes
+    /// any digits, and an encoded 87th month sinks a reading.
+    fn id_score(&self, country: &str, value: &str, dob: Option<&str>) -> u8 {
+        let chars: Vec<char> = value.chars().collect();
+        let (mut changes, mut rejected) = (0u32, 0u32);
+        for (i, c) in chars.iter().enumerate() {
+            if !c.is_ascii_digit() {
+                continue;
+            }
+            for digit in ('0'..='9').filter(|d| d != c) {
+                let mut changed = chars.clone();
+                changed[i] = digit;
+                let changed: String = changed.into_iter().collect();
+                changes += 1;
+                if self.personal_id.validate(country, &changed) != Some(true) {
+                    rejected += 1;
+                }
+            }
+        }
+        let strictness = (12 * rejected).checked_div(changes).unwrap_or(0) as i32;
+        let length = chars
+            .iter()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .count()
+            .min(18) as i32
+            / 3;
+        let date = match dob {
+            None => 0,
+            // Only the month where the ID encodes no day.
+            Some(dob)
+                if dates::parse(dob)
+                    .or(dates::parse(&format!("{}-01", dob)))
+                    .is_some() =>
+            {
+                6
+            }
+            Some(_) => -10,
+        };
+        (55 + strictness + length + date).clamp(0, 79) as u8
+    }
+
+    /// Checks every value as `kind`, in order, for a bulk report. Empty for
+    /// an unknown kind.
+    pub fn validate_all(&self, kind: &str, country: &str, values: &[String]) -> Vec<ValidationRow> {
+        values
+            .iter()
+            .filter_map(|value| {
+                let (valid, reason) = self.validate(kind, country, value.trim())?;
+                Some(ValidationRow {
+                    value: value.trim().to_string(),
+                    valid,
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(value: &str) -> Vec<Interpretation> {
+        Validator::new().detect(value)
+    }
+
+    #[test]
+    fn pesel_outranks_looser_personal_id_formats() {
+        let found = detect("44051401359");
+        assert_eq!(found[0].kind, "id");
+        assert_eq!(found[0].country, "PL");
+        assert_eq!(found[0].description, "Valid PL PESEL, male, 1944-05-14");
+        let cuba = found
+            .iter()
+            .find(|i| i.kind == "id" && i.country == "CU")
+            .unwrap();
+        assert!(cuba.score < found[0].score);
+    }
+
+    #[test]
+    fn impossible_birth_dates_rank_below_plain_formats() {
+        let found = detect("8703041234");
+        let cpr = found
+            .iter()
+            .find(|i| i.kind == "id" && i.country == "DK")
+            .unwrap();
+        let tax = found.iter().find(|i| i.kind == "tax_id").unwrap();
+        assert!(cpr.score < tax.score, "{found:?}");
+    }
+
+    #[test]
+    fn many_personal_id_countries_collapse() {
+        let found = detect("123456789");
+        let ids: Vec<&Interpretation> = found.iter().filter(|i| i.kind == "id").collect();
+        assert_eq!(ids.len(), 1, "{found:?}");
+        assert_eq!(ids[0].country, "");
+        assert!(
+            ids[0]
+                .description
+                .starts_with("Matches the personal ID of ")
+        );
+    }
+
+    #[test]
+    fn checked_types_come_first() {
+        let found = detect("DE89 3704 0044 0532 0130 00");
+        assert_eq!((found[0].kind.as_str(), found[0].score), ("iban", 100));
+        assert_eq!(found[0].description, "Valid DE IBAN");
+        assert_eq!(detect("5299000J2N45DDNE4Y28")[0].kind, "lei");
+        assert_eq!(detect("DE136695976")[0].kind, "vat");
+        let card = detect("4111111111111111");
+        assert!(
+            card.iter()
+                .any(|i| i.description == "Valid Visa number (Luhn)")
+        );
+        let bic = detect("DEUTDEFF");
+        assert!(bic.iter().any(|i| i.kind == "swift" && i.country == "DE"));
+        for pair in card.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+    }
+
+    #[test]
+    fn nothing_to_detect() {
+        assert!(detect("   ").is_empty());
+        assert!(detect("not an identifier!").is_empty());
+    }
+
+    #[test]
+    fn junk_never_panics() {
+        let validator = Validator::new();
+        for value in [
+            "de89 3704 0044 0532 0130 00",
+            "DE89-3704!",
+            "DE89370400440532013Ä00",
+            "€€€€€",
+            "",
+        ] {
+            for (kind, _) in KINDS {
+                let country = if needs_country(kind) { "DE" } else { "" };
+                validator.validate(kind, country, value);
+            }
+        }
+        let lower = validator
+            .validate("iban", "", "de89 3704 0044 0532 0130 00")
+            .unwrap();
+        assert!(lower.0, "{lower:?}");
+        assert!(!validator.validate("iban", "", "DE89-3704!").unwrap().0);
+    }
+}
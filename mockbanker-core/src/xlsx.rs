@@ -0,0 +1,263 @@
+//! Minimal Excel workbooks: one worksheet of text cells with a bold header
+//! row, packed into an uncompressed ZIP as the Office Open XML package
+//! format allows, so no compression or spreadsheet crate is needed.
+
+use crate::sepa::escape;
+
+/// An `.xlsx` file with one sheet named `sheet`, `headers` as its first
+/// row and every field written as text, so codes keep their leading zeros.
+pub fn workbook(sheet: &str, headers: &[&str], rows: &[Vec<String>]) -> Vec<u8> {
+    // Excel limits sheet names to 31 characters.
+    let sheet: String = sheet.chars().take(31).collect();
+    let mut data = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>",
+    );
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for (i, fields) in std::iter::once(&header).chain(rows).enumerate() {
+        let number = i + 1;
+        data.push_str(&format!("<row r=\"{}\">", number));
+        for (col, field) in fields.iter().enumerate() {
+            let style = if i == 0 { " s=\"1\"" } else { "" };
+            data.push_str(&format!(
+                "<c r=\"{}{}\" t=\"inlineStr\"{}><is><t xml:space=\"preserve\">{}</t></is></c>",
+                column_name(col),
+                number,
+                style,
+                escape(field)
+            ));
+        }
+        data.push_str("</row>");
+    }
+    data.push_str("</sheetData></worksheet>\n");
+
+    let workbook = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+         <sheets><sheet name=\"{}\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>\n",
+        escape(&sheet)
+    );
+
+    let mut zip = Zip::default();
+    zip.add("[Content_Types].xml", CONTENT_TYPES);
+    zip.add("_rels/.rels", ROOT_RELS);
+    zip.add("xl/workbook.xml", &workbook);
+    zip.add("xl/_rels/workbook.xml.rels", WORKBOOK_RELS);
+    zip.add("xl/styles.xml", STYLES);
+    zip.add("xl/worksheets/sheet1.xml", &data);
+    zip.finish()
+}
+
+/// Spreadsheet column letters: 0 is `A`, 25 `Z`, 26 `AA`.
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+const CONTENT_TYPES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+<Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>\
+</Types>\n";
+
+const ROOT_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>\n";
+
+const WORKBOOK_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+<Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>\
+</Relationships>\n";
+
+/// Style 0 is the default, style 1 bold for the header row.
+const STYLES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<fonts count=\"2\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font>\
+<font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill></fills>\
+<borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"2\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/></cellXfs>\
+</styleSheet>\n";
+
+/// ZIP archive with stored (uncompressed) entries.
+#[derive(Default)]
+struct Zip {
+    data: Vec<u8>,
+    directory: Vec<u8>,
+    entries: u16,
+}
+
+impl Zip {
+    fn add(&mut self, name: &str, content: &str) {
+        let content = content.as_bytes();
+        let crc = crc32(content);
+        let offset = self.data.len() as u32;
+        // Local file header, then the central directory record pointing at it.
+        self.data.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.data
+            .extend_from_slice(&entry_fields(name, crc, content.len() as u32));
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        self.directory
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.directory.extend_from_slice(&20u16.to_le_bytes());
+        self.directory
+            .extend_from_slice(&entry_fields(name, crc, content.len() as u32));
+        // Comment length, disk number, internal and external attributes.
+        self.directory.extend_from_slice(&[0; 10]);
+        self.directory.extend_from_slice(&offset.to_le_bytes());
+        self.directory.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let offset = self.data.len() as u32;
+        let size = self.directory.len() as u32;
+        self.data.extend_from_slice(&self.directory);
+        self.data.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.data.extend_from_slice(&[0; 4]);
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&size.to_le_bytes());
+        self.data.extend_from_slice(&offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+/// Header fields shared by local and central records, from the version
+/// needed to extract through the extra field length.
+fn entry_fields(name: &str, crc: u32, size: u32) -> Vec<u8> {
+    let mut fields = Vec::with_capacity(26);
+    fields.extend_from_slice(&20u16.to_le_bytes());
+    // UTF-8 names, stored, modified 1980-01-01 00:00.
+    fields.extend_from_slice(&0x0800u16.to_le_bytes());
+    fields.extend_from_slice(&0u16.to_le_bytes());
+    fields.extend_from_slice(&0u16.to_le_bytes());
+    fields.extend_from_slice(&0x0021u16.to_le_bytes());
+    fields.extend_from_slice(&crc.to_le_bytes());
+    fields.extend_from_slice(&size.to_le_bytes());
+    fields.extend_from_slice(&size.to_le_bytes());
+    fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    fields.extend_from_slice(&0u16.to_le_bytes());
+    fields
+}
+
+/// CRC-32 (IEEE) as ZIP uses it.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> usize {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    /// `(name, content)` of each stored entry, checking its CRC on the way.
+    fn entries(zip: &[u8]) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        let mut at = 0;
+        while u32_at(zip, at) == 0x0403_4b50 {
+            let crc = u32_at(zip, at + 14);
+            let size = u32_at(zip, at + 18) as usize;
+            assert_eq!(u32_at(zip, at + 22) as usize, size, "stored entries");
+            let name_len = u16_at(zip, at + 26);
+            let start = at + 30 + name_len + u16_at(zip, at + 28);
+            let name = String::from_utf8(zip[at + 30..at + 30 + name_len].to_vec()).unwrap();
+            let content = &zip[start..start + size];
+            assert_eq!(crc32(content), crc, "{}", name);
+            entries.push((name, String::from_utf8(content.to_vec()).unwrap()));
+            at = start + size;
+        }
+        // The central directory follows, then the end record counting the entries.
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(zip, end), 0x0605_4b50);
+        assert_eq!(u16_at(zip, end + 10), entries.len());
+        assert_eq!(u32_at(zip, end + 16) as usize, at);
+        entries
+    }
+
+    #[test]
+    fn workbook_is_a_stored_ooxml_package() {
+        let rows = vec![vec!["007".to_string(), "A & B".to_string()]];
+        let zip = workbook("personal_ids", &["Code", "Name"], &rows);
+        let entries = entries(&zip);
+        let names: Vec<&str> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/workbook.xml",
+                "xl/_rels/workbook.xml.rels",
+                "xl/styles.xml",
+                "xl/worksheets/sheet1.xml",
+            ]
+        );
+        assert!(entries[2].1.contains("<sheet name=\"personal_ids\""));
+        let sheet = &entries[5].1;
+        assert!(sheet.contains(
+            "<c r=\"A1\" t=\"inlineStr\" s=\"1\"><is><t xml:space=\"preserve\">Code</t>"
+        ));
+        assert!(
+            sheet.contains("<c r=\"A2\" t=\"inlineStr\"><is><t xml:space=\"preserve\">007</t>")
+        );
+        assert!(sheet.contains(">A &amp; B<"));
+    }
+
+    #[test]
+    fn sheet_names_are_cut_to_31_characters() {
+        let zip = workbook(&"x".repeat(40), &["a"], &[]);
+        let workbook = &entries(&zip)[2].1;
+        assert!(workbook.contains(&format!("name=\"{}\"", "x".repeat(31))));
+    }
+
+    #[test]
+    fn column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
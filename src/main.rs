@@ -1,6 +1,7 @@
+use std::sync::Arc;
+
 use leptos::prelude::*;
 use leptos::task::spawn_local;
-use rand::thread_rng;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
 
@@ -8,8 +9,20 @@ use idsmith::{
     bank_account, company_id, countries, credit_card, driver_license, iban, lei, passport,
     personal_id, swift, tax_id, vat,
 };
+use mockbanker_core::bulk;
+use mockbanker_core::dataset::{self, Dataset, Schema};
+use mockbanker_core::diagnose::Check;
+use mockbanker_core::export::{CsvOptions, Dialect, SqlOptions, SqlValue};
+use mockbanker_core::negative::{self, Breakable, Defect};
+use mockbanker_core::validate::{DETECT, Interpretation};
+use mockbanker_core::{
+    BankAccountRow, CompanyGenerator, CompanyIdRow, CompanyRow, CreditCardRow, CreditorIdRow,
+    DriverLicenseRow, IbanRow, IdRow, LeiRow, PassportRow, PersonGenerator, PersonRow, Record,
+    SwiftRow, TaxIdRow, ValidationRow, Validator, VatRow, anatomy, dates, export, generate, nacha,
+    pseudonymize, sepa, snippet, statement,
+};
 
-#[wasm_bindgen(inline_js = r#"
+#[wasm_bindgen(inline_js = r##"
 export function copy_text(text) {
     if (navigator.clipboard) {
         navigator.clipboard.writeText(text).catch(() => {});
@@ -51,6 +64,54 @@ export function download_file(filename, content, mimeType) {
     URL.revokeObjectURL(url);
 }
 
+export function start_worker(url, job, on_progress, on_end) {
+    const worker = new Worker(url);
+    worker.onmessage = (e) => {
+        const data = e.data;
+        if (data.ready) {
+            worker.postMessage(job);
+        } else if (data.progress) {
+            on_progress(data.progress[0], data.progress[1]);
+        } else if (data.blob) {
+            worker.terminate();
+            const url = URL.createObjectURL(data.blob);
+            const a = document.createElement("a");
+            a.href = url;
+            a.download = data.filename;
+            a.click();
+            URL.revokeObjectURL(url);
+            on_end("");
+        } else if (data.error !== undefined) {
+            worker.terminate();
+            on_end(data.error);
+        }
+    };
+    worker.onerror = (e) => {
+        worker.terminate();
+        on_end(e.message || "Background generation failed");
+    };
+    return worker;
+}
+
+export function stop_worker(worker) {
+    worker.terminate();
+}
+
+export function read_file_text(input, callback) {
+    const file = input.files && input.files[0];
+    if (file) {
+        file.text().then(callback);
+    }
+}
+
+export function read_hash() {
+    return window.location.hash.replace(/^#/, "");
+}
+
+export function write_hash(hash) {
+    history.replaceState(null, "", "#" + hash);
+}
+
 export function check_online(callback) {
     window.addEventListener('online', () => callback(true));
     window.addEventListener('offline', () => callback(false));
@@ -73,12 +134,24 @@ export async function trigger_pwa_install() {
     window.deferredPrompt = null;
     return outcome === 'accepted';
 }
-"#)]
+"##)]
 extern "C" {
     fn copy_text(text: &str);
     fn toggle_theme() -> bool;
     fn init_theme() -> bool;
     fn download_file(filename: &str, content: &str, mimeType: &str);
+    #[wasm_bindgen(js_name = download_file)]
+    fn download_bytes(filename: &str, content: &[u8], mimeType: &str);
+    fn start_worker(
+        url: &str,
+        job: &str,
+        on_progress: &js_sys::Function,
+        on_end: &js_sys::Function,
+    ) -> JsValue;
+    fn stop_worker(worker: &JsValue);
+    fn read_file_text(input: &JsValue, callback: &js_sys::Function);
+    fn read_hash() -> String;
+    fn write_hash(hash: &str);
     fn check_online(callback: js_sys::Function) -> bool;
     fn register_pwa_install(callback: js_sys::Function);
     fn trigger_pwa_install() -> js_sys::Promise;
@@ -92,99 +165,197 @@ fn download_csv(filename: &str, content: &str) {
     download_file(filename, content, "text/csv;charset=utf-8;");
 }
 
-fn country_name(code: &str) -> &'static str {
-    countries::get_country_name(code).unwrap_or("Unknown")
+/// Download name carrying the seed, e.g. `ibans_seed_42.csv`.
+fn file_name(stem: &str, seed: u64, ext: &str) -> String {
+    format!("{}_seed_{}.{}", stem, seed, ext)
 }
 
-fn main() {
-    leptos::mount::mount_to_body(App);
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct IbanRow {
-    raw: String,
-    formatted: String,
-    valid: bool,
+/// CSV layout picked in the [`CsvOptionsMenu`], shared by every tab and
+/// kept in local storage.
+fn csv_options() -> CsvOptions {
+    local_storage()
+        .and_then(|s| s.get_item("csv_options").ok().flatten())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct IdRow {
-    code: String,
-    gender: String,
-    dob: String,
-    valid: bool,
+fn save_csv_options(options: &CsvOptions) {
+    if let Some(storage) = local_storage()
+        && let Ok(json) = serde_json::to_string(options)
+    {
+        let _ = storage.set_item("csv_options", &json);
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct BankAccountRow {
-    account: String,
-    routing: String,
-    valid: bool,
+fn export_csv<T: Record>(rows: &[T], seed: u64) {
+    download_csv(
+        &file_name(T::TABLE, seed, "csv"),
+        &export::to_csv(rows, &csv_options()),
+    );
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct CreditCardRow {
-    number: String,
-    brand: String,
-    valid: bool,
+fn export_json<T: Record>(rows: &[T], seed: u64) {
+    download_file(
+        &file_name(T::TABLE, seed, "json"),
+        &export::to_json(rows),
+        "application/json;charset=utf-8;",
+    );
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct SwiftRow {
-    code: String,
-    bank: String,
-    country: String,
-    location: String,
-    valid: bool,
+/// SQL options picked in the [`SqlOptionsMenu`], kept in local storage
+/// with the table name remembered for each `table` it replaces.
+fn sql_options(table: &str) -> SqlOptions {
+    let storage = local_storage();
+    let get = |key: &str| storage.as_ref()?.get_item(key).ok().flatten();
+    SqlOptions {
+        table: get(&format!("sql_table.{}", table)).unwrap_or_default(),
+        ..get("sql_options")
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct CompanyIdRow {
-    code: String,
-    name: String,
-    valid: bool,
+fn save_sql_options(table: &str, options: &SqlOptions) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let shared = SqlOptions {
+        table: String::new(),
+        ..options.clone()
+    };
+    if let Ok(json) = serde_json::to_string(&shared) {
+        let _ = storage.set_item("sql_options", &json);
+    }
+    let _ = storage.set_item(&format!("sql_table.{}", table), options.table.trim());
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct DriverLicenseRow {
-    code: String,
-    name: String,
-    country: String,
-    state: Option<String>,
-    valid: bool,
+/// Downloads the SQL script, plus the rows file for MySQL `LOAD DATA`.
+fn export_sql<T: Record>(rows: &[T], seed: u64) {
+    let options = sql_options(T::TABLE);
+    download_file(
+        &file_name(T::TABLE, seed, "sql"),
+        &export::to_sql(rows, Some(seed), &options),
+        "text/plain;charset=utf-8;",
+    );
+    if options.separate_data() {
+        download_file(
+            &options.data_file(T::TABLE),
+            &export::load_data(rows),
+            "text/tab-separated-values;charset=utf-8;",
+        );
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct PassportRow {
-    code: String,
-    name: String,
-    country: String,
-    valid: bool,
+/// Downloads `rows` in one of the [`export::MORE_FORMATS`].
+fn export_more<T: Record>(rows: &[T], seed: u64, format: &str, mime: &str) {
+    if let Ok(bytes) = export::to_bytes(rows, format) {
+        download_bytes(&file_name(T::TABLE, seed, format), &bytes, mime);
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct TaxIdRow {
-    code: String,
-    name: String,
-    country: String,
-    holder_type: Option<String>,
-    valid: bool,
+/// Copies or downloads `rows` as a test fixture in `language`.
+fn export_snippet<T: Record>(rows: &[T], seed: u64, language: snippet::Language, copy: bool) {
+    let code = snippet::to_snippet(rows, language, Some(seed));
+    if copy {
+        copy_to_clipboard(&code);
+    } else {
+        download_file(
+            &file_name(T::TABLE, seed, language.extension()),
+            &code,
+            "text/plain;charset=utf-8;",
+        );
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct VatRow {
-    code: String,
-    country_code: String,
-    country_name: String,
-    valid: bool,
+/// Tab options carried in the URL hash, e.g.
+/// `#tab=id&country=PL&gender=female&year=1990&count=20&seed=42`.
+struct Permalink(Vec<(String, String)>);
+
+impl Permalink {
+    fn read() -> Self {
+        let hash = read_hash();
+        let params = hash
+            .split('&')
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                let value = js_sys::decode_uri_component(value).ok()?.as_string()?;
+                Some((key.to_string(), value))
+            })
+            .collect();
+        Self(params)
+    }
+
+    /// The link's options if it points at `tab`, otherwise none so the tab
+    /// starts from its defaults.
+    fn for_tab(tab: &str) -> Self {
+        let link = Self::read();
+        if link.get("tab").as_deref() == Some(tab) {
+            link
+        } else {
+            Self(Vec::new())
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    }
+
+    fn count(&self) -> u32 {
+        self.get("count")
+            .and_then(|c| c.parse::<u32>().ok())
+            .map_or(5, |c| c.clamp(1, MAX_COUNT))
+    }
+
+    /// Whether opening the link should regenerate its results: it has a
+    /// seed and the count fits the table, so links never start a download.
+    fn restores_results(&self) -> bool {
+        self.get("seed").is_some() && self.count() <= INLINE_LIMIT
+    }
+
+    /// Replaces the hash without adding a history entry. Empty values are
+    /// left out.
+    fn write(tab: &str, params: &[(&str, String)]) {
+        let mut hash = format!("tab={}", tab);
+        for (key, value) in params.iter().filter(|(_, v)| !v.is_empty()) {
+            let value: String = js_sys::encode_uri_component(value).into();
+            hash.push_str(&format!("&{}={}", key, value));
+        }
+        write_hash(&hash);
+    }
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-struct LeiRow {
-    code: String,
-    lou: String,
-    country_code: String,
-    valid: bool,
+/// Seed of the rows on screen, so a shared link regenerates them; empty
+/// before the first generation.
+fn link_seed<T: Send + Sync + 'static>(
+    results: RwSignal<Vec<T>>,
+    used_seed: RwSignal<u64>,
+) -> String {
+    if results.with(|r| r.is_empty()) {
+        String::new()
+    } else {
+        used_seed.get().to_string()
+    }
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+fn today() -> String {
+    let iso: String = js_sys::Date::new_0().to_iso_string().into();
+    iso[..10].to_string()
+}
+
+fn country_name(code: &str) -> &'static str {
+    countries::get_country_name(code).unwrap_or("Unknown")
+}
+
+fn main() {
+    leptos::mount::mount_to_body(App);
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -194,6 +365,9 @@ struct HistoryItem {
     category: String,
     country: String,
     count: u32,
+    /// `None` for entries saved before generation was seeded.
+    #[serde(default)]
+    seed: Option<u64>,
     results: Vec<String>,
 }
 
@@ -208,540 +382,2338 @@ fn Tooltip(text: String) -> impl IntoView {
 }
 
 #[component]
-fn App() -> impl IntoView {
-    let active_tab = RwSignal::new("iban");
-    let is_light = RwSignal::new(init_theme());
+fn SeedField(seed: RwSignal<String>) -> impl IntoView {
+    view! {
+        <div class="field">
+            <label>
+                "Seed "
+                <Tooltip text="Leave empty for a random seed. Reuse a seed to regenerate exactly the same data.".to_string() />
+            </label>
+            <input type="text" placeholder="random"
+                prop:value=move || seed.get()
+                on:input=move |ev| seed.set(event_target_value(&ev))
+            />
+        </div>
+    }
+}
 
-    let is_online = RwSignal::new(true);
-    let online_cb = Closure::wrap(Box::new(move |online: bool| {
-        is_online.set(online);
-    }) as Box<dyn FnMut(bool)>);
-    is_online.set(check_online(online_cb.into_js_value().unchecked_into()));
+/// Counts above this are generated by the background worker and
+/// downloaded as a file instead of shown in the table.
+const INLINE_LIMIT: u32 = 100;
+
+const MAX_COUNT: u32 = 1_000_000;
+
+/// Loader Trunk builds for the `mockbanker-worker` binary.
+const WORKER_URL: &str = "./mockbanker-worker_loader.js";
+
+/// A tab's background run: the file format for large counts, progress
+/// while the worker is busy and the error if it failed.
+#[derive(Clone, Copy)]
+struct Bulk {
+    /// The tab's default SQL table, to look up the SQL options by.
+    table: &'static str,
+    format: RwSignal<String>,
+    progress: RwSignal<Option<(u32, u32)>>,
+    error: RwSignal<String>,
+    running: StoredValue<Option<Running>, LocalStorage>,
+}
 
-    let can_install = RwSignal::new(false);
-    let install_cb = Closure::wrap(Box::new(move |can: bool| {
-        can_install.set(can);
-    }) as Box<dyn FnMut(bool)>);
-    register_pwa_install(install_cb.into_js_value().unchecked_into());
+/// A worker in progress and the callbacks it reports to, dropped together
+/// when the run ends or is cancelled.
+struct Running {
+    worker: JsValue,
+    _on_progress: Closure<dyn Fn(u32, u32)>,
+    _on_end: Closure<dyn Fn(String)>,
+}
 
-    let install_app = move |_| {
-        spawn_local(async move {
-            let res = wasm_bindgen_futures::JsFuture::from(trigger_pwa_install()).await;
-            if let Ok(val) = res
-                && val.as_bool().unwrap_or(false)
-            {
-                can_install.set(false);
-            }
+impl Bulk {
+    fn new(table: &'static str) -> Self {
+        Self {
+            table,
+            format: RwSignal::new("csv".to_string()),
+            progress: RwSignal::new(None),
+            error: RwSignal::new(String::new()),
+            running: StoredValue::new_local(None),
+        }
+    }
+
+    /// Job for `tab` with the shared options filled in; the tab adds its own.
+    fn job(self, tab: &str, seed: u64, count: u32) -> bulk::Job {
+        bulk::Job {
+            tab: tab.to_string(),
+            count,
+            seed,
+            format: self.format.get(),
+            csv: csv_options(),
+            sql: sql_options(self.table),
+            ..Default::default()
+        }
+    }
+
+    /// Starts `job` in a fresh worker, cancelling any run in progress.
+    fn start(self, job: &bulk::Job) {
+        self.cancel();
+        self.error.set(String::new());
+        self.progress.set(Some((0, job.count)));
+        let on_progress = Closure::<dyn Fn(u32, u32)>::new(move |done, total| {
+            self.progress.set(Some((done, total)));
         });
-    };
+        let on_end = Closure::<dyn Fn(String)>::new(move |error: String| {
+            // Drops this callback too; wasm-bindgen frees it once it returns.
+            self.running.set_value(None);
+            self.progress.set(None);
+            self.error.set(error);
+        });
+        let worker = start_worker(
+            WORKER_URL,
+            &serde_json::to_string(job).unwrap_or_default(),
+            on_progress.as_ref().unchecked_ref(),
+            on_end.as_ref().unchecked_ref(),
+        );
+        self.running.set_value(Some(Running {
+            worker,
+            _on_progress: on_progress,
+            _on_end: on_end,
+        }));
+    }
+
+    fn cancel(self) {
+        if let Some(running) = self.running.try_update_value(Option::take).flatten() {
+            stop_worker(&running.worker);
+        }
+        self.progress.set(None);
+    }
+}
 
+/// Count input; above [`INLINE_LIMIT`] it also asks for the file format,
+/// as the rows go straight to a download.
+#[component]
+fn CountField(count: RwSignal<u32>, bulk: Bulk) -> impl IntoView {
     view! {
-        <div class="app">
-            <header>
-                <div class="header-main">
-                    <img src="assets/logo.svg" alt="MockBanker Logo" class="logo" />
-                    <h1>"MockBanker"</h1>
-                    <div class="header-badges">
-                        <Show when=move || !is_online.get()>
-                            <span class="badge badge-offline">"Offline"</span>
-                        </Show>
-                        <Show when=move || can_install.get()>
-                            <button class="btn-install" on:click=install_app>"Install App"</button>
-                        </Show>
-                    </div>
-                </div>
-                <p>"Generate valid, checksum-correct test data \u{2014} runs entirely in your browser"</p>
-                <button
-                    class="theme-toggle"
-                    aria-label="Toggle theme"
-                    on:click=move |_| { is_light.set(toggle_theme()); }
-                >
-                    {move || if is_light.get() { "\u{263e}" } else { "\u{2600}" }}
-                </button>
-            </header>
+        <div class="field">
+            <label>
+                "Count "
+                <Tooltip text=format!("Up to {} rows are shown here; more are generated in the background and downloaded as a file.", INLINE_LIMIT) />
+            </label>
+            <input type="number" min="1" max=MAX_COUNT
+                prop:value=move || count.get().to_string()
+                on:input=move |ev| {
+                    if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                        count.set(v.clamp(1, MAX_COUNT));
+                    }
+                }
+            />
+        </div>
 
-            <div class="tabs">
-                <button
-                    class=move || if active_tab.get() == "iban" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("iban")
-                >
-                    "IBAN"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "id" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("id")
-                >
-                    "Personal ID"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "bank" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("bank")
-                >
-                    "Bank Account"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "card" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("card")
-                >
-                    "Credit Card"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "swift" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("swift")
-                >
-                    "SWIFT/BIC"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "company" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("company")
-                >
-                    "Company ID"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "driver_license" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("driver_license")
-                >
-                    "Driver's License"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "passport" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("passport")
-                >
-                    "Passport"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "tax_id" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("tax_id")
-                >
-                    "Tax ID"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "vat" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("vat")
-                >
-                    "VAT"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "lei" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("lei")
-                >
-                    "LEI"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "validator" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("validator")
-                >
-                    "Validator"
-                </button>
-                <button
-                    class=move || if active_tab.get() == "history" { "tab active" } else { "tab" }
-                    on:click=move |_| active_tab.set("history")
-                >
-                    "History"
-                </button>
+        <Show when=move || { count.get() > INLINE_LIMIT }>
+            <div class="field">
+                <label>"File"</label>
+                <select on:change=move |ev| bulk.format.set(event_target_value(&ev))>
+                    <option value="csv" selected=move || bulk.format.get() == "csv">"CSV"</option>
+                    <option value="json" selected=move || bulk.format.get() == "json">"JSON"</option>
+                    <option value="sql" selected=move || bulk.format.get() == "sql">"SQL"</option>
+                    <option value="ndjson" selected=move || bulk.format.get() == "ndjson">"NDJSON"</option>
+                    <option value="xml" selected=move || bulk.format.get() == "xml">"XML"</option>
+                    <option value="yaml" selected=move || bulk.format.get() == "yaml">"YAML"</option>
+                </select>
             </div>
-
-            <Show when=move || active_tab.get() == "iban">
-                <IbanTab />
-            </Show>
-            <Show when=move || active_tab.get() == "id">
-                <PersonalIdTab />
-            </Show>
-            <Show when=move || active_tab.get() == "bank">
-                <BankAccountTab />
-            </Show>
-            <Show when=move || active_tab.get() == "card">
-                <CreditCardTab />
-            </Show>
-            <Show when=move || active_tab.get() == "swift">
-                <SwiftTab />
-            </Show>
-            <Show when=move || active_tab.get() == "company">
-                <CompanyIdTab />
-            </Show>
-            <Show when=move || active_tab.get() == "driver_license">
-                <DriverLicenseTab />
+            <Show when=move || bulk.format.get() == "csv">
+                <CsvOptionsMenu />
             </Show>
-            <Show when=move || active_tab.get() == "passport">
-                <PassportTab />
+            <Show when=move || bulk.format.get() == "sql">
+                <SqlOptionsMenu table=bulk.table />
             </Show>
-            <Show when=move || active_tab.get() == "tax_id">
+        </Show>
+    }
+}
+
+/// Gear menu beside CSV downloads for the delimiter, header row, UTF-8
+/// byte-order mark and line endings.
+#[component]
+fn CsvOptionsMenu() -> impl IntoView {
+    let options = RwSignal::new(csv_options());
+    Effect::new(move |_| save_csv_options(&options.get()));
+    view! {
+        <details class="export-options">
+            <summary class="btn btn-secondary" title="CSV options">"\u{2699}"</summary>
+            <div class="export-options-menu">
+                <label>
+                    "Delimiter "
+                    <select on:change=move |ev| {
+                        if let Ok(d) = CsvOptions::delimiter_for(&event_target_value(&ev)) {
+                            options.update(|o| o.delimiter = d);
+                        }
+                    }>
+                        {CsvOptions::DELIMITERS.into_iter().map(|(key, label, d)| view! {
+                            <option value=key selected=move || options.get().delimiter == d>{label}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label>
+                    <input type="checkbox"
+                        prop:checked=move || options.get().header
+                        on:change=move |_| options.update(|o| o.header = !o.header)
+                    />
+                    "Header row"
+                </label>
+                <label>
+                    <input type="checkbox"
+                        prop:checked=move || options.get().bom
+                        on:change=move |_| options.update(|o| o.bom = !o.bom)
+                    />
+                    "UTF-8 BOM (for Excel)"
+                </label>
+                <label>
+                    <input type="checkbox"
+                        prop:checked=move || options.get().crlf
+                        on:change=move |_| options.update(|o| o.crlf = !o.crlf)
+                    />
+                    "CRLF line endings"
+                </label>
+            </div>
+        </details>
+    }
+}
+
+/// Menu beside the SQL options with the [`export::MORE_FORMATS`]: NDJSON,
+/// XML, YAML, XLSX and Parquet.
+#[component]
+fn MoreExports<T: Record + Send + Sync + 'static>(
+    #[prop(into)] rows: Signal<Vec<T>>,
+    #[prop(into)] seed: Signal<u64>,
+) -> impl IntoView {
+    view! {
+        <details class="export-options">
+            <summary class="btn btn-secondary" title="More formats">"More \u{25be}"</summary>
+            <div class="export-options-menu">
+                {export::MORE_FORMATS.into_iter().map(|(key, label, mime)| view! {
+                    <button class="btn btn-secondary"
+                        on:click=move |_| rows.with(|r| export_more(r, seed.get(), key, mime))
+                    >{label}</button>
+                }).collect_view()}
+            </div>
+        </details>
+    }
+}
+
+/// Menu to copy or download the results as a test fixture for Rust,
+/// pytest, JUnit or TypeScript.
+#[component]
+fn CodeExports<T: Record + Send + Sync + 'static>(
+    #[prop(into)] rows: Signal<Vec<T>>,
+    #[prop(into)] seed: Signal<u64>,
+) -> impl IntoView {
+    view! {
+        <details class="export-options">
+            <summary class="btn btn-secondary" title="Test fixtures">"Code \u{25be}"</summary>
+            <div class="export-options-menu">
+                {snippet::Language::ALL.into_iter().map(|language| view! {
+                    <div class="snippet-row">
+                        <span>{language.label()}</span>
+                        <button class="btn btn-secondary"
+                            on:click=move |_| rows.with(|r| export_snippet(r, seed.get(), language, true))
+                        >"Copy"</button>
+                        <button class="btn btn-secondary"
+                            on:click=move |_| rows.with(|r| export_snippet(r, seed.get(), language, false))
+                        >"Download"</button>
+                    </div>
+                }).collect_view()}
+            </div>
+        </details>
+    }
+}
+
+/// Gear menu beside SQL downloads for the dialect, table name, rows per
+/// `INSERT` and bulk loading. `table` is the tab's default table name.
+#[component]
+fn SqlOptionsMenu(table: &'static str) -> impl IntoView {
+    let options = RwSignal::new(sql_options(table));
+    Effect::new(move |_| save_sql_options(table, &options.get()));
+    view! {
+        <details class="export-options">
+            <summary class="btn btn-secondary" title="SQL options">"\u{2699}"</summary>
+            <div class="export-options-menu">
+                <label>
+                    "Dialect "
+                    <select on:change=move |ev| {
+                        if let Ok(d) = Dialect::parse(&event_target_value(&ev)) {
+                            options.update(|o| o.dialect = d);
+                        }
+                    }>
+                        {Dialect::ALL.into_iter().map(|d| view! {
+                            <option value=d.key() selected=move || options.get().dialect == d>{d.label()}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label>
+                    "Table "
+                    <input type="text" class="table-name"
+                        placeholder=table
+                        prop:value=move || options.get().table
+                        on:input=move |ev| options.update(|o| o.table = event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Rows per INSERT "
+                    <select on:change=move |ev| {
+                        if let Ok(n) = event_target_value(&ev).parse() {
+                            options.update(|o| o.batch = n);
+                        }
+                    }>
+                        {[1u32, 100, 1000].into_iter().map(|n| view! {
+                            <option value=n.to_string() selected=move || options.get().batch == n>{n}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                {move || options.get().dialect.load_statement().map(|statement| view! {
+                    <label>
+                        <input type="checkbox"
+                            prop:checked=move || options.get().load
+                            on:change=move |_| options.update(|o| o.load = !o.load)
+                        />
+                        {format!("Bulk load with {}", statement)}
+                    </label>
+                })}
+                <Show when=move || options.get().separate_data()>
+                    <p class="hint">{move || format!("Rows download separately as {}", options.get().data_file(table))}</p>
+                </Show>
+            </div>
+        </details>
+    }
+}
+
+/// Progress bar and Cancel button while a background run is going.
+#[component]
+fn BulkProgress(bulk: Bulk) -> impl IntoView {
+    view! {
+        <Show when=move || bulk.progress.get().is_some()>
+            <div class="bulk-progress">
+                {move || bulk.progress.get().map(|(done, total)| view! {
+                    <progress max=total value=done></progress>
+                    <span>{format!("{} / {} rows", done, total)}</span>
+                })}
+                <button class="btn btn-secondary" on:click=move |_| bulk.cancel()>"Cancel"</button>
+            </div>
+        </Show>
+
+        <Show when=move || !bulk.error.with(String::is_empty)>
+            <div class="result-box result-invalid">
+                <p>{move || bulk.error.get()}</p>
+            </div>
+        </Show>
+    }
+}
+
+/// The "Data" select: valid rows, or near-misses with any or one defect.
+#[component]
+fn DefectSelect(defects: RwSignal<String>, kind: &'static str) -> impl IntoView {
+    let options: Vec<Defect> = Defect::ALL
+        .into_iter()
+        .filter(|d| d.applies_to(kind))
+        .collect();
+    view! {
+        <div class="field">
+            <label>
+                "Data "
+                <Tooltip text="Near-miss rows are broken in one way each, for negative tests; every row is invalid and names its defect.".to_string() />
+            </label>
+            <select on:change=move |ev| defects.set(event_target_value(&ev))>
+                <option value="" selected=move || defects.get().is_empty()>"Valid"</option>
+                <option value="any" selected=move || defects.get() == "any">"Near-miss (any defect)"</option>
+                {options.into_iter().map(|d| view! {
+                    <option value=d.key() selected=move || defects.get() == d.key()>{d.label()}</option>
+                }).collect_view()}
+            </select>
+        </div>
+    }
+}
+
+/// Breaks freshly generated rows as the "Data" select asks; valid rows
+/// pass through untouched. Rows the defect can't be applied to are
+/// reported under the tab, with no rows shown.
+fn near_miss<T: Breakable>(
+    rows: Vec<T>,
+    country: &str,
+    spec: &str,
+    bulk: Bulk,
+    rng: &mut rand::rngs::StdRng,
+) -> Vec<T> {
+    let defects = negative::parse(spec).unwrap_or_default();
+    match negative::break_rows(rows, country, &defects, rng) {
+        Ok(rows) => {
+            bulk.error.set(String::new());
+            rows
+        }
+        Err(e) => {
+            bulk.error.set(e);
+            Vec::new()
+        }
+    }
+}
+
+/// Valid cell text; near-miss rows say how they were broken.
+fn valid_label(valid: bool, defect: Option<Defect>) -> String {
+    match (valid, defect) {
+        (true, _) => "Yes".to_string(),
+        (false, Some(defect)) => format!("No \u{2014} {}", defect.label()),
+        (false, None) => "No".to_string(),
+    }
+}
+
+/// Height of a results row in pixels. [`ResultsTable`] only renders the
+/// rows in view and pads the rest, so every row is kept this tall.
+const ROW_HEIGHT: usize = 41;
+/// Rows that fit the scroll area, see `.table-scroll` in the stylesheet.
+const VISIBLE_ROWS: usize = 15;
+/// Extra rows rendered above and below the visible ones.
+const OVERSCAN: usize = 10;
+
+/// Maps a row to one cell's text, class or view.
+type Cell<T, O> = Arc<dyn Fn(&T) -> O + Send + Sync>;
+
+/// One column of a [`ResultsTable`]. `text` is what the column sorts and
+/// filters by, and what it shows unless a `view` is given.
+struct Column<T> {
+    label: Arc<str>,
+    text: Cell<T, String>,
+    class: Option<Cell<T, &'static str>>,
+    view: Option<Cell<T, AnyView>>,
+}
+
+impl<T> Clone for Column<T> {
+    fn clone(&self) -> Self {
+        Self {
+            label: Arc::clone(&self.label),
+            text: Arc::clone(&self.text),
+            class: self.class.clone(),
+            view: self.view.clone(),
+        }
+    }
+}
+
+impl<T> Column<T> {
+    fn new(
+        label: impl Into<Arc<str>>,
+        text: impl Fn(&T) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            text: Arc::new(text),
+            class: None,
+            view: None,
+        }
+    }
+
+    /// CSS class of each cell.
+    fn class(mut self, class: impl Fn(&T) -> &'static str + Send + Sync + 'static) -> Self {
+        self.class = Some(Arc::new(class));
+        self
+    }
+
+    /// Renders cells with `view` instead of the plain text.
+    fn view<V: IntoView + 'static>(
+        mut self,
+        view: impl Fn(&T) -> V + Send + Sync + 'static,
+    ) -> Self {
+        self.view = Some(Arc::new(move |row| view(row).into_any()));
+        self
+    }
+}
+
+/// The "Valid" column, naming the defect of near-miss rows.
+fn valid_column<T: Record + 'static>(valid: fn(&T) -> bool) -> Column<T> {
+    Column::new("Valid", move |row| valid_label(valid(row), row.defect()))
+        .class(move |row| if valid(row) { "valid-yes" } else { "valid-no" })
+}
+
+/// How a cell sorts: numbers by value and ahead of text, so `9` comes
+/// before `10`, and text as written.
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl SortKey {
+    fn new(text: String) -> Self {
+        match text.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => SortKey::Number(n),
+            _ => SortKey::Text(text),
+        }
+    }
+
+    fn order(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Number(_), SortKey::Text(_)) => std::cmp::Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => std::cmp::Ordering::Greater,
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+/// Generated rows with a text filter, click-to-sort headers and a column
+/// picker. Only the rows scrolled into view are rendered. The Copy button
+/// copies the first visible column's text.
+#[component]
+fn ResultsTable<T>(#[prop(into)] rows: Signal<Vec<T>>, columns: Vec<Column<T>>) -> impl IntoView
+where
+    T: Send + Sync + 'static,
+{
+    let labels = StoredValue::new(
+        columns
+            .iter()
+            .map(|c| Arc::clone(&c.label))
+            .collect::<Vec<_>>(),
+    );
+    let columns = StoredValue::new(columns);
+    let filter = RwSignal::new(String::new());
+    let hidden: RwSignal<Vec<Arc<str>>> = RwSignal::new(Vec::new());
+    // An empty column keeps the rows in generated order.
+    let sort: RwSignal<(Arc<str>, bool)> = RwSignal::new(("".into(), true));
+    let scroll_top = RwSignal::new(0usize);
+    let copied_idx = RwSignal::new(None::<usize>);
+    let scroller = NodeRef::<leptos::html::Div>::new();
+
+    Effect::new(move |_| {
+        rows.track();
+        copied_idx.set(None);
+        scroll_top.set(0);
+        if let Some(el) = scroller.get_untracked() {
+            el.set_scroll_top(0);
+        }
+    });
+
+    // Indices into `rows` of the rows that pass the filter, in sort order.
+    let shown = Memo::new(move |_| {
+        let needle = filter.get().trim().to_lowercase();
+        let (column, ascending) = sort.get();
+        let hidden = hidden.get();
+        rows.with(|rows| {
+            columns.with_value(|columns| {
+                let visible: Vec<&Column<T>> = columns
+                    .iter()
+                    .filter(|c| !hidden.contains(&c.label))
+                    .collect();
+                let mut shown: Vec<usize> = (0..rows.len())
+                    .filter(|&i| {
+                        needle.is_empty()
+                            || visible
+                                .iter()
+                                .any(|c| (c.text)(&rows[i]).to_lowercase().contains(&needle))
+                    })
+                    .collect();
+                if let Some(c) = visible.iter().find(|c| c.label == column) {
+                    let keys: Vec<SortKey> =
+                        rows.iter().map(|row| SortKey::new((c.text)(row))).collect();
+                    shown.sort_by(|a, b| {
+                        let order = keys[*a].order(&keys[*b]);
+                        if ascending { order } else { order.reverse() }
+                    });
+                }
+                shown
+            })
+        })
+    });
+
+    let sort_header = move |column: Arc<str>| {
+        let text = column.to_string();
+        let arrow = Arc::clone(&column);
+        view! {
+            <th
+                class="sortable"
+                on:click=move |_| sort.update(|(c, ascending)| {
+                    if *c == column {
+                        *ascending = !*ascending;
+                    } else {
+                        *c = Arc::clone(&column);
+                        *ascending = true;
+                    }
+                })
+            >
+                {text}
+                {move || match sort.get() {
+                    (c, true) if c == arrow => " \u{25b2}",
+                    (c, false) if c == arrow => " \u{25bc}",
+                    _ => "",
+                }}
+            </th>
+        }
+    };
+
+    let body = move || {
+        let shown = shown.get();
+        let hidden = hidden.get();
+        let cidx = copied_idx.get();
+        let first = (scroll_top.get() / ROW_HEIGHT)
+            .saturating_sub(OVERSCAN)
+            .min(shown.len());
+        let last = (first + VISIBLE_ROWS + 2 * OVERSCAN).min(shown.len());
+        let span = labels.with_value(|l| l.iter().filter(|l| !hidden.contains(l)).count()) + 1;
+        let spacer = move |n: usize| {
+            (n > 0).then(|| {
+                view! {
+                    <tr class="table-spacer">
+                        <td colspan=span style=format!("height: {}px", n * ROW_HEIGHT)></td>
+                    </tr>
+                }
+            })
+        };
+        let page = rows.with(|rows| {
+            columns.with_value(|columns| {
+                shown[first..last]
+                    .iter()
+                    .map(|&i| {
+                        let row = &rows[i];
+                        let copy_text = columns
+                            .iter()
+                            .find(|c| !hidden.contains(&c.label))
+                            .map(|c| (c.text)(row))
+                            .unwrap_or_default();
+                        let is_copied = cidx == Some(i);
+                        let cells = columns
+                            .iter()
+                            .filter(|c| !hidden.contains(&c.label))
+                            .map(|c| {
+                                let class = c.class.as_ref().map(|f| f(row)).unwrap_or_default();
+                                let content = match &c.view {
+                                    Some(view) => view(row),
+                                    None => (c.text)(row).into_any(),
+                                };
+                                view! { <td class=class>{content}</td> }
+                            })
+                            .collect_view();
+                        view! {
+                            <tr>
+                                {cells}
+                                <td>
+                                    <button
+                                        class=if is_copied { "btn-copy copied" } else { "btn-copy" }
+                                        on:click=move |_| {
+                                            copy_to_clipboard(&copy_text);
+                                            copied_idx.set(Some(i));
+                                        }
+                                    >
+                                        {if is_copied { "Copied!" } else { "Copy" }}
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()
+            })
+        });
+        view! {
+            {spacer(first)}
+            {page}
+            {spacer(shown.len() - last)}
+        }
+    };
+
+    view! {
+        <div class="table-tools">
+            <input type="text" class="table-filter"
+                placeholder="Filter rows"
+                prop:value=move || filter.get()
+                on:input=move |ev| filter.set(event_target_value(&ev))
+            />
+            <span>{move || {
+                let total = rows.with(Vec::len);
+                let shown = shown.with(Vec::len);
+                if shown == total {
+                    format!("{} rows", total)
+                } else {
+                    format!("{} of {} rows", shown, total)
+                }
+            }}</span>
+            <details class="column-picker">
+                <summary>"Columns"</summary>
+                {labels.get_value().into_iter().map(|label| {
+                    let text = label.to_string();
+                    let checked = Arc::clone(&label);
+                    view! {
+                        <label>
+                            <input type="checkbox"
+                                prop:checked=move || !hidden.get().contains(&checked)
+                                on:change=move |_| hidden.update(|h| {
+                                    if let Some(pos) = h.iter().position(|l| *l == label) {
+                                        h.remove(pos);
+                                    } else {
+                                        h.push(Arc::clone(&label));
+                                    }
+                                })
+                            />
+                            {text}
+                        </label>
+                    }
+                }).collect_view()}
+            </details>
+        </div>
+        <div
+            class="table-scroll"
+            node_ref=scroller
+            on:scroll=move |ev| {
+                scroll_top.set(event_target::<web_sys::Element>(&ev).scroll_top().max(0) as usize)
+            }
+        >
+            <table>
+                <thead>
+                    <tr>
+                        {move || {
+                            let hidden = hidden.get();
+                            labels.with_value(|labels| {
+                                labels
+                                    .iter()
+                                    .filter(|l| !hidden.contains(l))
+                                    .map(|label| sort_header(Arc::clone(label)))
+                                    .collect_view()
+                            })
+                        }}
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>{body}</tbody>
+            </table>
+        </div>
+    }
+}
+
+const TABS: &[&str] = &[
+    "iban",
+    "id",
+    "bank",
+    "card",
+    "swift",
+    "company",
+    "driver_license",
+    "passport",
+    "tax_id",
+    "vat",
+    "lei",
+    "person",
+    "entity",
+    "sepa_ct",
+    "sepa_dd",
+    "creditor_id",
+    "statement",
+    "ach",
+    "dataset",
+    "pseudonymize",
+    "validator",
+    "history",
+];
+
+#[component]
+fn App() -> impl IntoView {
+    let active_tab = RwSignal::new(
+        Permalink::read()
+            .get("tab")
+            .and_then(|t| TABS.iter().find(|k| **k == t).copied())
+            .unwrap_or("iban"),
+    );
+    let is_light = RwSignal::new(init_theme());
+
+    let is_online = RwSignal::new(true);
+    let online_cb = Closure::wrap(Box::new(move |online: bool| {
+        is_online.set(online);
+    }) as Box<dyn FnMut(bool)>);
+    is_online.set(check_online(online_cb.into_js_value().unchecked_into()));
+
+    let can_install = RwSignal::new(false);
+    let install_cb = Closure::wrap(Box::new(move |can: bool| {
+        can_install.set(can);
+    }) as Box<dyn FnMut(bool)>);
+    register_pwa_install(install_cb.into_js_value().unchecked_into());
+
+    let install_app = move |_| {
+        spawn_local(async move {
+            let res = wasm_bindgen_futures::JsFuture::from(trigger_pwa_install()).await;
+            if let Ok(val) = res
+                && val.as_bool().unwrap_or(false)
+            {
+                can_install.set(false);
+            }
+        });
+    };
+
+    view! {
+        <div class="app">
+            <header>
+                <div class="header-main">
+                    <img src="assets/logo.svg" alt="MockBanker Logo" class="logo" />
+                    <h1>"MockBanker"</h1>
+                    <div class="header-badges">
+                        <Show when=move || !is_online.get()>
+                            <span class="badge badge-offline">"Offline"</span>
+                        </Show>
+                        <Show when=move || can_install.get()>
+                            <button class="btn-install" on:click=install_app>"Install App"</button>
+                        </Show>
+                    </div>
+                </div>
+                <p>"Generate valid, checksum-correct test data \u{2014} runs entirely in your browser"</p>
+                <button
+                    class="theme-toggle"
+                    aria-label="Toggle theme"
+                    on:click=move |_| { is_light.set(toggle_theme()); }
+                >
+                    {move || if is_light.get() { "\u{263e}" } else { "\u{2600}" }}
+                </button>
+            </header>
+
+            <div class="tabs">
+                <button
+                    class=move || if active_tab.get() == "iban" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("iban")
+                >
+                    "IBAN"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "id" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("id")
+                >
+                    "Personal ID"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "bank" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("bank")
+                >
+                    "Bank Account"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "card" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("card")
+                >
+                    "Credit Card"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "swift" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("swift")
+                >
+                    "SWIFT/BIC"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "company" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("company")
+                >
+                    "Company ID"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "driver_license" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("driver_license")
+                >
+                    "Driver's License"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "passport" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("passport")
+                >
+                    "Passport"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "tax_id" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("tax_id")
+                >
+                    "Tax ID"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "vat" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("vat")
+                >
+                    "VAT"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "lei" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("lei")
+                >
+                    "LEI"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "person" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("person")
+                >
+                    "Person"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "entity" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("entity")
+                >
+                    "Company"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "sepa_ct" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("sepa_ct")
+                >
+                    "SEPA Transfer"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "sepa_dd" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("sepa_dd")
+                >
+                    "SEPA Direct Debit"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "creditor_id" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("creditor_id")
+                >
+                    "Creditor ID"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "statement" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("statement")
+                >
+                    "Statement"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "ach" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("ach")
+                >
+                    "ACH (NACHA)"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "dataset" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("dataset")
+                >
+                    "Dataset"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "pseudonymize" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("pseudonymize")
+                >
+                    "Pseudonymize"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "validator" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("validator")
+                >
+                    "Validator"
+                </button>
+                <button
+                    class=move || if active_tab.get() == "history" { "tab active" } else { "tab" }
+                    on:click=move |_| active_tab.set("history")
+                >
+                    "History"
+                </button>
+            </div>
+
+            <Show when=move || active_tab.get() == "iban">
+                <IbanTab />
+            </Show>
+            <Show when=move || active_tab.get() == "id">
+                <PersonalIdTab />
+            </Show>
+            <Show when=move || active_tab.get() == "bank">
+                <BankAccountTab />
+            </Show>
+            <Show when=move || active_tab.get() == "card">
+                <CreditCardTab />
+            </Show>
+            <Show when=move || active_tab.get() == "swift">
+                <SwiftTab />
+            </Show>
+            <Show when=move || active_tab.get() == "company">
+                <CompanyIdTab />
+            </Show>
+            <Show when=move || active_tab.get() == "driver_license">
+                <DriverLicenseTab />
+            </Show>
+            <Show when=move || active_tab.get() == "passport">
+                <PassportTab />
+            </Show>
+            <Show when=move || active_tab.get() == "tax_id">
                 <TaxIdTab />
             </Show>
             <Show when=move || active_tab.get() == "vat">
                 <VatTab />
             </Show>
-            <Show when=move || active_tab.get() == "lei">
-                <LeiTab />
+            <Show when=move || active_tab.get() == "lei">
+                <LeiTab />
+            </Show>
+            <Show when=move || active_tab.get() == "person">
+                <PersonTab />
+            </Show>
+            <Show when=move || active_tab.get() == "entity">
+                <CompanyTab />
+            </Show>
+            <Show when=move || active_tab.get() == "sepa_ct">
+                <SepaTransferTab />
+            </Show>
+            <Show when=move || active_tab.get() == "sepa_dd">
+                <SepaDebitTab />
+            </Show>
+            <Show when=move || active_tab.get() == "creditor_id">
+                <CreditorIdTab />
+            </Show>
+            <Show when=move || active_tab.get() == "statement">
+                <StatementTab />
+            </Show>
+            <Show when=move || active_tab.get() == "ach">
+                <AchTab />
+            </Show>
+            <Show when=move || active_tab.get() == "dataset">
+                <DatasetTab />
+            </Show>
+            <Show when=move || active_tab.get() == "pseudonymize">
+                <PseudonymizeTab />
+            </Show>
+            <Show when=move || active_tab.get() == "validator">
+                <ValidatorTab />
+            </Show>
+            <Show when=move || active_tab.get() == "history">
+                <HistoryTab />
+            </Show>
+
+            <footer>
+                <p>
+                    "Built with \u{2764} by "
+                    <a href="https://tonybenoy.com" target="_blank">"Tony Benoy"</a>
+                    " \u{00b7} "
+                    <a href="https://github.com/tonybenoy/mockbanker" target="_blank">"GitHub"</a>
+                    " \u{00b7} "
+                    <a href="https://github.com/tonybenoy/mockbanker/issues" target="_blank">"Contribute"</a>
+                </p>
+                <div class="share-links">
+                    <span>"Share: "</span>
+                    <a href="https://www.facebook.com/sharer/sharer.php?u=https://tonybenoy.github.io/mockbanker/" target="_blank">"Facebook"</a>
+                    <a href="https://twitter.com/intent/tweet?url=https://tonybenoy.github.io/mockbanker/&text=MockBanker%20%E2%80%94%20Free%20IBAN%20%26%20Personal%20ID%20Generator" target="_blank">"Twitter"</a>
+                    <a href="https://www.linkedin.com/sharing/share-offsite/?url=https://tonybenoy.github.io/mockbanker/" target="_blank">"LinkedIn"</a>
+                </div>
+                <p style="margin-top: 0.5rem; opacity: 0.8;">
+                    "Powered by "
+                    <a href="https://github.com/Sunyata-OU/idsmith" target="_blank">"idsmith"</a>
+                    " \u{00b7} Built with "
+                    <a href="https://gemini.google.com" target="_blank">"Gemini"</a>
+                    " & "
+                    <a href="https://claude.ai" target="_blank">"Claude"</a>
+                </p>
+            </footer>
+        </div>
+    }
+}
+
+fn add_to_history(category: &str, country: &str, count: u32, seed: u64, results: Vec<String>) {
+    let window = web_sys::window().unwrap();
+    let storage = window.local_storage().unwrap().unwrap();
+    let mut history: Vec<HistoryItem> = storage
+        .get_item("history")
+        .unwrap()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    let item = HistoryItem {
+        id: rand::random::<u64>().to_string(),
+        timestamp: js_sys::Date::now() as u64,
+        category: category.to_string(),
+        country: country.to_string(),
+        count,
+        seed: Some(seed),
+        results,
+    };
+
+    history.insert(0, item);
+    if history.len() > 50 {
+        history.truncate(50);
+    }
+
+    if let Ok(json) = serde_json::to_string(&history) {
+        let _ = storage.set_item("history", &json);
+    }
+}
+
+#[component]
+fn HistoryTab() -> impl IntoView {
+    Permalink::write("history", &[]);
+    let get_history = || {
+        let window = web_sys::window().unwrap();
+        let storage = window.local_storage().unwrap().unwrap();
+        storage
+            .get_item("history")
+            .unwrap()
+            .and_then(|s| serde_json::from_str::<Vec<HistoryItem>>(&s).ok())
+            .unwrap_or_default()
+    };
+
+    let history = RwSignal::new(get_history());
+
+    let clear_history = move |_| {
+        let window = web_sys::window().unwrap();
+        let storage = window.local_storage().unwrap().unwrap();
+        let _ = storage.remove_item("history");
+        history.set(Vec::new());
+    };
+
+    view! {
+        <div class="history-tab">
+            <div class="controls">
+                <button class="btn btn-secondary" on:click=clear_history>"Clear History"</button>
+            </div>
+
+            <Show when=move || history.get().is_empty()>
+                <div class="empty">"No history yet. Generate some data to see it here!"</div>
+            </Show>
+
+            <div class="history-list">
+                {move || history.get().into_iter().map(|item| {
+                    let date = js_sys::Date::new(&js_sys::Number::from(item.timestamp as f64));
+                    let date_str = format!("{}/{}/{} {}:{:02}",
+                        date.get_date(), date.get_month() + 1, date.get_full_year(),
+                        date.get_hours(), date.get_minutes());
+
+                    view! {
+                        <div class="history-item">
+                            <div class="history-meta">
+                                <span class="history-category">{item.category}</span>
+                                <span class="history-country">{item.country}</span>
+                                <span class="history-count">{item.count} " items"</span>
+                                {item.seed.map(|s| view! { <span class="history-seed">"seed " {s}</span> })}
+                                <span class="history-date">{date_str}</span>
+                            </div>
+                            <div class="history-results">
+                                {item.results.join(", ")}
+                            </div>
+                        </div>
+                    }
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
+
+/// IBAN with each registry-defined part in its own colour, optionally
+/// grouped in blocks of four like `iban::format_iban`.
+#[component]
+fn IbanBreakdown(#[prop(into)] code: String, #[prop(optional)] spaces: bool) -> impl IntoView {
+    let mut position = 0;
+    let parts = anatomy::segments(&code)
+        .into_iter()
+        .map(|(role, text)| {
+            let mut chunk = String::new();
+            for c in text.chars() {
+                if spaces && position > 0 && position % 4 == 0 {
+                    chunk.push(' ');
+                }
+                chunk.push(c);
+                position += 1;
+            }
+            view! {
+                <span class=format!("iban-part iban-{}", role.key()) title=role.label()>{chunk}</span>
+            }
+        })
+        .collect_view();
+    view! { <span class="iban-anatomy">{parts}</span> }
+}
+
+/// Key to the [`IbanBreakdown`] colours.
+#[component]
+fn IbanLegend() -> impl IntoView {
+    use anatomy::Role;
+    let roles = [
+        Role::Country,
+        Role::CheckDigits,
+        Role::Bank,
+        Role::Branch,
+        Role::Account,
+        Role::NationalCheck,
+    ];
+    view! {
+        <span class="iban-legend">
+            {roles.into_iter().map(|role| view! {
+                <span class=format!("iban-part iban-{}", role.key())>{role.label()}</span>
+            }).collect_view()}
+        </span>
+    }
+}
+
+#[component]
+fn IbanTab() -> impl IntoView {
+    let link = Permalink::for_tab("iban");
+    let mut countries: Vec<&str> = iban::supported_countries();
+    countries.sort_by_key(|c| country_name(c));
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "DE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(IbanRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let spaces = RwSignal::new(link.get("spaces").as_deref() != Some("0"));
+    let bank_code = RwSignal::new(link.get("bank").unwrap_or_default());
+    let branch_code = RwSignal::new(link.get("branch").unwrap_or_default());
+    let error = RwSignal::new(String::new());
+    let results: RwSignal<Vec<IbanRow>> = RwSignal::new(Vec::new());
+
+    let countries_list: Vec<(String, String)> = countries
+        .into_iter()
+        .map(|c| (c.to_string(), country_name(c).to_string()))
+        .collect();
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                spaces: spaces.get(),
+                bank: bank_code.get(),
+                branch: branch_code.get(),
+                ..bulk.job("iban", seed_value, n)
+            });
+            return;
+        }
+        let c_opt = if c == "Random" {
+            None
+        } else {
+            Some(c.as_str())
+        };
+        let (bank, branch) = (bank_code.get(), branch_code.get());
+        let rows = if bank.trim().is_empty() && branch.trim().is_empty() {
+            generate::ibans(c_opt, n, &mut rng)
+        } else {
+            let Some(c) = c_opt else {
+                error.set("Pick a country to fix a bank or branch code".to_string());
+                return;
+            };
+            match generate::ibans_at_bank(c, &bank, &branch, n, &mut rng) {
+                Ok(rows) => rows,
+                Err(message) => {
+                    error.set(message);
+                    return;
+                }
+            }
+        };
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        error.set(String::new());
+        let history_results = rows.iter().map(|r| r.raw.clone()).collect();
+        results.set(rows);
+        used_seed.set(seed_value);
+        add_to_history("IBAN", &c, n, seed_value, history_results);
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let use_spaces = spaces.get();
+        let text: String = rows
+            .iter()
+            .map(|r| r.display(use_spaces))
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
+    };
+
+    let save_csv = move |_| {
+        download_csv(
+            &file_name(IbanRow::TABLE, used_seed.get(), "csv"),
+            &export::ibans_csv(&results.get(), spaces.get(), &csv_options()),
+        );
+    };
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "iban",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("bank", bank_code.get()),
+                ("branch", branch_code.get()),
+                (
+                    "spaces",
+                    if spaces.get() {
+                        String::new()
+                    } else {
+                        "0".to_string()
+                    },
+                ),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("IBAN", move |r: &IbanRow| {
+            r.display(spaces.get()).to_string()
+        })
+        .view(
+            move |r: &IbanRow| view! { <IbanBreakdown code=r.raw.clone() spaces=spaces.get() /> },
+        ),
+        valid_column(|r: &IbanRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Country"</label>
+                <SearchableSelect
+                    options=countries_list
+                    selected=country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <CountField count=count bulk=bulk />
+
+            <div class="field">
+                <label>
+                    "Bank code "
+                    <Tooltip text="Optional, e.g. a German BLZ; only the account part is random".to_string() />
+                </label>
+                <input type="text" style="width: 120px"
+                    placeholder="Any"
+                    prop:value=move || bank_code.get()
+                    on:input=move |ev| bank_code.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>
+                    "Branch code "
+                    <Tooltip text="Optional, e.g. a UK sort code".to_string() />
+                </label>
+                <input type="text" style="width: 120px"
+                    placeholder="Any"
+                    prop:value=move || branch_code.get()
+                    on:input=move |ev| branch_code.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="checkbox-field">
+                <input type="checkbox" id="spaces"
+                    prop:checked=move || spaces.get()
+                    on:change=move |_| spaces.update(|s| *s = !*s)
+                />
+                <label for="spaces">"Spaces"</label>
+            </div>
+
+            <DefectSelect defects=defects kind="iban" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=IbanRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
+            </Show>
+        </div>
+
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
+            </div>
+        </Show>
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+                <IbanLegend />
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
+    }
+}
+
+#[component]
+fn PersonalIdTab() -> impl IntoView {
+    let link = Permalink::for_tab("id");
+    let registry = personal_id::Registry::new();
+    let id_countries: Vec<(String, String, String)> = registry
+        .list_countries()
+        .iter()
+        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
+        .collect();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "EE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(IdRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let gender = RwSignal::new(link.get("gender").unwrap_or_else(|| "any".to_string()));
+    let year = RwSignal::new(link.get("year").unwrap_or_default());
+    let results: RwSignal<Vec<IdRow>> = RwSignal::new(Vec::new());
+
+    let registry = StoredValue::new(registry);
+    let id_countries_stored = StoredValue::new(id_countries.clone());
+
+    let current_description = Memo::new(move |_| {
+        let c = country.get();
+        id_countries_stored.with_value(|list| {
+            list.iter()
+                .find(|(code, _, _)| code == &c)
+                .map(|(_, _, d)| d.clone())
+                .unwrap_or_default()
+        })
+    });
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                gender: gender.get(),
+                year: year.get().parse().ok(),
+                ..bulk.job("id", seed_value, n)
+            });
+            return;
+        }
+        let g = gender.get();
+        let y: Option<u16> = year.get().parse().ok();
+        let rows = registry.with_value(|reg| generate::personal_ids(reg, &c, &g, y, n, &mut rng));
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
+        results.set(rows);
+        used_seed.set(seed_value);
+        add_to_history("Personal ID", &c, n, seed_value, history_results);
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let text: String = rows
+            .iter()
+            .map(|r| r.code.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
+    };
+
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> = id_countries
+        .clone()
+        .into_iter()
+        .map(|(c, n, _)| (c, n))
+        .collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "id",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("gender", gender.get().replace("any", "")),
+                ("year", year.get()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Code", |r: &IdRow| r.code.clone()),
+        Column::new("Gender", |r: &IdRow| r.gender.clone()).class(|_| "gender"),
+        Column::new("Date of Birth", |r: &IdRow| r.dob.clone()).class(|_| "dob"),
+        valid_column(|r: &IdRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>
+                    "Country "
+                    <Tooltip text=current_description.get() />
+                </label>
+                <SearchableSelect
+                    options=countries_for_select
+                    selected=country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <CountField count=count bulk=bulk />
+
+            <div class="field">
+                <label>"Gender"</label>
+                <select on:change=move |ev| {
+                    gender.set(event_target_value(&ev));
+                }>
+                    <option value="any" selected=move || gender.get() == "any">"Any"</option>
+                    <option value="male" selected=move || gender.get() == "male">"Male"</option>
+                    <option value="female" selected=move || gender.get() == "female">"Female"</option>
+                </select>
+            </div>
+
+            <div class="field">
+                <label>"Year"</label>
+                <input type="text" placeholder="any"
+                    prop:value=move || year.get()
+                    on:input=move |ev| {
+                        year.set(event_target_value(&ev));
+                    }
+                />
+            </div>
+
+            <DefectSelect defects=defects kind="id" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=IdRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
-            <Show when=move || active_tab.get() == "validator">
-                <ValidatorTab />
+        </div>
+
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
+    }
+}
+
+#[component]
+fn BankAccountTab() -> impl IntoView {
+    let link = Permalink::for_tab("bank");
+    let registry = bank_account::Registry::new();
+    let countries: Vec<(String, String, String, bool)> = registry
+        .list_countries()
+        .iter()
+        .map(|(c, n, d, i)| (c.to_string(), n.to_string(), d.to_string(), *i))
+        .collect();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "US".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(BankAccountRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<BankAccountRow>> = RwSignal::new(Vec::new());
+
+    let convert = RwSignal::new(link.get("mode").as_deref() == Some("convert"));
+    let conv_bank = RwSignal::new(String::new());
+    let conv_account = RwSignal::new(String::new());
+    let conv_iban = RwSignal::new(String::new());
+    let converted = RwSignal::new(String::new());
+    let conv_error = RwSignal::new(String::new());
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("bank", seed_value, n)
+            });
+            return;
+        }
+        let rows = generate::bank_accounts(&c, n, &mut rng);
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        results.set(rows);
+        used_seed.set(seed_value);
+    };
+
+    let to_iban = move || match anatomy::iban_from_account(
+        &country.get(),
+        &conv_bank.get(),
+        &conv_account.get(),
+    ) {
+        Ok(code) => {
+            conv_iban.set(iban::format_iban(&code));
+            converted.set(code);
+            conv_error.set(String::new());
+        }
+        Err(e) => {
+            converted.set(String::new());
+            conv_error.set(e);
+        }
+    };
+
+    let to_account = move || match anatomy::account_from_iban(&conv_iban.get()) {
+        Ok(domestic) => {
+            converted.set(conv_iban.get().split_whitespace().collect());
+            conv_account.set(domestic.written_account());
+            country.set(domestic.country);
+            conv_bank.set(domestic.bank_code);
+            conv_error.set(String::new());
+        }
+        Err(e) => {
+            converted.set(String::new());
+            conv_error.set(e);
+        }
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let text: String = rows
+            .iter()
+            .map(BankAccountRow::display)
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
+    };
+
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> = countries
+        .clone()
+        .into_iter()
+        .map(|(c, n, _, _)| (c, n))
+        .collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "bank",
+            &[
+                ("country", country.get()),
+                (
+                    "mode",
+                    if convert.get() { "convert" } else { "" }.to_string(),
+                ),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Account", |r: &BankAccountRow| r.account.clone()),
+        Column::new("Routing", |r: &BankAccountRow| r.routing.clone()),
+        Column::new("IBAN", |r: &BankAccountRow| iban::format_iban(&r.iban)),
+        valid_column(|r: &BankAccountRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Country"</label>
+                <SearchableSelect
+                    options=countries_for_select
+                    selected=country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <div class="field">
+                <label>"Mode"</label>
+                <select on:change=move |ev| convert.set(event_target_value(&ev) == "convert")>
+                    <option value="generate" selected=move || !convert.get()>"Generate"</option>
+                    <option value="convert" selected=move || convert.get()>"Convert IBAN"</option>
+                </select>
+            </div>
+
+            <Show when=move || convert.get()>
+                <div class="field">
+                    <label>
+                        "Bank code "
+                        <Tooltip text="Bank identifier, optionally followed by the branch code, e.g. 37040044 or NWBK601613".to_string() />
+                    </label>
+                    <input type="text" style="width: 140px"
+                        prop:value=move || conv_bank.get()
+                        on:input=move |ev| conv_bank.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <div class="field">
+                    <label>"Account"</label>
+                    <input type="text" style="width: 180px"
+                        prop:value=move || conv_account.get()
+                        on:input=move |ev| conv_account.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <button class="btn btn-primary" on:click=move |_| to_iban()>"To IBAN"</button>
+
+                <div class="field">
+                    <label>"IBAN"</label>
+                    <input type="text" style="width: 260px"
+                        prop:value=move || conv_iban.get()
+                        on:input=move |ev| conv_iban.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <button class="btn btn-primary" on:click=move |_| to_account()>"To account"</button>
             </Show>
-            <Show when=move || active_tab.get() == "history">
-                <HistoryTab />
+
+            <Show when=move || !convert.get()>
+                <CountField count=count bulk=bulk />
+
+                <DefectSelect defects=defects kind="bank" />
+
+                <SeedField seed=seed />
+
+                <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+                <Show when=move || !results.get().is_empty()>
+                    <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                    <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                    <CsvOptionsMenu />
+                    <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                    <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                    <SqlOptionsMenu table=BankAccountRow::TABLE />
+                    <MoreExports rows=results seed=used_seed />
+                    <CodeExports rows=results seed=used_seed />
+                </Show>
             </Show>
+        </div>
 
-            <footer>
-                <p>
-                    "Built with \u{2764} by "
-                    <a href="https://tonybenoy.com" target="_blank">"Tony Benoy"</a>
-                    " \u{00b7} "
-                    <a href="https://github.com/tonybenoy/mockbanker" target="_blank">"GitHub"</a>
-                    " \u{00b7} "
-                    <a href="https://github.com/tonybenoy/mockbanker/issues" target="_blank">"Contribute"</a>
-                </p>
-                <div class="share-links">
-                    <span>"Share: "</span>
-                    <a href="https://www.facebook.com/sharer/sharer.php?u=https://tonybenoy.github.io/mockbanker/" target="_blank">"Facebook"</a>
-                    <a href="https://twitter.com/intent/tweet?url=https://tonybenoy.github.io/mockbanker/&text=MockBanker%20%E2%80%94%20Free%20IBAN%20%26%20Personal%20ID%20Generator" target="_blank">"Twitter"</a>
-                    <a href="https://www.linkedin.com/sharing/share-offsite/?url=https://tonybenoy.github.io/mockbanker/" target="_blank">"LinkedIn"</a>
-                </div>
-                <p style="margin-top: 0.5rem; opacity: 0.8;">
-                    "Powered by "
-                    <a href="https://github.com/Sunyata-OU/idsmith" target="_blank">"idsmith"</a>
-                    " \u{00b7} Built with "
-                    <a href="https://gemini.google.com" target="_blank">"Gemini"</a>
-                    " & "
-                    <a href="https://claude.ai" target="_blank">"Claude"</a>
-                </p>
-            </footer>
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || convert.get() && !conv_error.with(String::is_empty)>
+            <div class="result-box result-invalid">
+                <p>{move || conv_error.get()}</p>
+            </div>
+        </Show>
+
+        <Show when=move || convert.get() && !converted.with(String::is_empty)>
+            <div class="iban-breakdown">
+                {move || view! { <IbanBreakdown code=converted.get() spaces=true /> }}
+                <IbanLegend />
+            </div>
+        </Show>
+
+        <Show when=move || !convert.get() && results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !convert.get() && !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
+    }
+}
+
+#[component]
+fn CreditCardTab() -> impl IntoView {
+    let link = Permalink::for_tab("card");
+    let registry = credit_card::Registry::new();
+    let brands: Vec<String> = registry
+        .list_brands()
+        .iter()
+        .map(|b| b.to_string())
+        .collect();
+
+    let brand = RwSignal::new(link.get("brand").unwrap_or_else(|| "visa".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(CreditCardRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<CreditCardRow>> = RwSignal::new(Vec::new());
+
+    let registry = StoredValue::new(registry);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let b = brand.get();
+        let n = count.get();
+        let year = (js_sys::Date::new_0().get_full_year() % 100) as u16;
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                brand: brand.get(),
+                current_year: year,
+                defects: defects.get(),
+                ..bulk.job("card", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::credit_cards(reg, &b, year, n, &mut rng));
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        results.set(rows);
+        used_seed.set(seed_value);
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let text: String = rows
+            .iter()
+            .map(|r| r.number.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
+    };
+
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let brands_for_select = brands.clone();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "card",
+            &[
+                ("brand", brand.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Number", |r: &CreditCardRow| r.number.clone()),
+        Column::new("Brand", |r: &CreditCardRow| r.brand.clone()),
+        valid_column(|r: &CreditCardRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Brand"</label>
+                <select on:change=move |ev| {
+                    brand.set(event_target_value(&ev));
+                }>
+                    {brands_for_select.into_iter().map(|id| {
+                        let id2 = id.clone();
+                        let label = id.clone();
+                        view! {
+                            <option value={id} selected=move || brand.get() == id2>
+                                {label}
+                            </option>
+                        }
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="card" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=CreditCardRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
+            </Show>
         </div>
+
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a brand and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
     }
 }
 
-fn add_to_history(category: &str, country: &str, count: u32, results: Vec<String>) {
-    let window = web_sys::window().unwrap();
-    let storage = window.local_storage().unwrap().unwrap();
-    let mut history: Vec<HistoryItem> = storage
-        .get_item("history")
-        .unwrap()
-        .and_then(|s| serde_json::from_str(&s).ok())
-        .unwrap_or_default();
+#[component]
+fn SwiftTab() -> impl IntoView {
+    let link = Permalink::for_tab("swift");
+    let registry = swift::Registry::new();
+    let countries: Vec<String> = iban::supported_countries()
+        .into_iter()
+        .map(|c| c.to_string())
+        .collect();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "DE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(SwiftRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<SwiftRow>> = RwSignal::new(Vec::new());
+
+    let registry = StoredValue::new(registry);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("swift", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::swift_codes(reg, &c, n, &mut rng));
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        results.set(rows);
+        used_seed.set(seed_value);
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let text: String = rows
+            .iter()
+            .map(|r| r.code.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
+    };
+
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> = countries
+        .clone()
+        .into_iter()
+        .map(|code| (code.clone(), country_name(&code).to_string()))
+        .collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "swift",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("SWIFT/BIC", |r: &SwiftRow| r.code.clone()),
+        Column::new("Bank", |r: &SwiftRow| r.bank.clone()),
+        Column::new("Country", |r: &SwiftRow| r.country.clone()),
+        Column::new("Location", |r: &SwiftRow| r.location.clone()),
+        valid_column(|r: &SwiftRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Country"</label>
+                <SearchableSelect
+                    options=countries_for_select
+                    selected=country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="swift" />
+
+            <SeedField seed=seed />
 
-    let item = HistoryItem {
-        id: rand::random::<u64>().to_string(),
-        timestamp: js_sys::Date::now() as u64,
-        category: category.to_string(),
-        country: country.to_string(),
-        count,
-        results,
-    };
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
-    history.insert(0, item);
-    if history.len() > 50 {
-        history.truncate(50);
-    }
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=SwiftRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
+            </Show>
+        </div>
 
-    if let Ok(json) = serde_json::to_string(&history) {
-        let _ = storage.set_item("history", &json);
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
     }
 }
 
 #[component]
-fn HistoryTab() -> impl IntoView {
-    let get_history = || {
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage
-            .get_item("history")
-            .unwrap()
-            .and_then(|s| serde_json::from_str::<Vec<HistoryItem>>(&s).ok())
-            .unwrap_or_default()
-    };
+fn CompanyIdTab() -> impl IntoView {
+    let link = Permalink::for_tab("company");
+    let registry = company_id::Registry::new();
+    let countries: Vec<(String, String, String)> = registry
+        .list_countries()
+        .iter()
+        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
+        .collect();
 
-    let history = RwSignal::new(get_history());
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "EE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(CompanyIdRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<CompanyIdRow>> = RwSignal::new(Vec::new());
 
-    let clear_history = move |_| {
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        let _ = storage.remove_item("history");
-        history.set(Vec::new());
+    let registry = StoredValue::new(registry);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("company", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::company_ids(reg, &c, n, &mut rng));
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        results.set(rows);
+        used_seed.set(seed_value);
+    };
+
+    let copy_all = move |_| {
+        let rows = results.get();
+        let text: String = rows
+            .iter()
+            .map(|r| r.code.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_to_clipboard(&text);
     };
 
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> = countries
+        .clone()
+        .into_iter()
+        .map(|(c, n, _)| (c, n))
+        .collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "company",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Code", |r: &CompanyIdRow| r.code.clone()),
+        Column::new("Name", |r: &CompanyIdRow| r.name.clone()),
+        valid_column(|r: &CompanyIdRow| r.valid),
+    ];
+
     view! {
-        <div class="history-tab">
-            <div class="controls">
-                <button class="btn btn-secondary" on:click=clear_history>"Clear History"</button>
+        <div class="controls">
+            <div class="field">
+                <label>"Country"</label>
+                <SearchableSelect
+                    options=countries_for_select
+                    selected=country
+                    on_change=Callback::new(|_| ())
+                />
             </div>
 
-            <Show when=move || history.get().is_empty()>
-                <div class="empty">"No history yet. Generate some data to see it here!"</div>
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="company" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=CompanyIdRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
+        </div>
 
-            <div class="history-list">
-                {move || history.get().into_iter().map(|item| {
-                    let date = js_sys::Date::new(&js_sys::Number::from(item.timestamp as f64));
-                    let date_str = format!("{}/{}/{} {}:{:02}",
-                        date.get_date(), date.get_month() + 1, date.get_full_year(),
-                        date.get_hours(), date.get_minutes());
+        <BulkProgress bulk=bulk />
 
-                    view! {
-                        <div class="history-item">
-                            <div class="history-meta">
-                                <span class="history-category">{item.category}</span>
-                                <span class="history-country">{item.country}</span>
-                                <span class="history-count">{item.count} " items"</span>
-                                <span class="history-date">{date_str}</span>
-                            </div>
-                            <div class="history-results">
-                                {item.results.join(", ")}
-                            </div>
-                        </div>
-                    }
-                }).collect_view()}
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
-        </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
     }
 }
 
 #[component]
-fn IbanTab() -> impl IntoView {
-    let mut countries: Vec<&str> = iban::supported_countries();
-    countries.sort_by_key(|c| country_name(c));
-    let country = RwSignal::new("DE".to_string());
-    let count = RwSignal::new(5u32);
-    let spaces = RwSignal::new(true);
-    let results: RwSignal<Vec<IbanRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
-
-    let countries_list: Vec<(String, String)> = countries
-        .into_iter()
-        .map(|c| (c.to_string(), country_name(c).to_string()))
+fn DriverLicenseTab() -> impl IntoView {
+    let link = Permalink::for_tab("driver_license");
+    let registry = driver_license::Registry::new();
+    let countries: Vec<(String, String, String)> = registry
+        .list_countries()
+        .iter()
+        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
         .collect();
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| {
+        countries
+            .first()
+            .map(|(c, _, _)| c.clone())
+            .unwrap_or_default()
+    }));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(DriverLicenseRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let state_input = RwSignal::new(link.get("state").unwrap_or_default());
+    let results: RwSignal<Vec<DriverLicenseRow>> = RwSignal::new(Vec::new());
+
+    let registry = StoredValue::new(registry);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
         let c = country.get();
         let n = count.get();
-        let c_opt = if c == "Random" {
-            None
-        } else {
-            Some(c.as_str())
-        };
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        for _ in 0..n {
-            if let Ok(code) = iban::generate_iban(c_opt, &mut rng) {
-                let valid = iban::validate_iban(&code);
-                rows.push(IbanRow {
-                    formatted: iban::format_iban(&code),
-                    raw: code.clone(),
-                    valid,
-                });
-                history_results.push(code);
-            }
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                state: state_input.get(),
+                defects: defects.get(),
+                ..bulk.job("driver_license", seed_value, n)
+            });
+            return;
         }
+        let s = state_input.get();
+        let state = if s.is_empty() { None } else { Some(s.as_str()) };
+        let rows =
+            registry.with_value(|reg| generate::driver_licenses(reg, &c, state, n, &mut rng));
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
-        copied_idx.set(None);
-        add_to_history("IBAN", &c, n, history_results);
+        used_seed.set(seed_value);
+        add_to_history("Driver's License", &c, n, seed_value, history_results);
     };
 
     let copy_all = move |_| {
         let rows = results.get();
-        let use_spaces = spaces.get();
         let text: String = rows
             .iter()
-            .map(|r| {
-                if use_spaces {
-                    r.formatted.as_str()
-                } else {
-                    r.raw.as_str()
-                }
-            })
+            .map(|r| r.code.as_str())
             .collect::<Vec<_>>()
             .join("\n");
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let use_spaces = spaces.get();
-        let mut csv = String::from("IBAN,Valid\n");
-        for row in rows.iter() {
-            let display = if use_spaces { &row.formatted } else { &row.raw };
-            csv.push_str(&format!(
-                "{},{}\n",
-                display,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_file("ibans.csv", &csv, "text/csv;charset=utf-8;");
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("ibans.json", &json, "application/json;charset=utf-8;");
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql =
-            String::from("CREATE TABLE IF NOT EXISTS ibans (iban TEXT, valid BOOLEAN);\n");
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO ibans (iban, valid) VALUES ('{}', {});\n",
-                row.raw, row.valid
-            ));
-        }
-        download_file("ibans.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> =
+        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "driver_license",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("state", state_input.get()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Code", |r: &DriverLicenseRow| r.code.clone()),
+        Column::new("Name", |r: &DriverLicenseRow| r.name.clone()),
+        Column::new("State", |r: &DriverLicenseRow| {
+            r.state.clone().unwrap_or_default()
+        }),
+        valid_column(|r: &DriverLicenseRow| r.valid),
+    ];
 
     view! {
         <div class="controls">
             <div class="field">
                 <label>"Country"</label>
                 <SearchableSelect
-                    options=countries_list
+                    options=countries_for_select
                     selected=country
                     on_change=Callback::new(|_| ())
                 />
             </div>
 
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
+                <label>"State (optional)"</label>
+                <input type="text" placeholder="e.g. CA"
+                    prop:value=move || state_input.get()
+                    on:input=move |ev| state_input.set(event_target_value(&ev))
                 />
             </div>
 
-            <div class="checkbox-field">
-                <input type="checkbox" id="spaces"
-                    prop:checked=move || spaces.get()
-                    on:change=move |_| spaces.update(|s| *s = !*s)
-                />
-                <label for="spaces">"Spaces"</label>
-            </div>
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="driver_license" />
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=DriverLicenseRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
-
-        <Show when=move || results.get().is_empty()>
-            <div class="empty">"Select a country and click Generate"</div>
-        </Show>
-
-        <Show when=move || !results.get().is_empty()>
-            <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
-            </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"IBAN"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let use_spaces = spaces.get();
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let display = if use_spaces { row.formatted.clone() } else { row.raw.clone() };
-                            let copy_text = display.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{display}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
+
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Select a country and click Generate"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
         </Show>
     }
 }
 
 #[component]
-fn PersonalIdTab() -> impl IntoView {
-    let registry = personal_id::Registry::new();
-    let id_countries: Vec<(String, String, String)> = registry
+fn PassportTab() -> impl IntoView {
+    let link = Permalink::for_tab("passport");
+    let registry = passport::Registry::new();
+    let countries: Vec<(String, String, String)> = registry
         .list_countries()
         .iter()
         .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
         .collect();
 
-    let country = RwSignal::new("EE".to_string());
-    let count = RwSignal::new(5u32);
-    let gender = RwSignal::new("any".to_string());
-    let year = RwSignal::new(String::new());
-    let results: RwSignal<Vec<IdRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| {
+        countries
+            .first()
+            .map(|(c, _, _)| c.clone())
+            .unwrap_or_default()
+    }));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(PassportRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<PassportRow>> = RwSignal::new(Vec::new());
 
     let registry = StoredValue::new(registry);
-    let id_countries_stored = StoredValue::new(id_countries.clone());
-
-    let current_description = Memo::new(move |_| {
-        let c = country.get();
-        id_countries_stored.with_value(|list| {
-            list.iter()
-                .find(|(code, _, _)| code == &c)
-                .map(|(_, _, d)| d.clone())
-                .unwrap_or_default()
-        })
-    });
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
         let c = country.get();
         let n = count.get();
-        let g = gender.get();
-        let y: Option<u16> = year.get().parse().ok();
-        let gender_opt = match g.as_str() {
-            "male" => Some(personal_id::date::Gender::Male),
-            "female" => Some(personal_id::date::Gender::Female),
-            _ => None,
-        };
-        let opts = personal_id::GenOptions {
-            gender: gender_opt,
-            year: y,
-        };
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                if let Some(code) = reg.generate(&c, &opts, &mut rng)
-                    && let Some(parsed) = reg.parse(&c, &code)
-                {
-                    rows.push(IdRow {
-                        code: parsed.code.clone(),
-                        gender: parsed.gender.unwrap_or_default(),
-                        dob: parsed.dob.unwrap_or_default(),
-                        valid: parsed.valid,
-                    });
-                    history_results.push(parsed.code);
-                }
-            }
-        });
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("passport", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::passports(reg, &c, n, &mut rng));
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
-        copied_idx.set(None);
-        add_to_history("Personal ID", &c, n, history_results);
+        used_seed.set(seed_value);
+        add_to_history("Passport", &c, n, seed_value, history_results);
     };
 
     let copy_all = move |_| {
@@ -754,58 +2726,40 @@ fn PersonalIdTab() -> impl IntoView {
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Gender,Date of Birth,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{}\n",
-                row.code,
-                row.gender,
-                row.dob,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_file("personal_ids.csv", &csv, "text/csv;charset=utf-8;");
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file(
-            "personal_ids.json",
-            &json,
-            "application/json;charset=utf-8;",
-        );
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS personal_ids (code TEXT, gender TEXT, dob TEXT, valid BOOLEAN);\n",
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> =
+        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "passport",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO personal_ids (code, gender, dob, valid) VALUES ('{}', '{}', '{}', {});\n",
-                row.code, row.gender, row.dob, row.valid
-            ));
-        }
-        download_file("personal_ids.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    });
+    if link.restores_results() {
+        generate();
+    }
 
-    let countries_for_select: Vec<(String, String)> = id_countries
-        .clone()
-        .into_iter()
-        .map(|(c, n, _)| (c, n))
-        .collect();
+    let columns = vec![
+        Column::new("Code", |r: &PassportRow| r.code.clone()),
+        Column::new("Name", |r: &PassportRow| r.name.clone()),
+        valid_column(|r: &PassportRow| r.valid),
+    ];
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>
-                    "Country "
-                    <Tooltip text=current_description.get() />
-                </label>
+                <label>"Country"</label>
                 <SearchableSelect
                     options=countries_for_select
                     selected=country
@@ -813,201 +2767,129 @@ fn PersonalIdTab() -> impl IntoView {
                 />
             </div>
 
-            <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
-                />
-            </div>
+            <CountField count=count bulk=bulk />
 
-            <div class="field">
-                <label>"Gender"</label>
-                <select on:change=move |ev| {
-                    gender.set(event_target_value(&ev));
-                }>
-                    <option value="any">"Any"</option>
-                    <option value="male">"Male"</option>
-                    <option value="female">"Female"</option>
-                </select>
-            </div>
+            <DefectSelect defects=defects kind="passport" />
 
-            <div class="field">
-                <label>"Year"</label>
-                <input type="text" placeholder="any"
-                    prop:value=move || year.get()
-                    on:input=move |ev| {
-                        year.set(event_target_value(&ev));
-                    }
-                />
-            </div>
+            <SeedField seed=seed />
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=PassportRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
             <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Code"</th>
-                        <th>"Gender"</th>
-                        <th>"Date of Birth"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_code = code.clone();
-                            let gender_str = row.gender.clone();
-                            let dob = row.dob.clone();
-                            let valid = row.valid;
-                            let valid_class = if valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{code}</td>
-                                    <td class="gender">{gender_str}</td>
-                                    <td class="dob">{dob}</td>
-                                    <td class={valid_class}>{if valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_code);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
+            <ResultsTable rows=results columns=columns.clone() />
         </Show>
     }
 }
 
 #[component]
-fn BankAccountTab() -> impl IntoView {
-    let registry = bank_account::Registry::new();
-    let countries: Vec<(String, String, String, bool)> = registry
+fn TaxIdTab() -> impl IntoView {
+    let link = Permalink::for_tab("tax_id");
+    let registry = tax_id::Registry::new();
+    let countries: Vec<(String, String, String)> = registry
         .list_countries()
         .iter()
-        .map(|(c, n, d, i)| (c.to_string(), n.to_string(), d.to_string(), *i))
+        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
         .collect();
 
-    let country = RwSignal::new("US".to_string());
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<BankAccountRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| {
+        countries
+            .first()
+            .map(|(c, _, _)| c.clone())
+            .unwrap_or_default()
+    }));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(TaxIdRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<TaxIdRow>> = RwSignal::new(Vec::new());
 
     let registry = StoredValue::new(registry);
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
         let c = country.get();
         let n = count.get();
-        let mut rows = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = bank_account::GenOptions::default();
-                if let Some(res) = reg.generate(&c, &opts, &mut rng) {
-                    rows.push(BankAccountRow {
-                        account: res.account_number,
-                        routing: res.bank_code.unwrap_or_default(),
-                        valid: res.valid,
-                    });
-                }
-            }
-        });
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("tax_id", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::tax_ids(reg, &c, None, n, &mut rng));
+        let rows = near_miss(rows, &c, &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
-        copied_idx.set(None);
+        used_seed.set(seed_value);
+        add_to_history("Tax ID", &c, n, seed_value, history_results);
     };
 
     let copy_all = move |_| {
         let rows = results.get();
         let text: String = rows
             .iter()
-            .map(|r| {
-                if r.routing.is_empty() {
-                    r.account.clone()
-                } else {
-                    format!("{} ({})", r.account, r.routing)
-                }
-            })
+            .map(|r| r.code.as_str())
             .collect::<Vec<_>>()
             .join("\n");
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Account,Routing,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{}\n",
-                row.account,
-                row.routing,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("bank_accounts.csv", &csv);
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file(
-            "bank_accounts.json",
-            &json,
-            "application/json;charset=utf-8;",
-        );
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS bank_accounts (account TEXT, routing TEXT, valid BOOLEAN);\n",
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select: Vec<(String, String)> =
+        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "tax_id",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO bank_accounts (account, routing, valid) VALUES ('{}', '{}', {});\n",
-                row.account, row.routing, row.valid
-            ));
-        }
-        download_file("bank_accounts.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    });
+    if link.restores_results() {
+        generate();
+    }
 
-    let countries_for_select: Vec<(String, String)> = countries
-        .clone()
-        .into_iter()
-        .map(|(c, n, _, _)| (c, n))
-        .collect();
+    let columns = vec![
+        Column::new("Code", |r: &TaxIdRow| r.code.clone()),
+        Column::new("Name", |r: &TaxIdRow| r.name.clone()),
+        Column::new("Type", |r: &TaxIdRow| {
+            r.holder_type.clone().unwrap_or_default()
+        }),
+        valid_column(|r: &TaxIdRow| r.valid),
+    ];
 
     view! {
         <div class="controls">
@@ -1020,301 +2902,215 @@ fn BankAccountTab() -> impl IntoView {
                 />
             </div>
 
-            <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
-                />
-            </div>
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="tax_id" />
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=TaxIdRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
             <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Account"</th>
-                        <th>"Routing"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let account = row.account.clone();
-                            let routing = row.routing.clone();
-                            let copy_text = if routing.is_empty() { account.clone() } else { format!("{} ({})", account, routing) };
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{account}</td>
-                                    <td>{routing}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
+            <ResultsTable rows=results columns=columns.clone() />
         </Show>
     }
 }
 
 #[component]
-fn CreditCardTab() -> impl IntoView {
-    let registry = credit_card::Registry::new();
-    let brands: Vec<String> = registry
-        .list_brands()
+fn VatTab() -> impl IntoView {
+    let link = Permalink::for_tab("vat");
+    let registry = vat::Registry::new();
+    let countries: Vec<(String, String)> = registry
+        .list_countries()
         .iter()
-        .map(|b| b.to_string())
+        .map(|(c, n)| (c.to_string(), n.to_string()))
         .collect();
 
-    let brand = RwSignal::new("visa".to_string());
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<CreditCardRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| {
+        countries
+            .first()
+            .map(|(c, _)| c.clone())
+            .unwrap_or_default()
+    }));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(VatRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<VatRow>> = RwSignal::new(Vec::new());
 
     let registry = StoredValue::new(registry);
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let b = brand.get();
-        let n = count.get();
-        let mut rows = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let year = (js_sys::Date::new_0().get_full_year() % 100) as u16;
-                let opts = credit_card::GenOptions {
-                    brand: Some(b.clone()),
-                    current_year: Some(year),
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    rows.push(CreditCardRow {
-                        number: res.number,
-                        brand: res.brand,
-                        valid: res.valid,
-                    });
-                }
-            }
-        });
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("vat", seed_value, n)
+            });
+            return;
+        }
+        let rows = registry.with_value(|reg| generate::vat_numbers(reg, &c, n, &mut rng));
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
-        copied_idx.set(None);
+        used_seed.set(seed_value);
+        add_to_history("VAT", &c, n, seed_value, history_results);
     };
 
     let copy_all = move |_| {
         let rows = results.get();
         let text: String = rows
             .iter()
-            .map(|r| r.number.as_str())
+            .map(|r| r.code.as_str())
             .collect::<Vec<_>>()
             .join("\n");
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Number,Brand,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{}\n",
-                row.number,
-                row.brand,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("credit_cards.csv", &csv);
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file(
-            "credit_cards.json",
-            &json,
-            "application/json;charset=utf-8;",
-        );
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS credit_cards (number TEXT, brand TEXT, valid BOOLEAN);\n",
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    let countries_for_select = countries.clone();
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "vat",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO credit_cards (number, brand, valid) VALUES ('{}', '{}', {});\n",
-                row.number, row.brand, row.valid
-            ));
-        }
-        download_file("credit_cards.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    });
+    if link.restores_results() {
+        generate();
+    }
 
-    let brands_for_select = brands.clone();
+    let columns = vec![
+        Column::new("Code", |r: &VatRow| r.code.clone()),
+        Column::new("Country", |r: &VatRow| {
+            format!("{} \u{2014} {}", r.country_code, r.country_name)
+        }),
+        valid_column(|r: &VatRow| r.valid),
+    ];
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Brand"</label>
-                <select on:change=move |ev| {
-                    brand.set(event_target_value(&ev));
-                }>
-                    {brands_for_select.into_iter().map(|id| {
-                        let id2 = id.clone();
-                        let label = id.clone();
-                        view! {
-                            <option value={id} selected=move || brand.get() == id2>
-                                {label}
-                            </option>
-                        }
-                    }).collect_view()}
-                </select>
-            </div>
-
-            <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
+                <label>"Country"</label>
+                <SearchableSelect
+                    options=countries_for_select
+                    selected=country
+                    on_change=Callback::new(|_| ())
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="vat" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=VatRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
-            <div class="empty">"Select a brand and click Generate"</div>
+            <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Number"</th>
-                        <th>"Brand"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let number = row.number.clone();
-                            let copy_text = number.clone();
-                            let brand = row.brand.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{number}</td>
-                                    <td>{brand}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
+            <ResultsTable rows=results columns=columns.clone() />
         </Show>
     }
 }
 
 #[component]
-fn SwiftTab() -> impl IntoView {
-    let registry = swift::Registry::new();
-    let countries: Vec<String> = iban::supported_countries()
-        .into_iter()
-        .map(|c| c.to_string())
-        .collect();
+fn LeiTab() -> impl IntoView {
+    let link = Permalink::for_tab("lei");
+    let registry = lei::Registry::new();
 
-    let country = RwSignal::new("DE".to_string());
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<SwiftRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(LeiRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let country = RwSignal::new(link.get("country").unwrap_or_default());
+    let results: RwSignal<Vec<LeiRow>> = RwSignal::new(Vec::new());
 
     let registry = StoredValue::new(registry);
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let c = country.get();
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
         let n = count.get();
-        let mut rows = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = swift::GenOptions {
-                    country: Some(c.clone()),
-                };
-                let res = reg.generate(&opts, &mut rng);
-                rows.push(SwiftRow {
-                    code: res.code,
-                    bank: res.bank,
-                    country: res.country,
-                    location: res.location,
-                    valid: res.valid,
-                });
-            }
-        });
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: country.get(),
+                defects: defects.get(),
+                ..bulk.job("lei", seed_value, n)
+            });
+            return;
+        }
+        let c = country.get();
+        let c_opt = if c.is_empty() { None } else { Some(c.as_str()) };
+        let rows = registry.with_value(|reg| generate::leis(reg, c_opt, n, &mut rng));
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
-        copied_idx.set(None);
+        used_seed.set(seed_value);
+        add_to_history(
+            "LEI",
+            if c.is_empty() { "Random" } else { &c },
+            n,
+            seed_value,
+            history_results,
+        );
     };
 
     let copy_all = move |_| {
@@ -1327,52 +3123,152 @@ fn SwiftTab() -> impl IntoView {
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("SWIFT/BIC,Bank,Country,Location,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{},{}\n",
-                row.code,
-                row.bank,
-                row.country,
-                row.location,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("swift_codes.csv", &csv);
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("swift_codes.json", &json, "application/json;charset=utf-8;");
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS swift_codes (code TEXT, bank TEXT, country TEXT, location TEXT, valid BOOLEAN);\n",
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "lei",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO swift_codes (code, bank, country, location, valid) VALUES ('{}', '{}', '{}', '{}', {});\n",
-                row.code, row.bank, row.country, row.location, row.valid
-            ));
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let columns = vec![
+        Column::new("Code", |r: &LeiRow| r.code.clone()),
+        Column::new("LOU", |r: &LeiRow| r.lou.clone()),
+        Column::new("Country", |r: &LeiRow| r.country_code.clone()),
+        valid_column(|r: &LeiRow| r.valid),
+    ];
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Country (optional)"</label>
+                <input type="text" placeholder="e.g. US (leave empty for random)"
+                    prop:value=move || country.get()
+                    on:input=move |ev| country.set(event_target_value(&ev))
+                />
+            </div>
+
+            <CountField count=count bulk=bulk />
+
+            <DefectSelect defects=defects kind="lei" />
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !results.get().is_empty()>
+                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=LeiRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
+            </Show>
+        </div>
+
+        <BulkProgress bulk=bulk />
+
+        <Show when=move || results.get().is_empty()>
+            <div class="empty">"Click Generate to create LEI codes"</div>
+        </Show>
+
+        <Show when=move || !results.get().is_empty()>
+            <div class="results-header">
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
+            </div>
+            <ResultsTable rows=results columns=columns.clone() />
+        </Show>
+    }
+}
+
+#[component]
+fn PersonTab() -> impl IntoView {
+    let link = Permalink::for_tab("person");
+    let generator = PersonGenerator::new();
+    let countries_for_select = generator.countries();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "EE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(PersonRow::TABLE);
+    let gender = RwSignal::new(link.get("gender").unwrap_or_else(|| "any".to_string()));
+    let year = RwSignal::new(link.get("year").unwrap_or_default());
+    let results: RwSignal<Vec<PersonRow>> = RwSignal::new(Vec::new());
+    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+
+    let generator = StoredValue::new(generator);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                gender: gender.get(),
+                year: year.get().parse().ok(),
+                ..bulk.job("person", seed_value, n)
+            });
+            return;
         }
-        download_file("swift_codes.sql", &sql, "text/plain;charset=utf-8;");
+        let g = gender.get();
+        let y: Option<u16> = year.get().parse().ok();
+        let rows = generator.with_value(|generator| generator.generate(&c, &g, y, n, &mut rng));
+        let history_results = rows.iter().map(|r| r.personal_id.clone()).collect();
+        results.set(rows);
+        used_seed.set(seed_value);
+        copied_idx.set(None);
+        add_to_history("Person", &c, n, seed_value, history_results);
     };
 
-    let countries_for_select: Vec<(String, String)> = countries
-        .clone()
-        .into_iter()
-        .map(|code| (code.clone(), country_name(&code).to_string()))
-        .collect();
+    let copy_all = move |_| copy_to_clipboard(&export::to_json(&results.get()));
+
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
+
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "person",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("gender", gender.get().replace("any", "")),
+                ("year", year.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
+                <label>
+                    "Country "
+                    <Tooltip text="Countries with a personal ID format; documents the country has no format for are left empty".to_string() />
+                </label>
                 <SearchableSelect
                     options=countries_for_select
                     selected=country
@@ -1380,43 +3276,66 @@ fn SwiftTab() -> impl IntoView {
                 />
             </div>
 
+            <CountField count=count bulk=bulk />
+
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
+                <label>"Gender"</label>
+                <select on:change=move |ev| {
+                    gender.set(event_target_value(&ev));
+                }>
+                    <option value="any" selected=move || gender.get() == "any">"Any"</option>
+                    <option value="male" selected=move || gender.get() == "male">"Male"</option>
+                    <option value="female" selected=move || gender.get() == "female">"Female"</option>
+                </select>
+            </div>
+
+            <div class="field">
+                <label>"Year"</label>
+                <input type="text" placeholder="any"
+                    prop:value=move || year.get()
                     on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
+                        year.set(event_target_value(&ev));
                     }
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=PersonRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
             <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
             <table>
                 <thead>
                     <tr>
-                        <th>"SWIFT/BIC"</th>
-                        <th>"Bank"</th>
-                        <th>"Country"</th>
-                        <th>"Location"</th>
+                        <th>"Gender"</th>
+                        <th>"Date of Birth"</th>
+                        <th>"Personal ID"</th>
+                        <th>"Passport"</th>
+                        <th>"Driver's License"</th>
+                        <th>"Tax ID"</th>
+                        <th>"IBAN"</th>
+                        <th>"Bank Account"</th>
                         <th>"Valid"</th>
                         <th></th>
                     </tr>
@@ -1425,25 +3344,31 @@ fn SwiftTab() -> impl IntoView {
                     {move || {
                         let cidx = copied_idx.get();
                         results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let bank = row.bank.clone();
-                            let country = row.country.clone();
-                            let location = row.location.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
+                            let copy_record = serde_json::to_string(row).unwrap_or_default();
+                            let account = if row.routing.is_empty() {
+                                row.bank_account.clone()
+                            } else {
+                                format!("{} ({})", row.bank_account, row.routing)
+                            };
+                            let valid = row.valid;
+                            let valid_class = if valid { "valid-yes" } else { "valid-no" };
                             let is_copied = cidx == Some(i);
                             view! {
                                 <tr>
-                                    <td>{code}</td>
-                                    <td>{bank}</td>
-                                    <td>{country}</td>
-                                    <td>{location}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
+                                    <td class="gender">{row.gender.clone()}</td>
+                                    <td class="dob">{row.dob.clone()}</td>
+                                    <td>{row.personal_id.clone()}</td>
+                                    <td>{row.passport.clone()}</td>
+                                    <td>{row.driver_license.clone()}</td>
+                                    <td>{row.tax_id.clone()}</td>
+                                    <td>{row.iban.clone()}</td>
+                                    <td>{account}</td>
+                                    <td class={valid_class}>{if valid { "Yes" } else { "No" }}</td>
                                     <td>
                                         <button
                                             class=if is_copied { "btn-copy copied" } else { "btn-copy" }
                                             on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
+                                                copy_to_clipboard(&copy_record);
                                                 copied_idx.set(Some(i));
                                             }
                                         >
@@ -1461,98 +3386,70 @@ fn SwiftTab() -> impl IntoView {
 }
 
 #[component]
-fn CompanyIdTab() -> impl IntoView {
-    let registry = company_id::Registry::new();
-    let countries: Vec<(String, String, String)> = registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
-        .collect();
-
-    let country = RwSignal::new("EE".to_string());
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<CompanyIdRow>> = RwSignal::new(Vec::new());
+fn CompanyTab() -> impl IntoView {
+    let link = Permalink::for_tab("entity");
+    let generator = CompanyGenerator::new();
+    let countries_for_select = generator.countries();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "EE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(CompanyRow::TABLE);
+    let results: RwSignal<Vec<CompanyRow>> = RwSignal::new(Vec::new());
     let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
 
-    let registry = StoredValue::new(registry);
+    let generator = StoredValue::new(generator);
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
         let c = country.get();
         let n = count.get();
-        let mut rows = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = company_id::GenOptions {
-                    country: Some(c.clone()),
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    rows.push(CompanyIdRow {
-                        code: res.code,
-                        name: res.name,
-                        valid: res.valid,
-                    });
-                }
-            }
-        });
-        results.set(rows);
-        copied_idx.set(None);
-    };
-
-    let copy_all = move |_| {
-        let rows = results.get();
-        let text: String = rows
-            .iter()
-            .map(|r| r.code.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        copy_to_clipboard(&text);
-    };
-
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Name,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{}\n",
-                row.code,
-                row.name,
-                if row.valid { "Yes" } else { "No" }
-            ));
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                ..bulk.job("entity", seed_value, n)
+            });
+            return;
         }
-        download_csv("company_ids.csv", &csv);
+        let rows = generator.with_value(|generator| generator.generate(&c, n, &mut rng));
+        let history_results = rows.iter().map(|r| r.company_id.clone()).collect();
+        results.set(rows);
+        used_seed.set(seed_value);
+        copied_idx.set(None);
+        add_to_history("Company", &c, n, seed_value, history_results);
     };
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("company_ids.json", &json, "application/json;charset=utf-8;");
-    };
+    let copy_all = move |_| copy_to_clipboard(&export::to_json(&results.get()));
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS company_ids (code TEXT, name TEXT, valid BOOLEAN);\n",
-        );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO company_ids (code, name, valid) VALUES ('{}', '{}', {});\n",
-                row.code, row.name, row.valid
-            ));
-        }
-        download_file("company_ids.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let countries_for_select: Vec<(String, String)> = countries
-        .clone()
-        .into_iter()
-        .map(|(c, n, _)| (c, n))
-        .collect();
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
+
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "entity",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
+                <label>
+                    "Country "
+                    <Tooltip text="Countries with a company registration format; the VAT number is derived from it where the country allows".to_string() />
+                </label>
                 <SearchableSelect
                     options=countries_for_select
                     selected=country
@@ -1560,41 +3457,42 @@ fn CompanyIdTab() -> impl IntoView {
                 />
             </div>
 
-            <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
-                />
-            </div>
+            <CountField count=count bulk=bulk />
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=CompanyRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
             <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
             <table>
                 <thead>
                     <tr>
-                        <th>"Code"</th>
-                        <th>"Name"</th>
+                        <th>"Company ID"</th>
+                        <th>"VAT"</th>
+                        <th>"LEI"</th>
+                        <th>"BIC"</th>
+                        <th>"IBAN"</th>
                         <th>"Valid"</th>
                         <th></th>
                     </tr>
@@ -1603,21 +3501,28 @@ fn CompanyIdTab() -> impl IntoView {
                     {move || {
                         let cidx = copied_idx.get();
                         results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let name = row.name.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
+                            let copy_record = serde_json::to_string(row).unwrap_or_default();
+                            let vat = if row.vat_derived {
+                                format!("{} (derived)", row.vat)
+                            } else {
+                                row.vat.clone()
+                            };
+                            let valid = row.valid;
+                            let valid_class = if valid { "valid-yes" } else { "valid-no" };
                             let is_copied = cidx == Some(i);
                             view! {
                                 <tr>
-                                    <td>{code}</td>
-                                    <td>{name}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
+                                    <td>{row.company_id.clone()}</td>
+                                    <td>{vat}</td>
+                                    <td>{row.lei.clone()}</td>
+                                    <td>{row.bic.clone()}</td>
+                                    <td>{row.iban.clone()}</td>
+                                    <td class={valid_class}>{if valid { "Yes" } else { "No" }}</td>
                                     <td>
                                         <button
                                             class=if is_copied { "btn-copy copied" } else { "btn-copy" }
                                             on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
+                                                copy_to_clipboard(&copy_record);
                                                 copied_idx.set(Some(i));
                                             }
                                         >
@@ -1634,56 +3539,513 @@ fn CompanyIdTab() -> impl IntoView {
     }
 }
 
-#[component]
-fn DriverLicenseTab() -> impl IntoView {
-    let registry = driver_license::Registry::new();
-    let countries: Vec<(String, String, String)> = registry
-        .list_countries()
+/// SEPA countries by name, after a "Random" entry for any of them.
+fn sepa_countries() -> Vec<(String, String)> {
+    let mut countries: Vec<(String, String)> = sepa::SEPA_COUNTRIES
         .iter()
-        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
+        .map(|c| (c.to_string(), country_name(c).to_string()))
         .collect();
+    countries.sort_by(|a, b| a.1.cmp(&b.1));
+    countries.insert(0, ("Random".to_string(), "Any SEPA country".to_string()));
+    countries
+}
 
-    let country = RwSignal::new(
-        countries
-            .first()
-            .map(|(c, _, _)| c.clone())
-            .unwrap_or_default(),
-    );
-    let count = RwSignal::new(5u32);
-    let state_input = RwSignal::new(String::new());
-    let results: RwSignal<Vec<DriverLicenseRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+/// Generated file shown under the controls of the payment file tabs.
+#[component]
+fn FilePreview(#[prop(into)] content: Signal<String>, summary: Signal<String>) -> impl IntoView {
+    view! {
+        <Show when=move || content.with(|c| c.is_empty())>
+            <div class="empty">"Set the options and click Generate"</div>
+        </Show>
 
-    let registry = StoredValue::new(registry);
+        <Show when=move || !content.with(|c| c.is_empty())>
+            <div class="results-header">
+                <span>{move || summary.get()}</span>
+            </div>
+            <pre class="file-preview">{move || content.get()}</pre>
+        </Show>
+    }
+}
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let c = country.get();
-        let n = count.get();
-        let s = state_input.get();
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = driver_license::GenOptions {
-                    country: Some(c.clone()),
-                    state: if s.is_empty() { None } else { Some(s.clone()) },
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    history_results.push(res.code.clone());
-                    rows.push(DriverLicenseRow {
-                        code: res.code,
-                        name: res.name,
-                        country: format!("{} — {}", res.country_code, res.country_name),
-                        state: res.state,
-                        valid: res.valid,
-                    });
-                }
+#[component]
+fn SepaTransferTab() -> impl IntoView {
+    let link = Permalink::for_tab("sepa_ct");
+    let number = |key: &str, default: u32, max: u32| {
+        link.get(key)
+            .and_then(|v| v.parse::<u32>().ok())
+            .map_or(default, |v| v.clamp(1, max))
+    };
+    let countries_list = sepa_countries();
+
+    let payment_infos = RwSignal::new(number("payments", 1, 100));
+    let transactions = RwSignal::new(number("transactions", 5, 1000));
+    let currency = RwSignal::new(link.get("currency").unwrap_or_else(|| "EUR".to_string()));
+    let min_amount = RwSignal::new(link.get("min").unwrap_or_else(|| "1.00".to_string()));
+    let max_amount = RwSignal::new(link.get("max").unwrap_or_else(|| "1000.00".to_string()));
+    let date = RwSignal::new(link.get("date").unwrap_or_else(today));
+    let debtor_country = RwSignal::new(link.get("debtor").unwrap_or_else(|| "DE".to_string()));
+    let creditor_country =
+        RwSignal::new(link.get("creditor").unwrap_or_else(|| "Random".to_string()));
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let xml = RwSignal::new(String::new());
+    let error = RwSignal::new(String::new());
+
+    let swift_registry = StoredValue::new(swift::Registry::new());
+
+    let generate = move || {
+        let (Some(min), Some(max)) = (
+            sepa::parse_amount(&min_amount.get()),
+            sepa::parse_amount(&max_amount.get()),
+        ) else {
+            error.set("Amounts must be decimal numbers like 12.50".to_string());
+            return;
+        };
+        let country = |c: String| if c == "Random" { None } else { Some(c) };
+        let opts = sepa::CreditTransferOptions {
+            payment_infos: payment_infos.get(),
+            transactions: transactions.get(),
+            currency: currency.get().trim().to_uppercase(),
+            min_amount: min,
+            max_amount: max,
+            execution_date: date.get(),
+            created_at: String::new(),
+            debtor_country: country(debtor_country.get()),
+            creditor_country: country(creditor_country.get()),
+        };
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        match swift_registry.with_value(|reg| sepa::credit_transfer(reg, &opts, &mut rng)) {
+            Ok(content) => {
+                xml.set(content);
+                used_seed.set(seed_value);
+                error.set(String::new());
             }
-        });
+            Err(e) => error.set(e),
+        }
+    };
+
+    let copy_xml = move |_| copy_to_clipboard(&xml.get());
+
+    let save_xml = move |_| {
+        download_file(
+            &file_name("pain.001", used_seed.get(), "xml"),
+            &xml.get(),
+            "application/xml;charset=utf-8;",
+        )
+    };
+
+    let summary = Signal::derive(move || {
+        format!(
+            "{} payment infos \u{00b7} {} transactions \u{00b7} seed {}",
+            payment_infos.get(),
+            payment_infos.get() * transactions.get(),
+            used_seed.get()
+        )
+    });
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "sepa_ct",
+            &[
+                ("payments", payment_infos.get().to_string()),
+                ("transactions", transactions.get().to_string()),
+                ("currency", currency.get()),
+                ("min", min_amount.get()),
+                ("max", max_amount.get()),
+                ("date", date.get()),
+                ("debtor", debtor_country.get()),
+                ("creditor", creditor_country.get()),
+                (
+                    "seed",
+                    if xml.with(|x| x.is_empty()) {
+                        String::new()
+                    } else {
+                        used_seed.get().to_string()
+                    },
+                ),
+            ],
+        );
+    });
+    if link.get("seed").is_some() {
+        generate();
+    }
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>"Debtor country"</label>
+                <SearchableSelect
+                    options=countries_list.clone()
+                    selected=debtor_country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <div class="field">
+                <label>"Creditor country"</label>
+                <SearchableSelect
+                    options=countries_list
+                    selected=creditor_country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <div class="field">
+                <label>
+                    "Payment infos "
+                    <Tooltip text="One PmtInf block per debtor account".to_string() />
+                </label>
+                <input type="number" min="1" max="100"
+                    prop:value=move || payment_infos.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            payment_infos.set(v.clamp(1, 100));
+                        }
+                    }
+                />
+            </div>
+
+            <div class="field">
+                <label>"Transactions each"</label>
+                <input type="number" min="1" max="1000"
+                    prop:value=move || transactions.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            transactions.set(v.clamp(1, 1000));
+                        }
+                    }
+                />
+            </div>
+
+            <div class="field">
+                <label>"Currency"</label>
+                <input type="text" maxlength="3"
+                    prop:value=move || currency.get()
+                    on:input=move |ev| currency.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Min amount"</label>
+                <input type="text"
+                    prop:value=move || min_amount.get()
+                    on:input=move |ev| min_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Max amount"</label>
+                <input type="text"
+                    prop:value=move || max_amount.get()
+                    on:input=move |ev| max_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Execution date"</label>
+                <input type="date"
+                    prop:value=move || date.get()
+                    on:input=move |ev| date.set(event_target_value(&ev))
+                />
+            </div>
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !xml.with(|x| x.is_empty())>
+                <button class="btn btn-secondary" on:click=copy_xml>"Copy XML"</button>
+                <button class="btn btn-secondary" on:click=save_xml>"Download XML"</button>
+            </Show>
+        </div>
+
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
+            </div>
+        </Show>
+
+        <FilePreview content=xml summary=summary />
+    }
+}
+
+#[component]
+fn SepaDebitTab() -> impl IntoView {
+    let link = Permalink::for_tab("sepa_dd");
+    let number = |key: &str, default: u32, max: u32| {
+        link.get(key)
+            .and_then(|v| v.parse::<u32>().ok())
+            .map_or(default, |v| v.clamp(1, max))
+    };
+    let countries_list = sepa_countries();
+
+    let payment_infos = RwSignal::new(number("payments", 1, 100));
+    let transactions = RwSignal::new(number("transactions", 5, 1000));
+    let currency = RwSignal::new(link.get("currency").unwrap_or_else(|| "EUR".to_string()));
+    let min_amount = RwSignal::new(link.get("min").unwrap_or_else(|| "1.00".to_string()));
+    let max_amount = RwSignal::new(link.get("max").unwrap_or_else(|| "1000.00".to_string()));
+    let date = RwSignal::new(link.get("date").unwrap_or_else(today));
+    let scheme = RwSignal::new(link.get("scheme").unwrap_or_else(|| "CORE".to_string()));
+    let sequence = RwSignal::new(link.get("sequence").unwrap_or_else(|| "mixed".to_string()));
+    let creditor_country = RwSignal::new(link.get("creditor").unwrap_or_else(|| "DE".to_string()));
+    let debtor_country = RwSignal::new(link.get("debtor").unwrap_or_else(|| "Random".to_string()));
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let xml = RwSignal::new(String::new());
+    let error = RwSignal::new(String::new());
+
+    let swift_registry = StoredValue::new(swift::Registry::new());
+
+    let generate = move || {
+        let (Some(min), Some(max)) = (
+            sepa::parse_amount(&min_amount.get()),
+            sepa::parse_amount(&max_amount.get()),
+        ) else {
+            error.set("Amounts must be decimal numbers like 12.50".to_string());
+            return;
+        };
+        let country = |c: String| if c == "Random" { None } else { Some(c) };
+        let s = sequence.get();
+        let opts = sepa::DirectDebitOptions {
+            payment_infos: payment_infos.get(),
+            transactions: transactions.get(),
+            currency: currency.get().trim().to_uppercase(),
+            min_amount: min,
+            max_amount: max,
+            collection_date: date.get(),
+            created_at: String::new(),
+            scheme: scheme.get(),
+            sequence_type: if s == "mixed" { None } else { Some(s) },
+            creditor_country: country(creditor_country.get()),
+            debtor_country: country(debtor_country.get()),
+        };
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        match swift_registry.with_value(|reg| sepa::direct_debit(reg, &opts, &mut rng)) {
+            Ok(content) => {
+                xml.set(content);
+                used_seed.set(seed_value);
+                error.set(String::new());
+            }
+            Err(e) => error.set(e),
+        }
+    };
+
+    let copy_xml = move |_| copy_to_clipboard(&xml.get());
+
+    let save_xml = move |_| {
+        download_file(
+            &file_name("pain.008", used_seed.get(), "xml"),
+            &xml.get(),
+            "application/xml;charset=utf-8;",
+        )
+    };
+
+    let summary = Signal::derive(move || {
+        format!(
+            "{} payment infos \u{00b7} {} transactions \u{00b7} seed {}",
+            payment_infos.get(),
+            payment_infos.get() * transactions.get(),
+            used_seed.get()
+        )
+    });
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "sepa_dd",
+            &[
+                ("payments", payment_infos.get().to_string()),
+                ("transactions", transactions.get().to_string()),
+                ("currency", currency.get()),
+                ("min", min_amount.get()),
+                ("max", max_amount.get()),
+                ("date", date.get()),
+                ("scheme", scheme.get()),
+                ("sequence", sequence.get().replace("mixed", "")),
+                ("creditor", creditor_country.get()),
+                ("debtor", debtor_country.get()),
+                (
+                    "seed",
+                    if xml.with(|x| x.is_empty()) {
+                        String::new()
+                    } else {
+                        used_seed.get().to_string()
+                    },
+                ),
+            ],
+        );
+    });
+    if link.get("seed").is_some() {
+        generate();
+    }
+
+    view! {
+        <div class="controls">
+            <div class="field">
+                <label>
+                    "Creditor country "
+                    <Tooltip text="Each creditor gets a Creditor Identifier from its country".to_string() />
+                </label>
+                <SearchableSelect
+                    options=countries_list.clone()
+                    selected=creditor_country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <div class="field">
+                <label>"Debtor country"</label>
+                <SearchableSelect
+                    options=countries_list
+                    selected=debtor_country
+                    on_change=Callback::new(|_| ())
+                />
+            </div>
+
+            <div class="field">
+                <label>
+                    "Payment infos "
+                    <Tooltip text="One PmtInf block per creditor and sequence type".to_string() />
+                </label>
+                <input type="number" min="1" max="100"
+                    prop:value=move || payment_infos.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            payment_infos.set(v.clamp(1, 100));
+                        }
+                    }
+                />
+            </div>
+
+            <div class="field">
+                <label>"Transactions each"</label>
+                <input type="number" min="1" max="1000"
+                    prop:value=move || transactions.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            transactions.set(v.clamp(1, 1000));
+                        }
+                    }
+                />
+            </div>
+
+            <div class="field">
+                <label>"Sequence type"</label>
+                <select on:change=move |ev| sequence.set(event_target_value(&ev))>
+                    <option value="mixed" selected=move || sequence.get() == "mixed">"Mixed"</option>
+                    {sepa::SEQUENCE_TYPES.iter().map(|(code, label)| {
+                        let code = code.to_string();
+                        let value = code.clone();
+                        view! {
+                            <option value=value selected=move || sequence.get() == code>
+                                {format!("{} ({})", label, code)}
+                            </option>
+                        }
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <div class="field">
+                <label>"Scheme"</label>
+                <select on:change=move |ev| scheme.set(event_target_value(&ev))>
+                    {sepa::SCHEMES.iter().map(|code| view! {
+                        <option value=*code selected=move || scheme.get() == *code>{*code}</option>
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <div class="field">
+                <label>"Currency"</label>
+                <input type="text" maxlength="3"
+                    prop:value=move || currency.get()
+                    on:input=move |ev| currency.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Min amount"</label>
+                <input type="text"
+                    prop:value=move || min_amount.get()
+                    on:input=move |ev| min_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Max amount"</label>
+                <input type="text"
+                    prop:value=move || max_amount.get()
+                    on:input=move |ev| max_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Collection date"</label>
+                <input type="date"
+                    prop:value=move || date.get()
+                    on:input=move |ev| date.set(event_target_value(&ev))
+                />
+            </div>
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !xml.with(|x| x.is_empty())>
+                <button class="btn btn-secondary" on:click=copy_xml>"Copy XML"</button>
+                <button class="btn btn-secondary" on:click=save_xml>"Download XML"</button>
+            </Show>
+        </div>
+
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
+            </div>
+        </Show>
+
+        <FilePreview content=xml summary=summary />
+    }
+}
+
+#[component]
+fn CreditorIdTab() -> impl IntoView {
+    let link = Permalink::for_tab("creditor_id");
+    let countries_list = sepa_countries();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "DE".to_string()));
+    let count = RwSignal::new(link.count());
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let bulk = Bulk::new(CreditorIdRow::TABLE);
+    let defects = RwSignal::new(link.get("defects").unwrap_or_default());
+    let results: RwSignal<Vec<CreditorIdRow>> = RwSignal::new(Vec::new());
+    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let c = country.get();
+        let n = count.get();
+        if n > INLINE_LIMIT {
+            bulk.start(&bulk::Job {
+                country: c.clone(),
+                defects: defects.get(),
+                ..bulk.job("creditor_id", seed_value, n)
+            });
+            return;
+        }
+        let c_opt = if c == "Random" {
+            None
+        } else {
+            Some(c.as_str())
+        };
+        let rows = generate::creditor_ids(c_opt, n, &mut rng);
+        let rows = near_miss(rows, "", &defects.get(), bulk, &mut rng);
+        let history_results = rows.iter().map(|r| r.code.clone()).collect();
         results.set(rows);
+        used_seed.set(seed_value);
         copied_idx.set(None);
-        add_to_history("Driver's License", &c, n, history_results);
+        add_to_history("Creditor ID", &c, n, seed_value, history_results);
     };
 
     let copy_all = move |_| {
@@ -1696,104 +4058,76 @@ fn DriverLicenseTab() -> impl IntoView {
         copy_to_clipboard(&text);
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Name,Country,State,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{},{}\n",
-                row.code,
-                row.name,
-                row.country,
-                row.state.as_deref().unwrap_or(""),
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("driver_licenses.csv", &csv);
-    };
+    let save_csv = move |_| export_csv(&results.get(), used_seed.get());
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file(
-            "driver_licenses.json",
-            &json,
-            "application/json;charset=utf-8;",
-        );
-    };
+    let save_json = move |_| export_json(&results.get(), used_seed.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS driver_licenses (code TEXT, name TEXT, country TEXT, state TEXT, valid BOOLEAN);\n",
-        );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO driver_licenses (code, name, country, state, valid) VALUES ('{}', '{}', '{}', '{}', {});\n",
-                row.code, row.name, row.country, row.state.as_deref().unwrap_or(""), row.valid
-            ));
-        }
-        download_file("driver_licenses.sql", &sql, "text/plain;charset=utf-8;");
-    };
+    let save_sql = move |_| export_sql(&results.get(), used_seed.get());
 
-    let countries_for_select: Vec<(String, String)> =
-        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+    Effect::new(move |_| {
+        Permalink::write(
+            "creditor_id",
+            &[
+                ("country", country.get()),
+                ("count", count.get().to_string()),
+                ("defects", defects.get()),
+                ("seed", link_seed(results, used_seed)),
+            ],
+        );
+    });
+    if link.restores_results() {
+        generate();
+    }
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
+                <label>
+                    "Country "
+                    <Tooltip text="SEPA Creditor Identifier: country, mod-97 check digits, ZZZ business code and national identifier".to_string() />
+                </label>
                 <SearchableSelect
-                    options=countries_for_select
+                    options=countries_list
                     selected=country
                     on_change=Callback::new(|_| ())
                 />
             </div>
 
-            <div class="field">
-                <label>"State (optional)"</label>
-                <input type="text" placeholder="e.g. CA"
-                    prop:value=move || state_input.get()
-                    on:input=move |ev| state_input.set(event_target_value(&ev))
-                />
-            </div>
+            <CountField count=count bulk=bulk />
 
-            <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
-                />
-            </div>
+            <DefectSelect defects=defects kind="creditor_id" />
+
+            <SeedField seed=seed />
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
             <Show when=move || !results.get().is_empty()>
                 <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
                 <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
                 <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table=CreditorIdRow::TABLE />
+                <MoreExports rows=results seed=used_seed />
+                <CodeExports rows=results seed=used_seed />
             </Show>
         </div>
 
+        <BulkProgress bulk=bulk />
+
         <Show when=move || results.get().is_empty()>
             <div class="empty">"Select a country and click Generate"</div>
         </Show>
 
         <Show when=move || !results.get().is_empty()>
             <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+                <span>{move || format!("{} results \u{00b7} seed {}", results.get().len(), used_seed.get())}</span>
             </div>
             <table>
                 <thead>
                     <tr>
-                        <th>"Code"</th>
-                        <th>"Name"</th>
-                        <th>"State"</th>
+                        <th>"Creditor ID"</th>
+                        <th>"Country"</th>
                         <th>"Valid"</th>
                         <th></th>
                     </tr>
@@ -1804,16 +4138,14 @@ fn DriverLicenseTab() -> impl IntoView {
                         results.get().iter().enumerate().map(|(i, row)| {
                             let code = row.code.clone();
                             let copy_text = code.clone();
-                            let name = row.name.clone();
-                            let state = row.state.clone().unwrap_or_default();
+                            let country_code = row.country.clone();
                             let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
                             let is_copied = cidx == Some(i);
                             view! {
                                 <tr>
                                     <td>{code}</td>
-                                    <td>{name}</td>
-                                    <td>{state}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
+                                    <td>{country_code}</td>
+                                    <td class={valid_class}>{valid_label(row.valid, row.defect)}</td>
                                     <td>
                                         <button
                                             class=if is_copied { "btn-copy copied" } else { "btn-copy" }
@@ -1836,724 +4168,1044 @@ fn DriverLicenseTab() -> impl IntoView {
 }
 
 #[component]
-fn PassportTab() -> impl IntoView {
-    let registry = passport::Registry::new();
-    let countries: Vec<(String, String, String)> = registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
-        .collect();
-
-    let country = RwSignal::new(
-        countries
-            .first()
-            .map(|(c, _, _)| c.clone())
-            .unwrap_or_default(),
-    );
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<PassportRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
-
-    let registry = StoredValue::new(registry);
-
-    let generate = move |_| {
-        let mut rng = thread_rng();
+fn StatementTab() -> impl IntoView {
+    let link = Permalink::for_tab("statement");
+    let number = |key: &str, default: u32, max: u32| {
+        link.get(key)
+            .and_then(|v| v.parse::<u32>().ok())
+            .map_or(default, |v| v.min(max))
+    };
+    let countries_list = sepa_countries();
+
+    let country = RwSignal::new(link.get("country").unwrap_or_else(|| "NL".to_string()));
+    let currency = RwSignal::new(link.get("currency").unwrap_or_else(|| "EUR".to_string()));
+    let days = RwSignal::new(number("days", 7, 366).max(1));
+    let transactions = RwSignal::new(number("transactions", 5, 50));
+    let opening = RwSignal::new(link.get("opening").unwrap_or_else(|| "2500.00".to_string()));
+    let date = RwSignal::new(link.get("date").unwrap_or_else(|| {
+        dates::parse(&today())
+            .map(|d| dates::format(d - 6))
+            .unwrap_or_else(today)
+    }));
+    let format = RwSignal::new(link.get("format").unwrap_or_else(|| "camt053".to_string()));
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let camt = RwSignal::new(String::new());
+    let mt940 = RwSignal::new(String::new());
+    let totals = RwSignal::new(String::new());
+    let error = RwSignal::new(String::new());
+
+    let swift_registry = StoredValue::new(swift::Registry::new());
+
+    let generate = move || {
+        let Some(opening_balance) = sepa::parse_signed_amount(&opening.get()) else {
+            error.set("Opening balance must be a decimal number like -12.50".to_string());
+            return;
+        };
         let c = country.get();
-        let n = count.get();
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = passport::GenOptions {
-                    country: Some(c.clone()),
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    history_results.push(res.code.clone());
-                    rows.push(PassportRow {
-                        code: res.code,
-                        name: res.name,
-                        country: format!("{} — {}", res.country_code, res.country_name),
-                        valid: res.valid,
-                    });
+        let opts = statement::StatementOptions {
+            country: if c == "Random" { None } else { Some(c) },
+            currency: currency.get().trim().to_uppercase(),
+            start_date: date.get(),
+            days: days.get(),
+            max_transactions: transactions.get(),
+            opening_balance,
+            created_at: String::new(),
+        };
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let generated =
+            match swift_registry.with_value(|reg| statement::generate(reg, &opts, &mut rng)) {
+                Ok(generated) => generated,
+                Err(e) => {
+                    error.set(e);
+                    return;
                 }
-            }
-        });
-        results.set(rows);
-        copied_idx.set(None);
-        add_to_history("Passport", &c, n, history_results);
-    };
-
-    let copy_all = move |_| {
-        let rows = results.get();
-        let text: String = rows
-            .iter()
-            .map(|r| r.code.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        copy_to_clipboard(&text);
+            };
+        let closing = generated.closing().unwrap_or(opening_balance);
+        totals.set(format!(
+            "{} \u{00b7} {} statements \u{00b7} {} entries \u{00b7} closing {}{} {}",
+            generated.iban,
+            generated.days.len(),
+            generated.entry_count(),
+            if closing < 0 { "-" } else { "" },
+            sepa::format_amount(closing.unsigned_abs()),
+            opts.currency
+        ));
+        camt.set(generated.camt053());
+        mt940.set(generated.mt940());
+        used_seed.set(seed_value);
+        error.set(String::new());
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Name,Country,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{}\n",
-                row.code,
-                row.name,
-                row.country,
-                if row.valid { "Yes" } else { "No" }
-            ));
+    let content = Signal::derive(move || {
+        if format.get() == "mt940" {
+            mt940.get()
+        } else {
+            camt.get()
         }
-        download_csv("passports.csv", &csv);
-    };
+    });
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("passports.json", &json, "application/json;charset=utf-8;");
-    };
+    let copy_file = move |_| copy_to_clipboard(&content.get());
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS passports (code TEXT, name TEXT, country TEXT, valid BOOLEAN);\n",
-        );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO passports (code, name, country, valid) VALUES ('{}', '{}', '{}', {});\n",
-                row.code, row.name, row.country, row.valid
-            ));
+    let save_file = move |_| {
+        if format.get() == "mt940" {
+            download_file(
+                &file_name("mt940", used_seed.get(), "sta"),
+                &mt940.get(),
+                "text/plain;charset=utf-8;",
+            )
+        } else {
+            download_file(
+                &file_name("camt.053", used_seed.get(), "xml"),
+                &camt.get(),
+                "application/xml;charset=utf-8;",
+            )
         }
-        download_file("passports.sql", &sql, "text/plain;charset=utf-8;");
     };
 
-    let countries_for_select: Vec<(String, String)> =
-        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+    let summary =
+        Signal::derive(move || format!("{} \u{00b7} seed {}", totals.get(), used_seed.get()));
+
+    Effect::new(move |_| {
+        Permalink::write(
+            "statement",
+            &[
+                ("country", country.get()),
+                ("currency", currency.get()),
+                ("date", date.get()),
+                ("days", days.get().to_string()),
+                ("transactions", transactions.get().to_string()),
+                ("opening", opening.get()),
+                ("format", format.get()),
+                (
+                    "seed",
+                    if camt.with(|x| x.is_empty()) {
+                        String::new()
+                    } else {
+                        used_seed.get().to_string()
+                    },
+                ),
+            ],
+        );
+    });
+    if link.get("seed").is_some() {
+        generate();
+    }
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
+                <label>"Account country"</label>
                 <SearchableSelect
-                    options=countries_for_select
+                    options=countries_list
                     selected=country
                     on_change=Callback::new(|_| ())
                 />
             </div>
 
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
+                <label>"Start date"</label>
+                <input type="date"
+                    prop:value=move || date.get()
+                    on:input=move |ev| date.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>
+                    "Days "
+                    <Tooltip text="Calendar days covered; one statement per business day".to_string() />
+                </label>
+                <input type="number" min="1" max="366"
+                    prop:value=move || days.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            days.set(v.clamp(1, 366));
+                        }
+                    }
+                />
+            </div>
+
+            <div class="field">
+                <label>"Max bookings a day"</label>
+                <input type="number" min="0" max="50"
+                    prop:value=move || transactions.get().to_string()
                     on:input=move |ev| {
                         if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
+                            transactions.set(v.min(50));
                         }
                     }
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <div class="field">
+                <label>"Opening balance"</label>
+                <input type="text"
+                    prop:value=move || opening.get()
+                    on:input=move |ev| opening.set(event_target_value(&ev))
+                />
+            </div>
 
-            <Show when=move || !results.get().is_empty()>
-                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
-                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
-                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
-                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+            <div class="field">
+                <label>"Currency"</label>
+                <input type="text" maxlength="3"
+                    prop:value=move || currency.get()
+                    on:input=move |ev| currency.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Format"</label>
+                <select on:change=move |ev| format.set(event_target_value(&ev))>
+                    <option value="camt053" selected=move || format.get() == "camt053">"camt.053 XML"</option>
+                    <option value="mt940" selected=move || format.get() == "mt940">"MT940"</option>
+                </select>
+            </div>
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !camt.with(|x| x.is_empty())>
+                <button class="btn btn-secondary" on:click=copy_file>"Copy"</button>
+                <button class="btn btn-secondary" on:click=save_file>"Download"</button>
             </Show>
         </div>
 
-        <Show when=move || results.get().is_empty()>
-            <div class="empty">"Select a country and click Generate"</div>
-        </Show>
-
-        <Show when=move || !results.get().is_empty()>
-            <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Code"</th>
-                        <th>"Name"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let name = row.name.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{code}</td>
-                                    <td>{name}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
         </Show>
+
+        <FilePreview content=content summary=summary />
     }
 }
 
 #[component]
-fn TaxIdTab() -> impl IntoView {
-    let registry = tax_id::Registry::new();
-    let countries: Vec<(String, String, String)> = registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, d)| (c.to_string(), n.to_string(), d.to_string()))
-        .collect();
+fn AchTab() -> impl IntoView {
+    let link = Permalink::for_tab("ach");
+    let number = |key: &str, default: u32, max: u32| {
+        link.get(key)
+            .and_then(|v| v.parse::<u32>().ok())
+            .map_or(default, |v| v.clamp(1, max))
+    };
 
-    let country = RwSignal::new(
-        countries
-            .first()
-            .map(|(c, _, _)| c.clone())
-            .unwrap_or_default(),
+    let batches = RwSignal::new(number("batches", 1, 100));
+    let entries = RwSignal::new(number("entries", 5, 1000));
+    let sec_code = RwSignal::new(link.get("sec").unwrap_or_else(|| "mixed".to_string()));
+    let service_class = RwSignal::new(
+        link.get("class")
+            .and_then(|v| v.parse::<u16>().ok())
+            .filter(|c| nacha::SERVICE_CLASSES.iter().any(|(code, _)| code == c))
+            .unwrap_or(200),
     );
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<TaxIdRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
-
-    let registry = StoredValue::new(registry);
-
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let c = country.get();
-        let n = count.get();
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = tax_id::GenOptions {
-                    country: Some(c.clone()),
-                    holder_type: None,
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    history_results.push(res.code.clone());
-                    rows.push(TaxIdRow {
-                        code: res.code,
-                        name: res.name,
-                        country: format!("{} — {}", res.country_code, res.country_name),
-                        holder_type: res.holder_type,
-                        valid: res.valid,
-                    });
-                }
+    let min_amount = RwSignal::new(link.get("min").unwrap_or_else(|| "1.00".to_string()));
+    let max_amount = RwSignal::new(link.get("max").unwrap_or_else(|| "1000.00".to_string()));
+    let date = RwSignal::new(link.get("date").unwrap_or_else(today));
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let file = RwSignal::new(String::new());
+    let error = RwSignal::new(String::new());
+
+    let generate = move || {
+        let (Some(min), Some(max)) = (
+            sepa::parse_amount(&min_amount.get()),
+            sepa::parse_amount(&max_amount.get()),
+        ) else {
+            error.set("Amounts must be decimal numbers like 12.50".to_string());
+            return;
+        };
+        let s = sec_code.get();
+        let opts = nacha::AchOptions {
+            batches: batches.get(),
+            entries: entries.get(),
+            sec_code: if s == "mixed" { None } else { Some(s) },
+            service_class: service_class.get(),
+            min_amount: min,
+            max_amount: max,
+            effective_date: date.get(),
+        };
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        match nacha::ach_file(&opts, &mut rng) {
+            Some(content) => {
+                file.set(content);
+                used_seed.set(seed_value);
+                error.set(String::new());
             }
-        });
-        results.set(rows);
-        copied_idx.set(None);
-        add_to_history("Tax ID", &c, n, history_results);
+            None => error.set("Pick a valid effective date".to_string()),
+        }
     };
 
-    let copy_all = move |_| {
-        let rows = results.get();
-        let text: String = rows
-            .iter()
-            .map(|r| r.code.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        copy_to_clipboard(&text);
-    };
+    let copy_file = move |_| copy_to_clipboard(&file.get());
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Name,Type,Country,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{},{}\n",
-                row.code,
-                row.name,
-                row.holder_type.as_deref().unwrap_or(""),
-                row.country,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("tax_ids.csv", &csv);
+    let save_file = move |_| {
+        download_file(
+            &file_name("nacha", used_seed.get(), "ach"),
+            &file.get(),
+            "text/plain;charset=utf-8;",
+        )
     };
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("tax_ids.json", &json, "application/json;charset=utf-8;");
-    };
+    let summary = Signal::derive(move || {
+        format!(
+            "{} batches \u{00b7} {} entries \u{00b7} {} records \u{00b7} seed {}",
+            batches.get(),
+            batches.get() * entries.get(),
+            file.with(|f| f.lines().count()),
+            used_seed.get()
+        )
+    });
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS tax_ids (code TEXT, name TEXT, holder_type TEXT, country TEXT, valid BOOLEAN);\n",
+    Effect::new(move |_| {
+        Permalink::write(
+            "ach",
+            &[
+                ("batches", batches.get().to_string()),
+                ("entries", entries.get().to_string()),
+                ("sec", sec_code.get().replace("mixed", "")),
+                ("class", service_class.get().to_string()),
+                ("min", min_amount.get()),
+                ("max", max_amount.get()),
+                ("date", date.get()),
+                (
+                    "seed",
+                    if file.with(|f| f.is_empty()) {
+                        String::new()
+                    } else {
+                        used_seed.get().to_string()
+                    },
+                ),
+            ],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO tax_ids (code, name, holder_type, country, valid) VALUES ('{}', '{}', '{}', '{}', {});\n",
-                row.code, row.name, row.holder_type.as_deref().unwrap_or(""), row.country, row.valid
-            ));
-        }
-        download_file("tax_ids.sql", &sql, "text/plain;charset=utf-8;");
-    };
-
-    let countries_for_select: Vec<(String, String)> =
-        countries.into_iter().map(|(c, n, _)| (c, n)).collect();
+    });
+    if link.get("seed").is_some() {
+        generate();
+    }
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
-                <SearchableSelect
-                    options=countries_for_select
-                    selected=country
-                    on_change=Callback::new(|_| ())
+                <label>
+                    "Batches "
+                    <Tooltip text="One batch per originating company and ODFI".to_string() />
+                </label>
+                <input type="number" min="1" max="100"
+                    prop:value=move || batches.get().to_string()
+                    on:input=move |ev| {
+                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
+                            batches.set(v.clamp(1, 100));
+                        }
+                    }
                 />
             </div>
 
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
+                <label>"Entries each"</label>
+                <input type="number" min="1" max="1000"
+                    prop:value=move || entries.get().to_string()
                     on:input=move |ev| {
                         if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
+                            entries.set(v.clamp(1, 1000));
                         }
                     }
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <div class="field">
+                <label>"SEC code"</label>
+                <select on:change=move |ev| sec_code.set(event_target_value(&ev))>
+                    <option value="mixed" selected=move || sec_code.get() == "mixed">"Mixed"</option>
+                    {nacha::SEC_CODES.iter().map(|(code, label)| {
+                        let code = code.to_string();
+                        let value = code.clone();
+                        view! {
+                            <option value=value selected=move || sec_code.get() == code>
+                                {format!("{} ({})", label, code)}
+                            </option>
+                        }
+                    }).collect_view()}
+                </select>
+            </div>
 
-            <Show when=move || !results.get().is_empty()>
-                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
-                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
-                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
-                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+            <div class="field">
+                <label>"Service class"</label>
+                <select on:change=move |ev| {
+                    if let Ok(v) = event_target_value(&ev).parse::<u16>() {
+                        service_class.set(v);
+                    }
+                }>
+                    {nacha::SERVICE_CLASSES.iter().map(|(code, label)| {
+                        let code = *code;
+                        view! {
+                            <option value=code.to_string() selected=move || service_class.get() == code>
+                                {format!("{} ({})", label, code)}
+                            </option>
+                        }
+                    }).collect_view()}
+                </select>
+            </div>
+
+            <div class="field">
+                <label>"Min amount"</label>
+                <input type="text"
+                    prop:value=move || min_amount.get()
+                    on:input=move |ev| min_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Max amount"</label>
+                <input type="text"
+                    prop:value=move || max_amount.get()
+                    on:input=move |ev| max_amount.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="field">
+                <label>"Effective date"</label>
+                <input type="date"
+                    prop:value=move || date.get()
+                    on:input=move |ev| date.set(event_target_value(&ev))
+                />
+            </div>
+
+            <SeedField seed=seed />
+
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
+
+            <Show when=move || !file.with(|f| f.is_empty())>
+                <button class="btn btn-secondary" on:click=copy_file>"Copy"</button>
+                <button class="btn btn-secondary" on:click=save_file>"Download"</button>
             </Show>
         </div>
 
-        <Show when=move || results.get().is_empty()>
-            <div class="empty">"Select a country and click Generate"</div>
-        </Show>
-
-        <Show when=move || !results.get().is_empty()>
-            <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Code"</th>
-                        <th>"Name"</th>
-                        <th>"Type"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let name = row.name.clone();
-                            let holder_type = row.holder_type.clone().unwrap_or_default();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{code}</td>
-                                    <td>{name}</td>
-                                    <td>{holder_type}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
         </Show>
+
+        <FilePreview content=file summary=summary />
     }
 }
 
-#[component]
-fn VatTab() -> impl IntoView {
-    let registry = vat::Registry::new();
-    let countries: Vec<(String, String)> = registry
-        .list_countries()
-        .iter()
-        .map(|(c, n)| (c.to_string(), n.to_string()))
-        .collect();
+/// Dataset builder columns, kept in local storage between visits; a
+/// customer table to start from on the first.
+fn dataset_schema() -> Schema {
+    local_storage()
+        .and_then(|s| s.get_item("dataset_schema").ok().flatten())
+        .and_then(|json| Schema::from_json(&json).ok())
+        .unwrap_or_else(|| Schema {
+            name: "customers".to_string(),
+            columns: vec![
+                dataset::Column::new("name", "name"),
+                dataset::Column {
+                    country: "PL".to_string(),
+                    ..dataset::Column::new("pesel", "personal_id")
+                },
+                dataset::Column {
+                    field: "dob".to_string(),
+                    country: "PL".to_string(),
+                    ..dataset::Column::new("date_of_birth", "personal_id")
+                },
+                dataset::Column {
+                    country: "PL".to_string(),
+                    ..dataset::Column::new("iban", "iban")
+                },
+            ],
+        })
+}
 
-    let country = RwSignal::new(
-        countries
-            .first()
-            .map(|(c, _)| c.clone())
-            .unwrap_or_default(),
-    );
-    let count = RwSignal::new(5u32);
-    let results: RwSignal<Vec<VatRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+fn save_dataset_schema(schema: &Schema) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item("dataset_schema", &schema.to_json());
+    }
+}
 
-    let registry = StoredValue::new(registry);
+/// Applies `f` to column `i` of the schema.
+fn edit_column(schema: RwSignal<Schema>, i: usize, f: impl FnOnce(&mut dataset::Column)) {
+    schema.update(|s| {
+        if let Some(column) = s.columns.get_mut(i) {
+            f(column);
+        }
+    });
+}
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let c = country.get();
-        let n = count.get();
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = vat::GenOptions {
-                    country: Some(c.clone()),
-                };
-                if let Some(res) = reg.generate(&opts, &mut rng) {
-                    history_results.push(res.code.clone());
-                    rows.push(VatRow {
-                        code: res.code,
-                        country_code: res.country_code,
-                        country_name: res.country_name,
-                        valid: res.valid,
-                    });
-                }
+/// Input for one of the generator options a dataset column reads.
+fn column_option(schema: RwSignal<Schema>, i: usize, option: &'static str) -> AnyView {
+    let read = move |f: fn(&dataset::Column) -> String| {
+        schema.with(|s| s.columns.get(i).map(f).unwrap_or_default())
+    };
+    let text = move |label: &'static str,
+                     placeholder: &'static str,
+                     get: fn(&dataset::Column) -> String,
+                     set: fn(&mut dataset::Column, String)| {
+        view! {
+            <label>
+                {label}
+                <input type="text" placeholder=placeholder
+                    prop:value=move || read(get)
+                    on:input=move |ev| {
+                        let value = event_target_value(&ev);
+                        edit_column(schema, i, |c| set(c, value));
+                    }
+                />
+            </label>
+        }
+        .into_any()
+    };
+    match option {
+        "country" => text(
+            "Country ",
+            "e.g. DE",
+            |c| c.country.clone(),
+            |c, v| c.country = v.to_uppercase(),
+        ),
+        "brand" => text(
+            "Brand ",
+            "visa",
+            |c| c.brand.clone(),
+            |c, v| c.brand = v.to_lowercase(),
+        ),
+        "state" => text(
+            "State ",
+            "any",
+            |c| c.state.clone(),
+            |c, v| c.state = v.to_uppercase(),
+        ),
+        "holder_type" => text(
+            "Holder type ",
+            "any",
+            |c| c.holder_type.clone(),
+            |c, v| c.holder_type = v,
+        ),
+        "gender" => view! {
+            <label>
+                "Gender "
+                <select on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    edit_column(schema, i, |c| c.gender = value);
+                }>
+                    {[("", "Any"), ("male", "Male"), ("female", "Female")].into_iter().map(|(key, label)| view! {
+                        <option value=key selected=move || read(|c| c.gender.clone()) == key>{label}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+        }
+        .into_any(),
+        "year" => view! {
+            <label>
+                "Birth year "
+                <input type="number" min="1900" max="2100" placeholder="any"
+                    prop:value=move || read(|c| c.year.map(|y| y.to_string()).unwrap_or_default())
+                    on:input=move |ev| {
+                        let year = event_target_value(&ev).parse::<u16>().ok();
+                        edit_column(schema, i, |c| c.year = year);
+                    }
+                />
+            </label>
+        }
+        .into_any(),
+        _ => ().into_any(),
+    }
+}
+
+#[component]
+fn DatasetTab() -> impl IntoView {
+    let link = Permalink::for_tab("dataset");
+    let schema = RwSignal::new(dataset_schema());
+    let count = RwSignal::new(link.count().min(dataset::MAX_ROWS));
+    let seed = RwSignal::new(link.get("seed").unwrap_or_default());
+    let used_seed = RwSignal::new(0u64);
+    let result: RwSignal<Option<Dataset>> = RwSignal::new(None);
+    let error = RwSignal::new(String::new());
+
+    Effect::new(move |_| schema.with(save_dataset_schema));
+
+    let rows =
+        Memo::new(move |_| result.with(|d| d.as_ref().map(|d| d.rows.clone()).unwrap_or_default()));
+    // Rendering the column editors only when columns come or go keeps the
+    // focus in the field being typed into.
+    let column_count = Memo::new(move |_| schema.with(|s| s.columns.len()));
+
+    let generate = move || {
+        let seed_value = generate::resolve_seed(&seed.get());
+        let mut rng = generate::seeded_rng(seed_value);
+        let year = (js_sys::Date::new_0().get_full_year() % 100) as u16;
+        match schema.with(|s| s.generate(count.get(), year, &mut rng)) {
+            Ok(dataset) => {
+                error.set(String::new());
+                // The first column of as many rows as the other tabs keep.
+                let history_results = dataset
+                    .rows
+                    .iter()
+                    .take(INLINE_LIMIT as usize)
+                    .map(|row| row.first().map(dataset::cell).unwrap_or_default())
+                    .collect();
+                add_to_history(
+                    "Dataset",
+                    &dataset.table,
+                    count.get(),
+                    seed_value,
+                    history_results,
+                );
+                result.set(Some(dataset));
+                used_seed.set(seed_value);
             }
-        });
-        results.set(rows);
-        copied_idx.set(None);
-        add_to_history("VAT", &c, n, history_results);
+            Err(e) => {
+                error.set(e);
+                result.set(None);
+            }
+        }
     };
 
-    let copy_all = move |_| {
-        let rows = results.get();
-        let text: String = rows
-            .iter()
-            .map(|r| r.code.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        copy_to_clipboard(&text);
+    let add_column = move |_| {
+        schema.update(|s| {
+            let name = format!("column_{}", s.columns.len() + 1);
+            s.columns.push(dataset::Column {
+                country: "DE".to_string(),
+                ..dataset::Column::new(&name, "iban")
+            });
+        })
+    };
+
+    let save_schema = move |_| {
+        schema.with(|s| {
+            download_file(
+                &format!("{}.schema.json", s.table()),
+                &s.to_json(),
+                "application/json;charset=utf-8;",
+            )
+        })
+    };
+
+    let load_schema = move |ev: leptos::ev::Event| {
+        let Some(input) = ev.target() else {
+            return;
+        };
+        let callback = Closure::once_into_js(move |text: String| match Schema::from_json(&text) {
+            Ok(loaded) => {
+                error.set(String::new());
+                schema.set(loaded);
+            }
+            Err(e) => error.set(e),
+        });
+        read_file_text(&input, callback.unchecked_ref());
     };
 
     let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,Country Code,Country Name,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{}\n",
-                row.code,
-                row.country_code,
-                row.country_name,
-                if row.valid { "Yes" } else { "No" }
-            ));
-        }
-        download_csv("vat_numbers.csv", &csv);
+        result.with(|d| {
+            if let Some(d) = d {
+                download_csv(
+                    &file_name(&d.table, used_seed.get(), "csv"),
+                    &d.to_csv(&csv_options()),
+                );
+            }
+        })
     };
 
     let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("vat_numbers.json", &json, "application/json;charset=utf-8;");
+        result.with(|d| {
+            if let Some(d) = d {
+                download_file(
+                    &file_name(&d.table, used_seed.get(), "json"),
+                    &d.to_json(),
+                    "application/json;charset=utf-8;",
+                );
+            }
+        })
     };
 
     let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS vat_numbers (code TEXT, country_code TEXT, country_name TEXT, valid BOOLEAN);\n",
+        result.with(|d| {
+            let Some(d) = d else {
+                return;
+            };
+            let options = sql_options("dataset");
+            download_file(
+                &file_name(&d.table, used_seed.get(), "sql"),
+                &d.to_sql(Some(used_seed.get()), &options),
+                "text/plain;charset=utf-8;",
+            );
+            if options.separate_data() {
+                download_file(
+                    &options.data_file(&d.table),
+                    &d.load_data(),
+                    "text/tab-separated-values;charset=utf-8;",
+                );
+            }
+        })
+    };
+
+    Effect::new(move |_| {
+        let seed = if result.with(Option::is_some) {
+            used_seed.get().to_string()
+        } else {
+            String::new()
+        };
+        Permalink::write(
+            "dataset",
+            &[("count", count.get().to_string()), ("seed", seed)],
         );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO vat_numbers (code, country_code, country_name, valid) VALUES ('{}', '{}', '{}', {});\n",
-                row.code, row.country_code, row.country_name, row.valid
-            ));
+    });
+    if link.restores_results() {
+        generate();
+    }
+
+    let column_editor = move |i: usize| {
+        let read = move || schema.with(|s| s.columns.get(i).cloned().unwrap_or_default());
+        let kind_key = Memo::new(move |_| read().kind);
+        view! {
+            <div class="dataset-column">
+                <label>
+                    "Name "
+                    <input type="text"
+                        prop:value=move || read().name
+                        on:input=move |ev| {
+                            let value = event_target_value(&ev);
+                            edit_column(schema, i, |c| c.name = value);
+                        }
+                    />
+                </label>
+                <label>
+                    "Generator "
+                    <select on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        edit_column(schema, i, |c| {
+                            c.kind = value;
+                            c.field = String::new();
+                        });
+                    }>
+                        {dataset::KINDS.iter().map(|k| view! {
+                            <option value=k.key selected=move || kind_key.get() == k.key>{k.label}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label>
+                    "Field "
+                    <select on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        edit_column(schema, i, |c| c.field = value);
+                    }>
+                        {move || dataset::kind(&kind_key.get()).map(|k| {
+                            k.fields.iter().enumerate().map(|(j, (name, _))| {
+                                // The first field is the code itself, saved as empty.
+                                let value = if j == 0 { "" } else { *name };
+                                view! {
+                                    <option value=value selected=move || read().field == value>{*name}</option>
+                                }
+                            }).collect_view()
+                        })}
+                    </select>
+                </label>
+                {move || dataset::kind(&kind_key.get()).map(|k| {
+                    k.options.iter().map(|&option| column_option(schema, i, option)).collect_view()
+                })}
+                <button class="btn btn-secondary" title="Remove column"
+                    on:click=move |_| schema.update(|s| {
+                        if i < s.columns.len() {
+                            s.columns.remove(i);
+                        }
+                    })
+                >"\u{2715}"</button>
+            </div>
         }
-        download_file("vat_numbers.sql", &sql, "text/plain;charset=utf-8;");
     };
 
-    let countries_for_select = countries.clone();
-
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country"</label>
-                <SearchableSelect
-                    options=countries_for_select
-                    selected=country
-                    on_change=Callback::new(|_| ())
+                <label>
+                    "Table "
+                    <Tooltip text="SQL table name and download file name".to_string() />
+                </label>
+                <input type="text" placeholder="dataset"
+                    prop:value=move || schema.with(|s| s.name.clone())
+                    on:input=move |ev| schema.update(|s| s.name = event_target_value(&ev))
                 />
             </div>
 
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
+                <label>
+                    "Count "
+                    <Tooltip text=format!("Up to {} rows, all shown in the table.", dataset::MAX_ROWS) />
+                </label>
+                <input type="number" min="1" max=dataset::MAX_ROWS
                     prop:value=move || count.get().to_string()
                     on:input=move |ev| {
                         if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
+                            count.set(v.clamp(1, dataset::MAX_ROWS));
                         }
                     }
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
-
-            <Show when=move || !results.get().is_empty()>
-                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
-                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
-                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
-                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
-            </Show>
-        </div>
-
-        <Show when=move || results.get().is_empty()>
-            <div class="empty">"Select a country and click Generate"</div>
-        </Show>
-
-        <Show when=move || !results.get().is_empty()>
-            <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
-            </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Code"</th>
-                        <th>"Country"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let country_display = format!("{} — {}", row.country_code, row.country_name);
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{code}</td>
-                                    <td>{country_display}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
-        </Show>
-    }
-}
-
-#[component]
-fn LeiTab() -> impl IntoView {
-    let registry = lei::Registry::new();
+            <SeedField seed=seed />
 
-    let count = RwSignal::new(5u32);
-    let country = RwSignal::new(String::new());
-    let results: RwSignal<Vec<LeiRow>> = RwSignal::new(Vec::new());
-    let copied_idx: RwSignal<Option<usize>> = RwSignal::new(None);
+            <button class="btn btn-primary" on:click=move |_| generate()>"Generate"</button>
 
-    let registry = StoredValue::new(registry);
+            <Show when=move || result.with(Option::is_some)>
+                <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                <CsvOptionsMenu />
+                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
+                <SqlOptionsMenu table="dataset" />
+            </Show>
+        </div>
 
-    let generate = move |_| {
-        let mut rng = thread_rng();
-        let n = count.get();
-        let c = country.get();
-        let mut rows = Vec::new();
-        let mut history_results = Vec::new();
-        registry.with_value(|reg| {
-            for _ in 0..n {
-                let opts = lei::GenOptions {
-                    country: if c.is_empty() { None } else { Some(c.clone()) },
-                };
-                let res = reg.generate(&opts, &mut rng);
-                history_results.push(res.code.clone());
-                rows.push(LeiRow {
-                    code: res.code,
-                    lou: res.lou,
-                    country_code: res.country_code,
-                    valid: res.valid,
-                });
+        <div class="dataset-columns">
+            {move || (0..column_count.get()).map(column_editor).collect_view()}
+            <div class="dataset-actions">
+                <button class="btn btn-secondary" on:click=add_column>"Add column"</button>
+                <button class="btn btn-secondary" on:click=save_schema>"Save schema"</button>
+                <label>
+                    "Load schema "
+                    <input type="file" accept=".json,application/json" on:change=load_schema />
+                </label>
+            </div>
+        </div>
+
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
+            </div>
+        </Show>
+
+        <Show when=move || result.with(Option::is_none) && error.get().is_empty()>
+            <div class="empty">"Define the columns and click Generate"</div>
+        </Show>
+
+        {move || result.with(|d| d.as_ref().map(|d| {
+            let columns: Vec<Column<Vec<SqlValue>>> = d
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, (name, _))| {
+                    Column::new(name.as_str(), move |row: &Vec<SqlValue>| {
+                        row.get(i).map(dataset::cell).unwrap_or_default()
+                    })
+                })
+                .collect();
+            view! {
+                <div class="results-header">
+                    <span>{move || format!("{} rows \u{00b7} seed {}", rows.with(Vec::len), used_seed.get())}</span>
+                </div>
+                <ResultsTable rows=rows columns=columns />
             }
-        });
-        results.set(rows);
-        copied_idx.set(None);
-        add_to_history(
-            "LEI",
-            if c.is_empty() { "Random" } else { &c },
-            n,
-            history_results,
-        );
-    };
+        }))}
+    }
+}
 
-    let copy_all = move |_| {
-        let rows = results.get();
-        let text: String = rows
-            .iter()
-            .map(|r| r.code.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        copy_to_clipboard(&text);
+#[component]
+fn PseudonymizeTab() -> impl IntoView {
+    Permalink::write("pseudonymize", &[]);
+    let validator = StoredValue::new(Validator::new());
+    let input = RwSignal::new(String::new());
+    let headers: RwSignal<Vec<String>> = RwSignal::new(Vec::new());
+    // `None` keeps a column as it is.
+    let rules: RwSignal<Vec<Option<pseudonymize::Rule>>> = RwSignal::new(Vec::new());
+    let key = RwSignal::new(String::new());
+    let result: RwSignal<Option<pseudonymize::Pseudonymized>> = RwSignal::new(None);
+    let error = RwSignal::new(String::new());
+
+    let rows =
+        Memo::new(move |_| result.with(|r| r.as_ref().map(|r| r.rows.clone()).unwrap_or_default()));
+
+    let upload = move |ev: leptos::ev::Event| {
+        let Some(target) = ev.target() else {
+            return;
+        };
+        let callback = Closure::once_into_js(move |text: String| {
+            let (names, sample, _) = export::read_csv(&text);
+            // Columns whose values clearly read as one type start marked.
+            let suggested = (0..names.len())
+                .map(|i| {
+                    validator
+                        .with_value(|v| {
+                            pseudonymize::suggest(
+                                v,
+                                sample
+                                    .iter()
+                                    .filter_map(|row| row.get(i).map(String::as_str)),
+                            )
+                        })
+                        .map(|kind| pseudonymize::Rule {
+                            kind: kind.to_string(),
+                            country: String::new(),
+                        })
+                })
+                .collect();
+            error.set(if names.is_empty() {
+                "The file is empty".to_string()
+            } else {
+                String::new()
+            });
+            result.set(None);
+            headers.set(names);
+            rules.set(suggested);
+            input.set(text);
+        });
+        read_file_text(&target, callback.unchecked_ref());
     };
 
-    let save_csv = move |_| {
-        let rows = results.get();
-        let mut csv = String::from("Code,LOU,Country,Valid\n");
-        for row in rows.iter() {
-            csv.push_str(&format!(
-                "{},{},{},{}\n",
-                row.code,
-                row.lou,
-                row.country_code,
-                if row.valid { "Yes" } else { "No" }
-            ));
+    let run = move |_| {
+        let marked: Vec<(String, pseudonymize::Rule)> = headers.with(|h| {
+            rules.with(|r| {
+                h.iter()
+                    .zip(r)
+                    .filter_map(|(name, rule)| Some((name.clone(), rule.clone()?)))
+                    .collect()
+            })
+        });
+        let year = (js_sys::Date::new_0().get_full_year() % 100) as u16;
+        let outcome = pseudonymize::Pseudonymizer::new(&key.get(), year)
+            .and_then(|mut p| input.with(|text| p.csv(text, &marked)));
+        match outcome {
+            Ok(done) => {
+                error.set(String::new());
+                result.set(Some(done));
+            }
+            Err(e) => {
+                error.set(e);
+                result.set(None);
+            }
         }
-        download_csv("lei_codes.csv", &csv);
     };
 
-    let save_json = move |_| {
-        let rows = results.get();
-        let json = serde_json::to_string_pretty(&rows).unwrap_or_default();
-        download_file("lei_codes.json", &json, "application/json;charset=utf-8;");
+    let save_csv = move |_| {
+        result.with(|r| {
+            if let Some(r) = r {
+                download_csv("pseudonymized.csv", &r.csv);
+            }
+        })
     };
 
-    let save_sql = move |_| {
-        let rows = results.get();
-        let mut sql = String::from(
-            "CREATE TABLE IF NOT EXISTS lei_codes (code TEXT, lou TEXT, country_code TEXT, valid BOOLEAN);\n",
-        );
-        for row in rows.iter() {
-            sql.push_str(&format!(
-                "INSERT INTO lei_codes (code, lou, country_code, valid) VALUES ('{}', '{}', '{}', {});\n",
-                row.code, row.lou, row.country_code, row.valid
-            ));
+    let rule_editor = move |i: usize, name: String| {
+        let rule = move || rules.with(|r| r.get(i).cloned().flatten());
+        let set = move |f: &dyn Fn(&mut Option<pseudonymize::Rule>)| {
+            rules.update(|r| {
+                if let Some(rule) = r.get_mut(i) {
+                    f(rule);
+                }
+            })
+        };
+        view! {
+            <div class="dataset-column">
+                <strong>{name}</strong>
+                <label>
+                    "Holds "
+                    <select on:change=move |ev| {
+                        let kind = event_target_value(&ev);
+                        set(&|rule| {
+                            let country = rule.take().map(|r| r.country).unwrap_or_default();
+                            *rule = (!kind.is_empty()).then(|| pseudonymize::Rule {
+                                kind: kind.clone(),
+                                country,
+                            });
+                        });
+                    }>
+                        <option value="" selected=move || rule().is_none()>"Keep as is"</option>
+                        <option value=DETECT selected=move || rule().is_some_and(|r| r.kind == DETECT)>"Auto-detect"</option>
+                        {mockbanker_core::validate::KINDS.iter().map(|(key, label)| view! {
+                            <option value=*key selected=move || rule().is_some_and(|r| r.kind == *key)>{*label}</option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <Show when=move || rule().is_some()>
+                    <label>
+                        "Country "
+                        <Tooltip text="Country of every value in the column; leave empty to read it from each value".to_string() />
+                        <input type="text" placeholder="from value"
+                            prop:value=move || rule().map(|r| r.country).unwrap_or_default()
+                            on:input=move |ev| {
+                                let country = event_target_value(&ev).to_uppercase();
+                                set(&|rule| {
+                                    if let Some(rule) = rule {
+                                        rule.country = country.clone();
+                                    }
+                                });
+                            }
+                        />
+                    </label>
+                </Show>
+            </div>
         }
-        download_file("lei_codes.sql", &sql, "text/plain;charset=utf-8;");
     };
 
     view! {
         <div class="controls">
             <div class="field">
-                <label>"Country (optional)"</label>
-                <input type="text" placeholder="e.g. US (leave empty for random)"
-                    prop:value=move || country.get()
-                    on:input=move |ev| country.set(event_target_value(&ev))
-                />
+                <label>
+                    "Upload CSV "
+                    <Tooltip text="Processed in your browser; the file never leaves this device".to_string() />
+                </label>
+                <input type="file" accept=".csv,.txt,text/csv,text/plain" on:change=upload />
             </div>
 
             <div class="field">
-                <label>"Count"</label>
-                <input type="number" min="1" max="100"
-                    prop:value=move || count.get().to_string()
-                    on:input=move |ev| {
-                        if let Ok(v) = event_target_value(&ev).parse::<u32>() {
-                            count.set(v.clamp(1, 100));
-                        }
-                    }
+                <label>
+                    "Key "
+                    <Tooltip text="The same key maps each real value to the same fake one in every file. Keep it secret: with it the mapping can be recomputed.".to_string() />
+                </label>
+                <input type="password" autocomplete="off"
+                    prop:value=move || key.get()
+                    on:input=move |ev| key.set(event_target_value(&ev))
                 />
             </div>
 
-            <button class="btn btn-primary" on:click=generate>"Generate"</button>
+            <Show when=move || !headers.with(Vec::is_empty)>
+                <button class="btn btn-primary" on:click=run>"Pseudonymize"</button>
+            </Show>
 
-            <Show when=move || !results.get().is_empty()>
-                <button class="btn btn-secondary" on:click=copy_all>"Copy all"</button>
+            <Show when=move || result.with(Option::is_some)>
                 <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
-                <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
-                <button class="btn btn-secondary" on:click=save_sql>"SQL"</button>
             </Show>
         </div>
 
-        <Show when=move || results.get().is_empty()>
-            <div class="empty">"Click Generate to create LEI codes"</div>
+        <Show when=move || !headers.with(Vec::is_empty)>
+            <div class="dataset-columns">
+                {move || headers.get().into_iter().enumerate().map(|(i, name)| rule_editor(i, name)).collect_view()}
+            </div>
         </Show>
 
-        <Show when=move || !results.get().is_empty()>
-            <div class="results-header">
-                <span>{move || format!("{} results", results.get().len())}</span>
+        <Show when=move || !error.get().is_empty()>
+            <div class="result-box result-invalid">
+                <p>{move || error.get()}</p>
             </div>
-            <table>
-                <thead>
-                    <tr>
-                        <th>"Code"</th>
-                        <th>"LOU"</th>
-                        <th>"Country"</th>
-                        <th>"Valid"</th>
-                        <th></th>
-                    </tr>
-                </thead>
-                <tbody>
-                    {move || {
-                        let cidx = copied_idx.get();
-                        results.get().iter().enumerate().map(|(i, row)| {
-                            let code = row.code.clone();
-                            let copy_text = code.clone();
-                            let lou = row.lou.clone();
-                            let country_code = row.country_code.clone();
-                            let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
-                            let is_copied = cidx == Some(i);
-                            view! {
-                                <tr>
-                                    <td>{code}</td>
-                                    <td>{lou}</td>
-                                    <td>{country_code}</td>
-                                    <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
-                                    <td>
-                                        <button
-                                            class=if is_copied { "btn-copy copied" } else { "btn-copy" }
-                                            on:click=move |_| {
-                                                copy_to_clipboard(&copy_text);
-                                                copied_idx.set(Some(i));
-                                            }
-                                        >
-                                            {if is_copied { "Copied!" } else { "Copy" }}
-                                        </button>
-                                    </td>
-                                </tr>
-                            }
-                        }).collect_view()
-                    }}
-                </tbody>
-            </table>
         </Show>
+
+        <Show when=move || headers.with(Vec::is_empty) && error.get().is_empty()>
+            <div class="empty">"Upload a CSV file, mark the columns holding identifiers and click Pseudonymize"</div>
+        </Show>
+
+        {move || result.with(|r| r.as_ref().map(|r| {
+            let report = r.columns.iter().map(|c| {
+                let class = if c.blanked > 0 { "valid-no" } else { "valid-yes" };
+                view! {
+                    <li class=class>
+                        {format!("{}: {} replaced", c.name, c.replaced)}
+                        {(c.blanked > 0).then(|| format!(", {} blanked \u{2014} set the type or country to replace them", c.blanked))}
+                    </li>
+                }
+            }).collect_view();
+            let columns: Vec<Column<Vec<String>>> = r
+                .headers
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    Column::new(name.as_str(), move |row: &Vec<String>| {
+                        row.get(i).cloned().unwrap_or_default()
+                    })
+                })
+                .collect();
+            view! {
+                <ul class="pseudonymize-report">{report}</ul>
+                <ResultsTable rows=rows columns=columns />
+            }
+        }))}
     }
 }
 
@@ -2645,240 +5297,132 @@ fn SearchableSelect(
 
 #[component]
 fn ValidatorTab() -> impl IntoView {
+    Permalink::write("validator", &[]);
     let input_value = RwSignal::new(String::new());
     let selected_type = RwSignal::new("iban".to_string());
     let country = RwSignal::new("DE".to_string());
     let result: RwSignal<Option<(bool, String)>> = RwSignal::new(None);
-
-    let id_registry = personal_id::Registry::new();
-    let bank_registry = bank_account::Registry::new();
-    let card_registry = credit_card::Registry::new();
-    let swift_registry = swift::Registry::new();
-    let company_registry = company_id::Registry::new();
-    let dl_registry = driver_license::Registry::new();
-    let passport_registry = passport::Registry::new();
-    let tax_registry = tax_id::Registry::new();
-    let vat_registry = vat::Registry::new();
-    let lei_registry = lei::Registry::new();
-
-    let id_countries: Vec<(String, String)> = id_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let bank_countries: Vec<(String, String)> = bank_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let company_countries: Vec<(String, String)> = company_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let dl_countries: Vec<(String, String)> = dl_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let passport_countries: Vec<(String, String)> = passport_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let tax_countries: Vec<(String, String)> = tax_registry
-        .list_countries()
-        .iter()
-        .map(|(c, n, _)| (c.to_string(), n.to_string()))
-        .collect();
-
-    let id_countries = StoredValue::new(id_countries);
-    let bank_countries = StoredValue::new(bank_countries);
-    let company_countries = StoredValue::new(company_countries);
-    let dl_countries = StoredValue::new(dl_countries);
-    let passport_countries = StoredValue::new(passport_countries);
-    let tax_countries = StoredValue::new(tax_countries);
-
-    let id_registry = StoredValue::new(id_registry);
-    let bank_registry = StoredValue::new(bank_registry);
-    let card_registry = StoredValue::new(card_registry);
-    let swift_registry = StoredValue::new(swift_registry);
-    let company_registry = StoredValue::new(company_registry);
-    let dl_registry = StoredValue::new(dl_registry);
-    let passport_registry = StoredValue::new(passport_registry);
-    let tax_registry = StoredValue::new(tax_registry);
-    let vat_registry = StoredValue::new(vat_registry);
-    let lei_registry = StoredValue::new(lei_registry);
+    let readings: RwSignal<Vec<Interpretation>> = RwSignal::new(Vec::new());
+    let checks: RwSignal<Vec<Check>> = RwSignal::new(Vec::new());
+    let checked_iban = RwSignal::new(String::new());
+    let bulk = RwSignal::new(false);
+    let bulk_text = RwSignal::new(String::new());
+    // Rows keep their input line number so the table can return to input order.
+    let report: RwSignal<Vec<(usize, ValidationRow)>> = RwSignal::new(Vec::new());
+    let sort = RwSignal::new(("line", true));
+    let copied_idx = RwSignal::new(None::<usize>);
+
+    let validator = StoredValue::new(Validator::new());
 
     let validate = move |_| {
         let val = input_value.get().trim().to_string();
         if val.is_empty() {
             result.set(None);
+            readings.set(Vec::new());
+            checks.set(Vec::new());
+            checked_iban.set(String::new());
             return;
         }
 
-        match selected_type.get().as_str() {
-            "iban" => {
-                let is_valid = iban::validate_iban(&val);
-                result.set(Some((
-                    is_valid,
-                    if is_valid {
-                        "Valid IBAN".to_string()
-                    } else {
-                        "Invalid IBAN checksum or format".to_string()
-                    },
-                )));
-            }
-            "id" => {
-                id_registry.with_value(|reg| {
-                    if let Some(parsed) = reg.parse(&country.get(), &val) {
-                        if parsed.valid {
-                            result.set(Some((
-                                true,
-                                format!(
-                                    "Valid ID ({} / {})",
-                                    parsed.gender.unwrap_or_default(),
-                                    parsed.dob.unwrap_or_default()
-                                ),
-                            )));
-                        } else {
-                            result
-                                .set(Some((false, "Invalid ID for selected country".to_string())));
-                        }
+        let kind = selected_type.get();
+        let c = country.get();
+        if let Some(res) = validator.with_value(|v| v.validate(&kind, &c, &val)) {
+            result.set(Some(res));
+        }
+        readings.set(if kind == DETECT {
+            validator.with_value(|v| v.detect(&val))
+        } else {
+            Vec::new()
+        });
+        checks.set(
+            validator
+                .with_value(|v| v.diagnose(&kind, &c, &val))
+                .map(|d| d.checks)
+                .filter(|_| kind != DETECT)
+                .unwrap_or_default(),
+        );
+        checked_iban.set(if kind == "iban" { val } else { String::new() });
+    };
+
+    let validate_bulk = move || {
+        let values = mockbanker_core::validate::values(&bulk_text.get());
+        let kind = selected_type.get();
+        let c = country.get();
+        let rows = validator.with_value(|v| v.validate_all(&kind, &c, &values));
+        report.set(
+            rows.into_iter()
+                .enumerate()
+                .map(|(i, r)| (i + 1, r))
+                .collect(),
+        );
+        copied_idx.set(None);
+    };
+
+    let upload = move |ev: leptos::ev::Event| {
+        let Some(input) = ev.target() else {
+            return;
+        };
+        let callback = Closure::once_into_js(move |text: String| {
+            bulk_text.set(text);
+            validate_bulk();
+        });
+        read_file_text(&input, callback.unchecked_ref());
+    };
+
+    let sorted = Memo::new(move |_| {
+        let (column, ascending) = sort.get();
+        let mut rows = report.get();
+        rows.sort_by(|(la, a), (lb, b)| {
+            let order = match column {
+                "value" => a.value.cmp(&b.value),
+                "valid" => a.valid.cmp(&b.valid),
+                "reason" => a.reason.cmp(&b.reason),
+                _ => la.cmp(lb),
+            };
+            if ascending { order } else { order.reverse() }
+        });
+        rows
+    });
+
+    let sort_header = move |column: &'static str, label: &'static str| {
+        view! {
+            <th
+                class="sortable"
+                on:click=move |_| sort.update(|(c, ascending)| {
+                    if *c == column {
+                        *ascending = !*ascending;
                     } else {
-                        result.set(Some((false, "Could not parse ID".to_string())));
+                        *c = column;
+                        *ascending = true;
                     }
-                });
-            }
-            "bank" => {
-                bank_registry.with_value(|reg| match reg.validate(&country.get(), &val) {
-                    Some(true) => result.set(Some((
-                        true,
-                        "Valid Bank Account for selected country".to_string(),
-                    ))),
-                    Some(false) => result.set(Some((
-                        false,
-                        "Invalid Bank Account checksum or format".to_string(),
-                    ))),
-                    None => result.set(Some((
-                        false,
-                        "Unsupported country for Bank Account validation".to_string(),
-                    ))),
-                });
-            }
-            "card" => {
-                card_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid Credit Card (Luhn check passed)".to_string()
-                        } else {
-                            "Invalid Credit Card (Luhn check failed)".to_string()
-                        },
-                    )));
-                });
-            }
-            "swift" => {
-                swift_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid SWIFT/BIC format".to_string()
-                        } else {
-                            "Invalid SWIFT/BIC format".to_string()
-                        },
-                    )));
-                });
-            }
-            "company" => {
-                company_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&country.get(), &val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid Company ID for selected country".to_string()
-                        } else {
-                            "Invalid Company ID checksum or format".to_string()
-                        },
-                    )));
-                });
-            }
-            "driver_license" => {
-                dl_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&country.get(), &val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid Driver's License for selected country".to_string()
-                        } else {
-                            "Invalid Driver's License format".to_string()
-                        },
-                    )));
-                });
-            }
-            "passport" => {
-                passport_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&country.get(), &val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid Passport for selected country".to_string()
-                        } else {
-                            "Invalid Passport format".to_string()
-                        },
-                    )));
-                });
-            }
-            "tax_id" => {
-                tax_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&country.get(), &val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid Tax ID for selected country".to_string()
-                        } else {
-                            "Invalid Tax ID format".to_string()
-                        },
-                    )));
-                });
-            }
-            "vat" => {
-                vat_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid VAT number".to_string()
-                        } else {
-                            "Invalid VAT number format".to_string()
-                        },
-                    )));
-                });
-            }
-            "lei" => {
-                lei_registry.with_value(|reg| {
-                    let is_valid = reg.validate(&val);
-                    result.set(Some((
-                        is_valid,
-                        if is_valid {
-                            "Valid LEI code".to_string()
-                        } else {
-                            "Invalid LEI code format".to_string()
-                        },
-                    )));
-                });
-            }
-            _ => {}
+                })
+            >
+                {label}
+                {move || match sort.get() {
+                    (c, true) if c == column => " \u{25b2}",
+                    (c, false) if c == column => " \u{25bc}",
+                    _ => "",
+                }}
+            </th>
         }
     };
 
+    let report_rows =
+        move || report.with(|r| r.iter().map(|(_, row)| row.clone()).collect::<Vec<_>>());
+    let save_csv = move |_| {
+        download_csv(
+            &format!("{}_{}.csv", ValidationRow::TABLE, selected_type.get()),
+            &export::to_csv(&report_rows(), &csv_options()),
+        )
+    };
+    let save_json = move |_| {
+        download_file(
+            &format!("{}_{}.json", ValidationRow::TABLE, selected_type.get()),
+            &export::to_json(&report_rows()),
+            "application/json;charset=utf-8;",
+        )
+    };
+
     view! {
         <div class="validator-tab">
             <div class="controls">
@@ -2888,6 +5432,10 @@ fn ValidatorTab() -> impl IntoView {
                         let t = event_target_value(&ev);
                         selected_type.set(t.clone());
                         result.set(None);
+                        readings.set(Vec::new());
+                        checks.set(Vec::new());
+                        checked_iban.set(String::new());
+                        report.set(Vec::new());
                         match t.as_str() {
                             "id" => country.set("DE".to_string()),
                             "bank" => country.set("US".to_string()),
@@ -2895,7 +5443,8 @@ fn ValidatorTab() -> impl IntoView {
                             _ => {}
                         }
                     }>
-                        <option value="iban">"IBAN"</option>
+                        <option value="detect">"Auto-detect"</option>
+                        <option value="iban" selected>"IBAN"</option>
                         <option value="id">"Personal ID"</option>
                         <option value="bank">"Bank Account"</option>
                         <option value="card">"Credit Card"</option>
@@ -2906,55 +5455,143 @@ fn ValidatorTab() -> impl IntoView {
                         <option value="tax_id">"Tax ID"</option>
                         <option value="vat">"VAT"</option>
                         <option value="lei">"LEI"</option>
+                        <option value="creditor_id">"SEPA Creditor ID"</option>
                     </select>
                 </div>
 
-                <Show when=move || {
-                    let t = selected_type.get();
-                    t == "id" || t == "bank" || t == "company" || t == "driver_license" || t == "passport" || t == "tax_id"
-                }>
+                <Show when=move || mockbanker_core::validate::needs_country(&selected_type.get())>
                     <div class="field">
                         <label>"Country"</label>
                         {move || {
-                            let list = match selected_type.get().as_str() {
-                                "id" => id_countries.get_value(),
-                                "bank" => bank_countries.get_value(),
-                                "company" => company_countries.get_value(),
-                                "driver_license" => dl_countries.get_value(),
-                                "passport" => passport_countries.get_value(),
-                                "tax_id" => tax_countries.get_value(),
-                                _ => Vec::new(),
-                            };
+                            let kind = selected_type.get();
+                            let list = validator.with_value(|v| v.countries(&kind));
                             view! {
                                 <SearchableSelect
                                     options=list
                                     selected=country
-                                    on_change=Callback::new(move |_| result.set(None))
+                                    on_change=Callback::new(move |_| {
+                                        result.set(None);
+                                        checks.set(Vec::new());
+                                        report.set(Vec::new());
+                                    })
                                 />
                             }
                         }}
                     </div>
                 </Show>
 
-                <div class="field" style="flex: 1">
-                    <label>"Value to validate"</label>
-                    <input type="text"
-                        placeholder="Enter code here..."
-                        prop:value=move || input_value.get()
-                        on:input=move |ev| input_value.set(event_target_value(&ev))
-                        on:keydown=move |ev| {
-                            if ev.key() == "Enter" {
-                                validate(());
-                            }
-                        }
-                    />
+                <div class="field">
+                    <label>"Mode"</label>
+                    <select on:change=move |ev| bulk.set(event_target_value(&ev) == "bulk")>
+                        <option value="single" selected=move || !bulk.get()>"Single value"</option>
+                        <option value="bulk" selected=move || bulk.get()>"Bulk list"</option>
+                    </select>
                 </div>
 
-                <button class="btn btn-primary" on:click=move |_| validate(())>"Validate"</button>
+                <Show when=move || !bulk.get()>
+                    <div class="field" style="flex: 1">
+                        <label>"Value to validate"</label>
+                        <input type="text"
+                            placeholder="Enter code here..."
+                            prop:value=move || input_value.get()
+                            on:input=move |ev| input_value.set(event_target_value(&ev))
+                            on:keydown=move |ev| {
+                                if ev.key() == "Enter" {
+                                    validate(());
+                                }
+                            }
+                        />
+                    </div>
+
+                    <button class="btn btn-primary" on:click=move |_| validate(())>"Validate"</button>
+                </Show>
+
+                <Show when=move || bulk.get()>
+                    <div class="field">
+                        <label>
+                            "Upload CSV/TXT "
+                            <Tooltip text="One value per line; only the first column of a CSV is checked".to_string() />
+                        </label>
+                        <input type="file" accept=".csv,.txt,text/csv,text/plain" on:change=upload />
+                    </div>
+
+                    <button class="btn btn-primary" on:click=move |_| validate_bulk()>"Validate all"</button>
+
+                    <Show when=move || !report.with(|r| r.is_empty())>
+                        <button class="btn btn-secondary" on:click=save_csv>"CSV"</button>
+                        <CsvOptionsMenu />
+                        <button class="btn btn-secondary" on:click=save_json>"JSON"</button>
+                    </Show>
+                </Show>
             </div>
 
+            <Show when=move || bulk.get()>
+                <textarea
+                    class="bulk-input"
+                    placeholder="Paste one value per line..."
+                    prop:value=move || bulk_text.get()
+                    on:input=move |ev| bulk_text.set(event_target_value(&ev))
+                ></textarea>
+
+                <Show when=move || !report.with(|r| r.is_empty())>
+                    <div class="results-header">
+                        <span>{move || {
+                            let (total, valid) = report.with(|r| {
+                                (r.len(), r.iter().filter(|(_, row)| row.valid).count())
+                            });
+                            format!(
+                                "{} values \u{00b7} {} valid \u{00b7} {} invalid",
+                                total,
+                                valid,
+                                total - valid
+                            )
+                        }}</span>
+                    </div>
+                    <table>
+                        <thead>
+                            <tr>
+                                {sort_header("line", "#")}
+                                {sort_header("value", "Value")}
+                                {sort_header("valid", "Valid")}
+                                {sort_header("reason", "Reason")}
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || {
+                                let cidx = copied_idx.get();
+                                sorted.get().into_iter().map(|(line, row)| {
+                                    let copy_text = row.value.clone();
+                                    let valid_class = if row.valid { "valid-yes" } else { "valid-no" };
+                                    let is_copied = cidx == Some(line);
+                                    view! {
+                                        <tr>
+                                            <td>{line}</td>
+                                            <td>{row.value}</td>
+                                            <td class={valid_class}>{if row.valid { "Yes" } else { "No" }}</td>
+                                            <td class="reason">{row.reason}</td>
+                                            <td>
+                                                <button
+                                                    class=if is_copied { "btn-copy copied" } else { "btn-copy" }
+                                                    on:click=move |_| {
+                                                        copy_to_clipboard(&copy_text);
+                                                        copied_idx.set(Some(line));
+                                                    }
+                                                >
+                                                    {if is_copied { "Copied!" } else { "Copy" }}
+                                                </button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()
+                            }}
+                        </tbody>
+                    </table>
+                </Show>
+            </Show>
+
             <div class="validator-result">
-                {move || result.get().map(|(valid, msg)| {
+                {move || result.get().filter(|_| !bulk.get()).map(|(valid, msg)| {
                     let class = if valid { "result-valid" } else { "result-invalid" };
                     view! {
                         <div class=format!("result-box {}", class)>
@@ -2964,6 +5601,51 @@ fn ValidatorTab() -> impl IntoView {
                     }
                 })}
             </div>
+
+            <Show when=move || !bulk.get() && !checked_iban.with(String::is_empty)>
+                <div class="iban-breakdown">
+                    {move || view! { <IbanBreakdown code=checked_iban.get() spaces=true /> }}
+                    <dl>
+                        {move || anatomy::segments(&checked_iban.get()).into_iter().map(|(role, text)| view! {
+                            <dt class=format!("iban-part iban-{}", role.key())>{role.label()}</dt>
+                            <dd>{text}</dd>
+                        }).collect_view()}
+                    </dl>
+                </div>
+            </Show>
+
+            <Show when=move || !bulk.get() && checks.with(|c| c.len() > 1)>
+                <ul class="checklist">
+                    {move || checks.get().into_iter().map(|check| {
+                        let class = if check.passed { "check-pass" } else { "check-fail" };
+                        view! {
+                            <li class=class>
+                                <span class="check-mark">{if check.passed { "\u{2713}" } else { "\u{2717}" }}</span>
+                                <strong>{check.label}</strong>
+                                <span class="check-detail">{check.detail}</span>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </Show>
+
+            <Show when=move || !bulk.get() && readings.with(|r| r.len() > 1)>
+                <ol class="readings">
+                    {move || readings.get().into_iter().map(|reading| {
+                        view! {
+                            <li>
+                                <span>{reading.description}</span>
+                                <span class="reading-kind">
+                                    {mockbanker_core::validate::KINDS
+                                        .iter()
+                                        .find(|(key, _)| *key == reading.kind)
+                                        .map_or("", |(_, label)| *label)}
+                                </span>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ol>
+            </Show>
         </div>
     }
 }
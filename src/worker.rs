@@ -0,0 +1,67 @@
+//! Background worker for large runs. Once loaded it says it is ready and
+//! the page posts a [`bulk::Job`] as JSON; the worker generates it chunk by
+//! chunk, reports progress after each chunk and posts the finished file
+//! back as a Blob. Cancelling terminates the worker.
+
+use mockbanker_core::bulk;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+const parts = [];
+
+export function on_job(callback) {
+    self.onmessage = (e) => callback(e.data);
+    self.postMessage({ ready: true });
+}
+
+export function append(text) {
+    parts.push(text);
+}
+
+export function progress(done, total) {
+    self.postMessage({ progress: [done, total] });
+}
+
+export function finish(filename, mimeType) {
+    const blob = new Blob(parts, { type: mimeType });
+    parts.length = 0;
+    self.postMessage({ filename, blob });
+}
+
+export function fail(message) {
+    parts.length = 0;
+    self.postMessage({ error: message });
+}
+"#)]
+extern "C" {
+    fn on_job(callback: &js_sys::Function);
+    fn append(text: &str);
+    fn progress(done: u32, total: u32);
+    fn finish(filename: &str, mimeType: &str);
+    fn fail(message: &str);
+}
+
+fn run(job: String) -> Result<(), String> {
+    let job: bulk::Job = serde_json::from_str(&job).map_err(|e| e.to_string())?;
+    let mut run = bulk::Run::new(job)?;
+    while let Some(text) = run.next_chunk()? {
+        append(&text);
+        progress(run.done(), run.total());
+    }
+    append(&run.finish());
+    finish(&run.file_name(), run.mime_type());
+    Ok(())
+}
+
+fn main() {
+    let callback = Closure::<dyn Fn(String)>::new(|job: String| {
+        if let Err(message) = run(job) {
+            fail(&message);
+        }
+    });
+    on_job(callback.as_ref().unchecked_ref());
+    // The handler serves every message for the worker's whole life, and the
+    // page terminates the worker once its run ends.
+    callback.forget();
+}